@@ -0,0 +1,9 @@
+// generates include/alps.h from the exported functions, see cbindgen.toml
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::generate(&crate_dir)
+        .expect("unable to generate the C header")
+        .write_to_file(format!("{}/include/alps.h", crate_dir));
+}
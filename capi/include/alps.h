@@ -0,0 +1,197 @@
+/* Generated by cbindgen from capi/src/lib.rs, do not edit. */
+
+#ifndef ALPS_H
+#define ALPS_H
+
+
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ The outcome of a call.
+ */
+typedef enum AlpsStatus {
+  ALPS_STATUS_OK = 0,
+  /*
+   The input is not a valid problem document
+   */
+  ALPS_STATUS_INVALID_INPUT = 1,
+  /*
+   An expression or constraint could not be parsed
+   */
+  ALPS_STATUS_PARSE = 2,
+  /*
+   The problem is inconsistent
+   */
+  ALPS_STATUS_INVALID_MODEL = 3,
+  /*
+   No assignment satisfies all the constraints
+   */
+  ALPS_STATUS_INFEASIBLE = 4,
+  /*
+   The objective can be improved without limit
+   */
+  ALPS_STATUS_UNBOUNDED = 5,
+  /*
+   Any other failure of the solver
+   */
+  ALPS_STATUS_SOLVER = 6,
+  /*
+   No variable or constraint has the requested name
+   */
+  ALPS_STATUS_NOT_FOUND = 7,
+  /*
+   A required pointer was null
+   */
+  ALPS_STATUS_NULL_ARGUMENT = 8,
+  /*
+   A limit or cancellation stopped the solve before it found a solution
+   */
+  ALPS_STATUS_STOPPED = 9,
+} AlpsStatus;
+
+/*
+ A problem ready to be solved.
+ */
+typedef struct AlpsProblem AlpsProblem;
+
+/*
+ The solution of a problem.
+ */
+typedef struct AlpsSolution AlpsSolution;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ The version of the library, as a static string.
+ */
+const char *alps_version(void);
+
+/*
+ A short name for a status, e.g. `"infeasible"`, as a static string.
+ Takes the status as an `int` so any value is safe to pass, values that
+ are no status get `"unknown"`.
+ */
+const char *alps_status_name(int status);
+
+/*
+ The message of the last failed call on this thread, or null.
+
+ The string stays valid until the next failing call on the same thread.
+ */
+const char *alps_last_error(void);
+
+/*
+ Reads a problem from the JSON input format.
+
+ # Safety
+
+ `json` must be a nul terminated string and `problem` must point to
+ writable memory, on success it receives a problem to release with
+ [`alps_problem_free`].
+ */
+enum AlpsStatus alps_problem_from_json(const char *json, struct AlpsProblem **problem);
+
+/*
+ Reads a problem from the `.alps` text format.
+
+ # Safety
+
+ Same as [`alps_problem_from_json`].
+ */
+enum AlpsStatus alps_problem_from_alps(const char *source, struct AlpsProblem **problem);
+
+/*
+ Releases a problem, null is ignored.
+
+ # Safety
+
+ `problem` must come from this library and not be used afterwards.
+ */
+void alps_problem_free(struct AlpsProblem *problem);
+
+/*
+ Solves a problem, the status tells whether it was optimal, infeasible,
+ unbounded or could not be read.
+
+ # Safety
+
+ `problem` must be a live problem and `solution` must point to writable
+ memory, on success it receives a solution to release with
+ [`alps_solution_free`].
+ */
+enum AlpsStatus alps_solve(const struct AlpsProblem *problem, struct AlpsSolution **solution);
+
+/*
+ The optimal objective value, NaN when `solution` is null.
+
+ # Safety
+
+ `solution` must be null or a live solution.
+ */
+double alps_solution_objective(const struct AlpsSolution *solution);
+
+/*
+ Writes the value of the variable called `name` to `value`.
+
+ # Safety
+
+ `solution` must be a live solution, `name` a nul terminated string and
+ `value` must point to writable memory.
+ */
+enum AlpsStatus alps_solution_variable(const struct AlpsSolution *solution,
+                                       const char *name,
+                                       double *value);
+
+/*
+ Writes the activity of the constraint called `name`, the value of its
+ left and right hand side at the optimum. Either output may be null.
+
+ # Safety
+
+ `solution` must be a live solution, `name` a nul terminated string and
+ `lhs` and `rhs` null or pointing to writable memory.
+ */
+enum AlpsStatus alps_solution_constraint(const struct AlpsSolution *solution,
+                                         const char *name,
+                                         double *lhs,
+                                         double *rhs);
+
+/*
+ The solution in the JSON output format of the CLI, or null when
+ `solution` is null. Release it with [`alps_string_free`].
+
+ # Safety
+
+ `solution` must be null or a live solution.
+ */
+char *alps_solution_to_json(const struct AlpsSolution *solution);
+
+/*
+ Releases a solution, null is ignored.
+
+ # Safety
+
+ `solution` must come from this library and not be used afterwards.
+ */
+void alps_solution_free(struct AlpsSolution *solution);
+
+/*
+ Releases a string returned by this library, null is ignored.
+
+ # Safety
+
+ `string` must come from this library and not be used afterwards.
+ */
+void alps_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ALPS_H */
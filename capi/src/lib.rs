@@ -0,0 +1,344 @@
+//! C bindings for alps, the header is generated into `include/alps.h` on
+//! every build:
+//!
+//! ```bash
+//! cargo build --package alps-capi
+//! cc capi/tests/alps_test.c -Icapi/include -Ltarget/debug -lalps_c -o target/alps_test
+//! LD_LIBRARY_PATH=target/debug target/alps_test
+//! ```
+//!
+//! Every function returns an [`AlpsStatus`], the message of the last failure
+//! on the calling thread is available from [`alps_last_error`]. Objects are
+//! created by the library and must be released with the matching `_free`.
+
+use alps_core::{parse_alps, AlpsError, SolutionResponse, UnoptimizedProblem};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// The outcome of a call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlpsStatus {
+    Ok = 0,
+    /// The input is not a valid problem document
+    InvalidInput = 1,
+    /// An expression or constraint could not be parsed
+    Parse = 2,
+    /// The problem is inconsistent
+    InvalidModel = 3,
+    /// No assignment satisfies all the constraints
+    Infeasible = 4,
+    /// The objective can be improved without limit
+    Unbounded = 5,
+    /// Any other failure of the solver
+    Solver = 6,
+    /// No variable or constraint has the requested name
+    NotFound = 7,
+    /// A required pointer was null
+    NullArgument = 8,
+    /// A limit or cancellation stopped the solve before it found a solution
+    Stopped = 9,
+}
+
+/// A problem ready to be solved.
+pub struct AlpsProblem {
+    inner: UnoptimizedProblem,
+}
+
+/// The solution of a problem.
+pub struct AlpsSolution {
+    inner: SolutionResponse,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+type Failure = (AlpsStatus, String);
+
+fn from_error(error: AlpsError) -> Failure {
+    let status = match error {
+        AlpsError::InvalidInput(_) => AlpsStatus::InvalidInput,
+        AlpsError::Parse(_) => AlpsStatus::Parse,
+        AlpsError::InvalidModel(_) => AlpsStatus::InvalidModel,
+        AlpsError::Infeasible => AlpsStatus::Infeasible,
+        AlpsError::Unbounded => AlpsStatus::Unbounded,
+        AlpsError::Solver(_) => AlpsStatus::Solver,
+        AlpsError::Stopped(_) => AlpsStatus::Stopped,
+    };
+    (status, error.to_string())
+}
+
+// runs the body of an exported function, recording the error message and
+// making sure a panic never unwinds into C
+fn run(body: impl FnOnce() -> Result<(), Failure>) -> AlpsStatus {
+    let result = catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|_| Err((AlpsStatus::Solver, "alps panicked".to_string())));
+    let (status, message) = match result {
+        Ok(()) => return AlpsStatus::Ok,
+        Err(failure) => failure,
+    };
+    // interior nul bytes can't be represented in a C string
+    let message = CString::new(message.replace('\0', " ")).unwrap();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+// borrows a C string argument, refusing null and invalid UTF-8
+unsafe fn read_str<'a>(pointer: *const c_char, argument: &str) -> Result<&'a str, Failure> {
+    if pointer.is_null() {
+        return Err(null_argument(argument));
+    }
+    CStr::from_ptr(pointer).to_str().map_err(|_| {
+        (
+            AlpsStatus::InvalidInput,
+            format!("`{}` is not valid UTF-8", argument),
+        )
+    })
+}
+
+fn null_argument(argument: &str) -> Failure {
+    (
+        AlpsStatus::NullArgument,
+        format!("`{}` must not be null", argument),
+    )
+}
+
+unsafe fn read_problem(
+    text: *const c_char,
+    problem: *mut *mut AlpsProblem,
+    parse: impl FnOnce(&str) -> Result<UnoptimizedProblem, AlpsError>,
+) -> AlpsStatus {
+    run(|| {
+        if problem.is_null() {
+            return Err(null_argument("problem"));
+        }
+        let inner = parse(read_str(text, "text")?).map_err(from_error)?;
+        *problem = Box::into_raw(Box::new(AlpsProblem { inner }));
+        Ok(())
+    })
+}
+
+/// The version of the library, as a static string.
+#[no_mangle]
+pub extern "C" fn alps_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// A short name for a status, e.g. `"infeasible"`, as a static string.
+/// Takes the status as an `int` so any value is safe to pass, values that
+/// are no status get `"unknown"`.
+#[no_mangle]
+pub extern "C" fn alps_status_name(status: c_int) -> *const c_char {
+    const OK: c_int = AlpsStatus::Ok as c_int;
+    const INVALID_INPUT: c_int = AlpsStatus::InvalidInput as c_int;
+    const PARSE: c_int = AlpsStatus::Parse as c_int;
+    const INVALID_MODEL: c_int = AlpsStatus::InvalidModel as c_int;
+    const INFEASIBLE: c_int = AlpsStatus::Infeasible as c_int;
+    const UNBOUNDED: c_int = AlpsStatus::Unbounded as c_int;
+    const SOLVER: c_int = AlpsStatus::Solver as c_int;
+    const NOT_FOUND: c_int = AlpsStatus::NotFound as c_int;
+    const NULL_ARGUMENT: c_int = AlpsStatus::NullArgument as c_int;
+    const STOPPED: c_int = AlpsStatus::Stopped as c_int;
+    let name: &'static str = match status {
+        OK => "ok\0",
+        INVALID_INPUT => "invalid_input\0",
+        PARSE => "parse\0",
+        INVALID_MODEL => "invalid_model\0",
+        INFEASIBLE => "infeasible\0",
+        UNBOUNDED => "unbounded\0",
+        SOLVER => "solver\0",
+        NOT_FOUND => "not_found\0",
+        NULL_ARGUMENT => "null_argument\0",
+        STOPPED => "stopped\0",
+        _ => "unknown\0",
+    };
+    name.as_ptr().cast()
+}
+
+/// The message of the last failed call on this thread, or null.
+///
+/// The string stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn alps_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Reads a problem from the JSON input format.
+///
+/// # Safety
+///
+/// `json` must be a nul terminated string and `problem` must point to
+/// writable memory, on success it receives a problem to release with
+/// [`alps_problem_free`].
+#[no_mangle]
+pub unsafe extern "C" fn alps_problem_from_json(
+    json: *const c_char,
+    problem: *mut *mut AlpsProblem,
+) -> AlpsStatus {
+    read_problem(json, problem, |json| Ok(serde_json::from_str(json)?))
+}
+
+/// Reads a problem from the `.alps` text format.
+///
+/// # Safety
+///
+/// Same as [`alps_problem_from_json`].
+#[no_mangle]
+pub unsafe extern "C" fn alps_problem_from_alps(
+    source: *const c_char,
+    problem: *mut *mut AlpsProblem,
+) -> AlpsStatus {
+    read_problem(source, problem, parse_alps)
+}
+
+/// Releases a problem, null is ignored.
+///
+/// # Safety
+///
+/// `problem` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn alps_problem_free(problem: *mut AlpsProblem) {
+    if !problem.is_null() {
+        drop(Box::from_raw(problem));
+    }
+}
+
+/// Solves a problem, the status tells whether it was optimal, infeasible,
+/// unbounded or could not be read.
+///
+/// # Safety
+///
+/// `problem` must be a live problem and `solution` must point to writable
+/// memory, on success it receives a solution to release with
+/// [`alps_solution_free`].
+#[no_mangle]
+pub unsafe extern "C" fn alps_solve(
+    problem: *const AlpsProblem,
+    solution: *mut *mut AlpsSolution,
+) -> AlpsStatus {
+    run(|| {
+        let problem = problem.as_ref().ok_or_else(|| null_argument("problem"))?;
+        if solution.is_null() {
+            return Err(null_argument("solution"));
+        }
+        let inner = alps_core::solve(problem.inner.clone()).map_err(from_error)?;
+        *solution = Box::into_raw(Box::new(AlpsSolution { inner }));
+        Ok(())
+    })
+}
+
+/// The optimal objective value, NaN when `solution` is null.
+///
+/// # Safety
+///
+/// `solution` must be null or a live solution.
+#[no_mangle]
+pub unsafe extern "C" fn alps_solution_objective(solution: *const AlpsSolution) -> f64 {
+    solution
+        .as_ref()
+        .map_or(f64::NAN, |solution| solution.inner.objective)
+}
+
+/// Writes the value of the variable called `name` to `value`.
+///
+/// # Safety
+///
+/// `solution` must be a live solution, `name` a nul terminated string and
+/// `value` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn alps_solution_variable(
+    solution: *const AlpsSolution,
+    name: *const c_char,
+    value: *mut f64,
+) -> AlpsStatus {
+    run(|| {
+        let solution = solution.as_ref().ok_or_else(|| null_argument("solution"))?;
+        let name = read_str(name, "name")?;
+        let value = value.as_mut().ok_or_else(|| null_argument("value"))?;
+        *value = *solution
+            .inner
+            .variable_solutions
+            .get(name)
+            .ok_or_else(|| (AlpsStatus::NotFound, format!("no variable `{}`", name)))?;
+        Ok(())
+    })
+}
+
+/// Writes the activity of the constraint called `name`, the value of its
+/// left and right hand side at the optimum. Either output may be null.
+///
+/// # Safety
+///
+/// `solution` must be a live solution, `name` a nul terminated string and
+/// `lhs` and `rhs` null or pointing to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn alps_solution_constraint(
+    solution: *const AlpsSolution,
+    name: *const c_char,
+    lhs: *mut f64,
+    rhs: *mut f64,
+) -> AlpsStatus {
+    run(|| {
+        let solution = solution.as_ref().ok_or_else(|| null_argument("solution"))?;
+        let name = read_str(name, "name")?;
+        let constraint = solution
+            .inner
+            .const_values
+            .iter()
+            .find(|constraint| constraint.name == name)
+            .ok_or_else(|| (AlpsStatus::NotFound, format!("no constraint `{}`", name)))?;
+        if let Some(lhs) = lhs.as_mut() {
+            *lhs = constraint.lhs;
+        }
+        if let Some(rhs) = rhs.as_mut() {
+            *rhs = constraint.rhs;
+        }
+        Ok(())
+    })
+}
+
+/// The solution in the JSON output format of the CLI, or null when
+/// `solution` is null. Release it with [`alps_string_free`].
+///
+/// # Safety
+///
+/// `solution` must be null or a live solution.
+#[no_mangle]
+pub unsafe extern "C" fn alps_solution_to_json(solution: *const AlpsSolution) -> *mut c_char {
+    match solution.as_ref() {
+        Some(solution) => {
+            CString::new(solution.inner.to_string()).map_or(std::ptr::null_mut(), CString::into_raw)
+        }
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Releases a solution, null is ignored.
+///
+/// # Safety
+///
+/// `solution` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn alps_solution_free(solution: *mut AlpsSolution) {
+    if !solution.is_null() {
+        drop(Box::from_raw(solution));
+    }
+}
+
+/// Releases a string returned by this library, null is ignored.
+///
+/// # Safety
+///
+/// `string` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn alps_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
@@ -0,0 +1,99 @@
+/* Exercises the C API, see the top of capi/src/lib.rs for how to build it. */
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "alps.h"
+
+/* unlike assert, the condition is evaluated even with NDEBUG */
+#define CHECK(condition)                                                   \
+    do {                                                                   \
+        if (!(condition)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                           \
+            exit(1);                                                       \
+        }                                                                  \
+    } while (0)
+
+static const char *BAKERY_JSON =
+    "{\"variables\": {\"bagels\": {}, \"doughnuts\": {}},"
+    " \"objective\": {\"goal\": \"max\", \"expression\": \"3 * bagels + 1.25 * doughnuts\"},"
+    " \"constraints\": ["
+    "  {\"name\": \"flour\", \"expression\": \"12 * bagels + 6.5 * doughnuts <= 400\"},"
+    "  {\"name\": \"milk\", \"expression\": \"bagels + 0.5 * doughnuts <= 200\"},"
+    "  {\"name\": \"sugar\", \"expression\": \"2 * bagels + 0.25 * doughnuts <= 200\"},"
+    "  {\"name\": \"bagel_min\", \"expression\": \"bagels >= 12\"},"
+    "  {\"name\": \"doughnut_min\", \"expression\": \"doughnuts >= 14\"}]}";
+
+static const char *BAKERY_ALPS =
+    "max: 3 * bagels + 1.25 * doughnuts;\n"
+    "flour: 12 * bagels + 6.5 * doughnuts <= 400;\n"
+    "milk: bagels + 0.5 * doughnuts <= 200;\n"
+    "sugar: 2 * bagels + 0.25 * doughnuts <= 200;\n"
+    "bagels >= 12;\n"
+    "doughnuts >= 14;\n";
+
+static int close_to(double a, double b) { return fabs(a - b) < 1e-6; }
+
+static void check_bakery(AlpsProblem *problem) {
+    AlpsSolution *solution = NULL;
+    CHECK(alps_solve(problem, &solution) == ALPS_STATUS_OK);
+    CHECK(close_to(alps_solution_objective(solution), 94.75));
+
+    double bagels = 0, doughnuts = 0;
+    CHECK(alps_solution_variable(solution, "bagels", &bagels) == ALPS_STATUS_OK);
+    CHECK(alps_solution_variable(solution, "doughnuts", &doughnuts) == ALPS_STATUS_OK);
+    CHECK(close_to(3 * bagels + 1.25 * doughnuts, 94.75));
+    CHECK(alps_solution_variable(solution, "croissants", &bagels) == ALPS_STATUS_NOT_FOUND);
+    CHECK(strstr(alps_last_error(), "croissants") != NULL);
+
+    double lhs = 0, rhs = 0;
+    CHECK(alps_solution_constraint(solution, "flour", &lhs, &rhs) == ALPS_STATUS_OK);
+    CHECK(close_to(lhs, 12 * bagels + 6.5 * doughnuts));
+    CHECK(close_to(rhs, 400));
+    CHECK(alps_solution_constraint(solution, "milk", NULL, &rhs) == ALPS_STATUS_OK);
+    CHECK(close_to(rhs, 200));
+
+    char *json = alps_solution_to_json(solution);
+    CHECK(json != NULL && strstr(json, "\"objective\"") != NULL);
+    alps_string_free(json);
+    alps_solution_free(solution);
+}
+
+int main(void) {
+    AlpsProblem *problem = NULL;
+
+    CHECK(alps_problem_from_json(BAKERY_JSON, &problem) == ALPS_STATUS_OK);
+    check_bakery(problem);
+    alps_problem_free(problem);
+
+    CHECK(alps_problem_from_alps(BAKERY_ALPS, &problem) == ALPS_STATUS_OK);
+    check_bakery(problem);
+    alps_problem_free(problem);
+
+    /* failures are reported through the status and alps_last_error */
+    problem = NULL;
+    CHECK(alps_problem_from_json("{", &problem) == ALPS_STATUS_INVALID_INPUT);
+    CHECK(problem == NULL);
+    CHECK(alps_problem_from_json(NULL, &problem) == ALPS_STATUS_NULL_ARGUMENT);
+
+    AlpsSolution *solution = NULL;
+    CHECK(alps_problem_from_alps("max: x; x <= 1; too_big: x >= 2;", &problem) == ALPS_STATUS_OK);
+    CHECK(alps_solve(problem, &solution) == ALPS_STATUS_INFEASIBLE);
+    CHECK(solution == NULL);
+    CHECK(strcmp(alps_status_name(ALPS_STATUS_INFEASIBLE), "infeasible") == 0);
+    CHECK(strcmp(alps_status_name(42), "unknown") == 0);
+    alps_problem_free(problem);
+
+    CHECK(alps_problem_from_alps("max: x + y; x <= 1;", &problem) == ALPS_STATUS_OK);
+    CHECK(alps_solve(problem, &solution) == ALPS_STATUS_UNBOUNDED);
+    alps_problem_free(problem);
+
+    alps_problem_free(NULL);
+    alps_solution_free(NULL);
+
+    printf("alps %s: all C API checks passed\n", alps_version());
+    return 0;
+}
@@ -0,0 +1,537 @@
+//! The `alps!` macro, re-exported as `alps::alps!`.
+
+use proc_macro2::{Delimiter, Literal, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+
+/// Writes a linear program inline, expanding at compile time into calls to
+/// the `alps::Problem` builder.
+///
+/// The syntax follows the `.alps` text format: `max:` or `min:` sets the
+/// objective, `name: lhs <= rhs;` adds a named constraint and an unnamed
+/// `variable >= number;` sets a bound. Any other unnamed constraint is
+/// called `c` followed by its position. Names that aren't Rust identifiers
+/// can be written as string literals, `"GPU:0": ...`.
+///
+/// ```
+/// use alps::alps;
+///
+/// let problem = alps! {
+///     max: 3*bagels + 1.25*doughnuts;
+///     flour: 12*bagels + 6.5*doughnuts <= 400;
+///     milk: bagels + 0.5*doughnuts <= 200;
+///     sugar: 2*bagels + 0.25*doughnuts <= 200;
+///     bagels >= 12;
+///     doughnuts >= 14;
+/// };
+/// let solution = problem.solve().unwrap();
+/// assert!((solution.objective - 94.75).abs() < 1e-9);
+/// assert_eq!(problem.to_problem().constraints.len(), 3);
+/// ```
+///
+/// Anything that isn't linear is a compile error pointing at the offending
+/// tokens:
+///
+/// ```compile_fail
+/// let problem = alps::alps! {
+///     max: bagels * doughnuts;
+/// };
+/// ```
+///
+/// ```compile_fail
+/// let problem = alps::alps! {
+///     max: bagels;
+///     flour: 12*bagels / doughnuts <= 400;
+/// };
+/// ```
+///
+/// ```compile_fail
+/// let problem = alps::alps! {
+///     max: bagels;
+///     bagels > 12;
+/// };
+/// ```
+///
+/// ```compile_fail
+/// let problem = alps::alps! {
+///     max: bagels;
+///     bagels <= 1e200 * 1e200;
+/// };
+/// ```
+///
+/// ```compile_fail
+/// let problem = alps::alps! {
+///     max: bagels;
+///     bagels <= 1e400;
+/// };
+/// ```
+#[proc_macro]
+pub fn alps(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match expand(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+// a message attached to the tokens it is about
+struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    fn new(span: Span, message: impl Into<String>) -> Error {
+        Error {
+            span,
+            message: message.into(),
+        }
+    }
+
+    fn to_compile_error(&self) -> TokenStream {
+        let message = &self.message;
+        quote_spanned!(self.span=> compile_error!(#message))
+    }
+}
+
+// a linear expression with coefficients known at compile time
+#[derive(Clone, Default)]
+struct Linear {
+    terms: Vec<(usize, f64)>,
+    constant: f64,
+}
+
+impl Linear {
+    fn is_constant(&self) -> bool {
+        self.terms.iter().all(|(_, value)| *value == 0.0)
+    }
+
+    fn add(mut self, other: Linear, sign: f64) -> Linear {
+        for (var, value) in other.terms {
+            match self.terms.iter_mut().find(|(existing, _)| *existing == var) {
+                Some((_, existing)) => *existing += sign * value,
+                None => self.terms.push((var, sign * value)),
+            }
+        }
+        self.constant += sign * other.constant;
+        self
+    }
+
+    fn scale(mut self, factor: f64) -> Linear {
+        for (_, value) in &mut self.terms {
+            *value *= factor;
+        }
+        self.constant *= factor;
+        self
+    }
+
+    // errors at `span` when a coefficient or the constant overflowed
+    fn finite(self, span: Span) -> Result<Linear, Error> {
+        let mut values = self.terms.iter().map(|(_, value)| *value);
+        if values.all(f64::is_finite) && self.constant.is_finite() {
+            Ok(self)
+        } else {
+            Err(Error::new(span, "the result is not a finite number"))
+        }
+    }
+
+    // the single variable of `x`, if this is exactly one variable
+    fn single_variable(&self) -> Option<usize> {
+        match self.terms.as_slice() {
+            [(var, value)] if *value == 1.0 && self.constant == 0.0 => Some(*var),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Sense {
+    Le,
+    Ge,
+    Eq,
+}
+
+#[derive(Default)]
+struct Model {
+    variables: Vec<String>,
+    bounds: Vec<(Option<f64>, Option<f64>)>,
+    objective: Option<(bool, Linear)>,
+    constraints: Vec<(String, Linear, Sense, Linear)>,
+}
+
+impl Model {
+    fn variable(&mut self, name: String) -> usize {
+        match self.variables.iter().position(|existing| *existing == name) {
+            Some(index) => index,
+            None => {
+                self.variables.push(name);
+                self.bounds.push((None, None));
+                self.variables.len() - 1
+            }
+        }
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut model = Model::default();
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    for statement in tokens.split(|token| is_punct(token, ';')) {
+        if !statement.is_empty() {
+            parse_statement(&mut model, statement)?;
+        }
+    }
+    Ok(generate(&model))
+}
+
+fn is_punct(token: &TokenTree, char: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == char)
+}
+
+fn parse_statement(model: &mut Model, statement: &[TokenTree]) -> Result<(), Error> {
+    // `label:` but not the `::` of a path
+    let (label, body) = match statement {
+        [label @ (TokenTree::Ident(_) | TokenTree::Literal(_)), TokenTree::Punct(colon), body @ ..]
+            if colon.as_char() == ':' && colon.spacing() == Spacing::Alone =>
+        {
+            (Some((name(label)?, label.span())), body)
+        }
+        _ => (None, statement),
+    };
+    if body.is_empty() {
+        return Err(Error::new(
+            statement[0].span(),
+            "the statement has no expression",
+        ));
+    }
+
+    let Some((position, width, sense)) = find_comparison(body)? else {
+        let Some((label, span)) = label else {
+            return Err(Error::new(
+                body[0].span(),
+                "expected an objective (`max: ...`) or a comparison (`<=`, `>=` or `==`)",
+            ));
+        };
+        let maximise = match label.to_lowercase().as_str() {
+            "max" | "maximize" | "maximise" => true,
+            "min" | "minimize" | "minimise" => false,
+            _ => {
+                return Err(Error::new(
+                    span,
+                    format!("`{}` is not `max` or `min` and has no comparison", label),
+                ))
+            }
+        };
+        if model.objective.is_some() {
+            return Err(Error::new(span, "the objective is set twice"));
+        }
+        let objective = Parser::new(model, body).parse()?;
+        model.objective = Some((maximise, objective));
+        return Ok(());
+    };
+
+    let lhs = Parser::new(model, &body[..position]).parse()?;
+    let rhs = Parser::new(model, &body[position + width..]).parse()?;
+    let name = match label {
+        Some((label, _)) => label,
+        None => {
+            // `x >= 3` and `3 <= x` are bounds
+            let bound = match (lhs.single_variable(), rhs.single_variable()) {
+                (Some(var), None) if rhs.is_constant() => Some((var, sense, rhs.constant)),
+                (None, Some(var)) if lhs.is_constant() => {
+                    let flipped = match sense {
+                        Sense::Le => Sense::Ge,
+                        Sense::Ge => Sense::Le,
+                        Sense::Eq => Sense::Eq,
+                    };
+                    Some((var, flipped, lhs.constant))
+                }
+                _ => None,
+            };
+            if let Some((var, sense, value)) = bound {
+                let (min, max) = &mut model.bounds[var];
+                match sense {
+                    Sense::Ge => *min = Some(value),
+                    Sense::Le => *max = Some(value),
+                    Sense::Eq => (*min, *max) = (Some(value), Some(value)),
+                }
+                return Ok(());
+            }
+            format!("c{}", model.constraints.len() + 1)
+        }
+    };
+    model.constraints.push((name, lhs, sense, rhs));
+    Ok(())
+}
+
+fn name(token: &TokenTree) -> Result<String, Error> {
+    match token {
+        TokenTree::Ident(ident) => Ok(ident.to_string()),
+        TokenTree::Literal(literal) => {
+            let text = literal.to_string();
+            match text
+                .strip_prefix('"')
+                .and_then(|text| text.strip_suffix('"'))
+            {
+                Some(name) if !name.contains('\\') => Ok(name.to_string()),
+                _ => Err(Error::new(
+                    token.span(),
+                    "names must be identifiers or plain string literals",
+                )),
+            }
+        }
+        _ => Err(Error::new(token.span(), "expected a name")),
+    }
+}
+
+// finds the single `<=`, `>=` or `==` of a constraint, returning its
+// position, its width in tokens and its sense
+fn find_comparison(body: &[TokenTree]) -> Result<Option<(usize, usize, Sense)>, Error> {
+    let mut found = None;
+    let mut index = 0;
+    while index < body.len() {
+        let TokenTree::Punct(punct) = &body[index] else {
+            index += 1;
+            continue;
+        };
+        let first = punct.as_char();
+        if !matches!(first, '<' | '>' | '=') {
+            index += 1;
+            continue;
+        }
+        let joined_equals = punct.spacing() == Spacing::Joint
+            && body.get(index + 1).is_some_and(|next| is_punct(next, '='));
+        let sense = match (first, joined_equals) {
+            ('<', true) => Sense::Le,
+            ('>', true) => Sense::Ge,
+            ('=', true) => Sense::Eq,
+            ('=', false) => {
+                return Err(Error::new(
+                    punct.span(),
+                    "use `==` for equality constraints",
+                ))
+            }
+            _ => {
+                return Err(Error::new(
+                    punct.span(),
+                    "strict inequalities are not supported, use `<=` or `>=`",
+                ))
+            }
+        };
+        if found.is_some() {
+            return Err(Error::new(
+                punct.span(),
+                "a constraint can only have one comparison",
+            ));
+        }
+        found = Some((index, 2, sense));
+        index += 2;
+    }
+    Ok(found)
+}
+
+// recursive descent over `+ - * /`, unary minus and parentheses, keeping
+// every intermediate result linear
+struct Parser<'a> {
+    model: &'a mut Model,
+    tokens: &'a [TokenTree],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(model: &'a mut Model, tokens: &'a [TokenTree]) -> Parser<'a> {
+        Parser {
+            model,
+            tokens,
+            position: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<Linear, Error> {
+        let expression = self.sum()?;
+        match self.tokens.get(self.position) {
+            Some(token) => Err(Error::new(token.span(), "expected `+`, `-`, `*` or `/`")),
+            None => Ok(expression),
+        }
+    }
+
+    fn peek_operator(&self) -> Option<(char, Span)> {
+        match self.tokens.get(self.position) {
+            Some(TokenTree::Punct(punct)) => Some((punct.as_char(), punct.span())),
+            _ => None,
+        }
+    }
+
+    fn sum(&mut self) -> Result<Linear, Error> {
+        let mut left = self.product()?;
+        while let Some((operator @ ('+' | '-'), span)) = self.peek_operator() {
+            self.position += 1;
+            let right = self.product()?;
+            left = left
+                .add(right, if operator == '+' { 1.0 } else { -1.0 })
+                .finite(span)?;
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Linear, Error> {
+        let mut left = self.unary()?;
+        while let Some((operator @ ('*' | '/'), span)) = self.peek_operator() {
+            self.position += 1;
+            let right = self.unary()?;
+            left = if operator == '*' {
+                match (left.is_constant(), right.is_constant()) {
+                    (true, _) => right.scale(left.constant),
+                    (_, true) => left.scale(right.constant),
+                    _ => {
+                        return Err(Error::new(
+                            span,
+                            "the product of two variables is not linear",
+                        ))
+                    }
+                }
+            } else if !right.is_constant() {
+                return Err(Error::new(span, "dividing by a variable is not linear"));
+            } else if right.constant == 0.0 {
+                return Err(Error::new(span, "division by zero"));
+            } else {
+                left.scale(1.0 / right.constant)
+            }
+            .finite(span)?;
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Linear, Error> {
+        match self.peek_operator() {
+            Some(('-', _)) => {
+                self.position += 1;
+                Ok(self.unary()?.scale(-1.0))
+            }
+            Some(('+', _)) => {
+                self.position += 1;
+                self.unary()
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Linear, Error> {
+        let Some(token) = self.tokens.get(self.position) else {
+            let span = self
+                .tokens
+                .last()
+                .map_or(Span::call_site(), |token| token.span());
+            return Err(Error::new(span, "expected a number, a variable or `(`"));
+        };
+        self.position += 1;
+        match token {
+            TokenTree::Ident(ident) => {
+                let var = self.model.variable(ident.to_string());
+                Ok(Linear {
+                    terms: vec![(var, 1.0)],
+                    constant: 0.0,
+                })
+            }
+            TokenTree::Literal(literal) => Ok(Linear {
+                terms: vec![],
+                constant: number(literal)?,
+            }),
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+                let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                Parser::new(self.model, &tokens).parse()
+            }
+            _ => Err(Error::new(
+                token.span(),
+                "expected a number, a variable or `(`",
+            )),
+        }
+    }
+}
+
+// reads an integer or float literal, suffixes and `_` separators included,
+// refusing ones too large for an f64
+fn number(literal: &Literal) -> Result<f64, Error> {
+    let text = literal.to_string().replace('_', "");
+    let digits = ["f64", "f32", "i64", "i32", "u64", "u32", "usize", "isize"]
+        .iter()
+        .find_map(|suffix| text.strip_suffix(suffix))
+        .unwrap_or(&text);
+    let value = digits
+        .parse::<f64>()
+        .map_err(|_| Error::new(literal.span(), "expected a number"))?;
+    if !value.is_finite() {
+        return Err(Error::new(literal.span(), "the number is too large"));
+    }
+    Ok(value)
+}
+
+// a float literal, negative numbers wrapped as `(-1.5)` since literals
+// themselves can't carry a sign
+fn float(value: f64) -> TokenStream {
+    let literal = Literal::f64_suffixed(value.abs());
+    if value < 0.0 {
+        quote!((-#literal))
+    } else {
+        quote!(#literal)
+    }
+}
+
+fn generate(model: &Model) -> TokenStream {
+    let problem = quote::format_ident!("problem", span = Span::mixed_site());
+    let vars = quote::format_ident!("vars", span = Span::mixed_site());
+
+    let declarations = model
+        .variables
+        .iter()
+        .zip(&model.bounds)
+        .map(|(name, (min, max))| {
+            let min = min.map(|min| {
+                let min = float(min);
+                quote!(.min(#min))
+            });
+            let max = max.map(|max| {
+                let max = float(max);
+                quote!(.max(#max))
+            });
+            quote!(#problem.var(#name) #min #max .build())
+        });
+    let expression = |linear: &Linear| {
+        let constant = float(linear.constant);
+        let terms = linear
+            .terms
+            .iter()
+            .filter(|(_, value)| *value != 0.0)
+            .map(|(var, value)| {
+                let value = float(*value);
+                quote!(+ #value * #vars[#var])
+            });
+        quote!(::alps::LinearExpr::from(#constant) #(#terms)*)
+    };
+
+    let objective = model.objective.as_ref().map(|(maximise, linear)| {
+        let expression = expression(linear);
+        if *maximise {
+            quote!(#problem.maximise(#expression);)
+        } else {
+            quote!(#problem.minimise(#expression);)
+        }
+    });
+    let constraints = model.constraints.iter().map(|(name, lhs, sense, rhs)| {
+        let lhs = expression(lhs);
+        let rhs = expression(rhs);
+        let sense = match sense {
+            Sense::Le => quote!(::alps::Sense::Le),
+            Sense::Ge => quote!(::alps::Sense::Ge),
+            Sense::Eq => quote!(::alps::Sense::Eq),
+        };
+        quote!(#problem.constraint(#name, #lhs, #sense, #rhs);)
+    });
+
+    let count = model.variables.len();
+    quote! {{
+        let mut #problem = ::alps::Problem::new();
+        #[allow(unused_variables)]
+        let #vars: [::alps::Var; #count] = [#(#declarations),*];
+        #objective
+        #(#constraints)*
+        #problem
+    }}
+}
@@ -0,0 +1,329 @@
+//! Python bindings for alps, built with maturin:
+//!
+//! ```bash
+//! cd python && maturin develop && pytest
+//! ```
+
+use alps_core::{AlpsError as CoreError, UnoptimizedProblem};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+create_exception!(
+    alps,
+    AlpsError,
+    PyException,
+    "Base class of every alps error."
+);
+create_exception!(
+    alps,
+    InvalidInputError,
+    AlpsError,
+    "The input is not a valid problem."
+);
+create_exception!(
+    alps,
+    ParseError,
+    AlpsError,
+    "An expression could not be parsed."
+);
+create_exception!(
+    alps,
+    InvalidModelError,
+    AlpsError,
+    "The problem is inconsistent."
+);
+create_exception!(
+    alps,
+    InfeasibleError,
+    AlpsError,
+    "No assignment satisfies the constraints."
+);
+create_exception!(
+    alps,
+    UnboundedError,
+    AlpsError,
+    "The objective can grow without limit."
+);
+create_exception!(
+    alps,
+    SolverError,
+    AlpsError,
+    "Any other failure of the solver."
+);
+create_exception!(
+    alps,
+    StoppedError,
+    AlpsError,
+    "A limit or cancellation stopped the solve before it found a solution."
+);
+
+// raises the python exception matching the rust error
+fn to_py_err(error: CoreError) -> PyErr {
+    let message = error.to_string();
+    match error {
+        CoreError::InvalidInput(_) => InvalidInputError::new_err(message),
+        CoreError::Parse(_) => ParseError::new_err(message),
+        CoreError::InvalidModel(_) => InvalidModelError::new_err(message),
+        CoreError::Infeasible => InfeasibleError::new_err(message),
+        CoreError::Unbounded => UnboundedError::new_err(message),
+        CoreError::Solver(_) => SolverError::new_err(message),
+        CoreError::Stopped(_) => StoppedError::new_err(message),
+    }
+}
+
+// turns any serializable value into plain python objects through json
+fn to_python<'py, T: serde::Serialize>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| to_py_err(e.into()))?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// A decision variable with optional bounds.
+#[pyclass(name = "Variable", module = "alps", get_all, set_all, eq)]
+#[derive(Clone, PartialEq)]
+struct PyVariable {
+    name: String,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[pymethods]
+impl PyVariable {
+    #[new]
+    #[pyo3(signature = (name, min = None, max = None))]
+    fn new(name: String, min: Option<f64>, max: Option<f64>) -> Self {
+        PyVariable { name, min, max }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Variable(name={:?}, min={:?}, max={:?})",
+            self.name, self.min, self.max
+        )
+    }
+}
+
+/// The goal (`"max"` or `"min"`) and expression to optimise.
+#[pyclass(name = "Objective", module = "alps", get_all, set_all, eq)]
+#[derive(Clone, PartialEq)]
+struct PyObjective {
+    goal: String,
+    expression: String,
+}
+
+#[pymethods]
+impl PyObjective {
+    #[new]
+    fn new(goal: String, expression: String) -> Self {
+        PyObjective { goal, expression }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Objective(goal={:?}, expression={:?})",
+            self.goal, self.expression
+        )
+    }
+}
+
+/// A named constraint such as `12 * bagels + 6.5 * doughnuts <= 400`.
+#[pyclass(name = "Constraint", module = "alps", get_all, set_all, eq)]
+#[derive(Clone, PartialEq)]
+struct PyConstraint {
+    name: String,
+    expression: String,
+}
+
+#[pymethods]
+impl PyConstraint {
+    #[new]
+    fn new(name: String, expression: String) -> Self {
+        PyConstraint { name, expression }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Constraint(name={:?}, expression={:?})",
+            self.name, self.expression
+        )
+    }
+}
+
+/// A problem as read from the JSON input format.
+///
+/// `variables`, `objective` and `constraints` return copies, use
+/// `add_variable`, `add_constraint` or assign the attribute to change them.
+#[pyclass(name = "UnoptimizedProblem", module = "alps")]
+#[derive(Clone)]
+struct PyProblem {
+    inner: UnoptimizedProblem,
+}
+
+#[pymethods]
+impl PyProblem {
+    #[new]
+    #[pyo3(signature = (variables = vec![], objective = None, constraints = vec![]))]
+    fn new(
+        variables: Vec<PyVariable>,
+        objective: Option<PyObjective>,
+        constraints: Vec<PyConstraint>,
+    ) -> Self {
+        let mut problem = PyProblem {
+            inner: UnoptimizedProblem::default(),
+        };
+        problem.set_variables(variables);
+        if let Some(objective) = objective {
+            problem.set_objective(objective);
+        }
+        problem.set_constraints(constraints);
+        problem
+    }
+
+    /// Reads a problem from a JSON string.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let inner = serde_json::from_str(json).map_err(|e| to_py_err(e.into()))?;
+        Ok(PyProblem { inner })
+    }
+
+    /// Reads a problem from a JSON file.
+    #[staticmethod]
+    fn from_file(path: std::path::PathBuf) -> PyResult<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// The problem in the JSON input format.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.inner).map_err(|e| to_py_err(e.into()))
+    }
+
+    #[getter]
+    fn variables(&self) -> HashMap<String, PyVariable> {
+        self.inner
+            .variables
+            .iter()
+            .map(|(key, variable)| {
+                let variable = PyVariable {
+                    name: key.clone(),
+                    min: variable.min,
+                    max: variable.max,
+                };
+                (key.clone(), variable)
+            })
+            .collect()
+    }
+
+    #[setter]
+    fn set_variables(&mut self, variables: Vec<PyVariable>) {
+        self.inner.variables.clear();
+        for variable in variables {
+            self.add_variable(variable);
+        }
+    }
+
+    #[getter]
+    fn objective(&self) -> PyObjective {
+        PyObjective {
+            goal: self.inner.objective.goal.clone(),
+            expression: self.inner.objective.expression.clone(),
+        }
+    }
+
+    #[setter]
+    fn set_objective(&mut self, objective: PyObjective) {
+        self.inner.objective.goal = objective.goal;
+        self.inner.objective.expression = objective.expression;
+    }
+
+    #[getter]
+    fn constraints(&self) -> Vec<PyConstraint> {
+        self.inner
+            .constraints
+            .iter()
+            .map(|constraint| PyConstraint {
+                name: constraint.name.clone(),
+                expression: constraint.expression.clone(),
+            })
+            .collect()
+    }
+
+    #[setter]
+    fn set_constraints(&mut self, constraints: Vec<PyConstraint>) {
+        self.inner.constraints.clear();
+        for constraint in constraints {
+            self.add_constraint(constraint);
+        }
+    }
+
+    /// Adds a variable, replacing any variable with the same name.
+    fn add_variable(&mut self, variable: PyVariable) {
+        let PyVariable { name, min, max } = variable;
+        let variable = alps_core::Variable {
+            name: name.clone(),
+            min,
+            max,
+        };
+        self.inner.variables.insert(name, variable);
+    }
+
+    fn add_constraint(&mut self, constraint: PyConstraint) {
+        self.inner.constraints.push(alps_core::Constraint {
+            name: constraint.name,
+            expression: constraint.expression,
+        });
+    }
+
+    /// Every issue the validator finds, as dicts with `pointer` and `message`.
+    fn validate<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let document = serde_json::to_value(&self.inner).map_err(|e| to_py_err(e.into()))?;
+        to_python(py, &alps_core::validate(&document))
+    }
+
+    /// Shorthand for `alps.solve(problem)`.
+    fn solve<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        solve(py, self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "UnoptimizedProblem({} variables, {} constraints)",
+            self.inner.variables.len(),
+            self.inner.constraints.len()
+        )
+    }
+}
+
+/// Solves a problem, returning the solution as a dict in the same shape as
+/// the JSON output of the CLI.
+///
+/// Raises a subclass of `AlpsError` when the problem can't be solved.
+#[pyfunction]
+fn solve<'py>(py: Python<'py>, problem: &PyProblem) -> PyResult<Bound<'py, PyAny>> {
+    let inner = problem.inner.clone();
+    // the solve doesn't touch python objects, let other threads run
+    let solution = py
+        .detach(move || alps_core::solve(inner))
+        .map_err(to_py_err)?;
+    to_python(py, &solution)
+}
+
+#[pymodule]
+fn alps(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyVariable>()?;
+    m.add_class::<PyObjective>()?;
+    m.add_class::<PyConstraint>()?;
+    m.add_class::<PyProblem>()?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add("AlpsError", py.get_type::<AlpsError>())?;
+    m.add("InvalidInputError", py.get_type::<InvalidInputError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("InvalidModelError", py.get_type::<InvalidModelError>())?;
+    m.add("InfeasibleError", py.get_type::<InfeasibleError>())?;
+    m.add("UnboundedError", py.get_type::<UnboundedError>())?;
+    m.add("SolverError", py.get_type::<SolverError>())?;
+    m.add("StoppedError", py.get_type::<StoppedError>())?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+import json
+import math
+import pathlib
+
+import pytest
+
+import alps
+
+PROBLEM_DIR = pathlib.Path(__file__).parents[2] / "problems"
+PROBLEMS = sorted(PROBLEM_DIR.glob("*.json"))
+
+
+@pytest.mark.parametrize("path", PROBLEMS, ids=lambda path: path.stem)
+def test_solve_bundled_problems(path):
+    problem = alps.UnoptimizedProblem.from_file(str(path))
+    assert problem.validate() == []
+
+    solution = alps.solve(problem)
+    assert math.isfinite(solution["objective"])
+    assert solution["num_constraints"] == len(problem.constraints)
+    assert set(solution["variable_solutions"]) == set(problem.variables)
+    for constraint in solution["const_values"]:
+        assert set(constraint) >= {"name", "expression", "lhs", "rhs"}
+
+
+def test_bakery_objective():
+    problem = alps.UnoptimizedProblem.from_file(str(PROBLEM_DIR / "bakery.json"))
+    solution = problem.solve()
+    assert solution["objective"] == pytest.approx(94.75)
+    assert solution["const_values"][0]["name"] == "flour"
+
+
+def test_build_problem_in_python():
+    problem = alps.UnoptimizedProblem(
+        variables=[alps.Variable("bagels", min=12), alps.Variable("doughnuts", min=14)],
+        objective=alps.Objective("max", "3 * bagels + 1.25 * doughnuts"),
+    )
+    problem.add_constraint(alps.Constraint("flour", "12 * bagels + 6.5 * doughnuts <= 400"))
+    assert problem.variables["bagels"] == alps.Variable("bagels", min=12.0)
+    assert problem.objective.goal == "max"
+
+    solution = alps.solve(problem)
+    assert solution["objective"] == pytest.approx(94.75)
+
+    # round trips through the JSON input format
+    again = alps.UnoptimizedProblem.from_json(problem.to_json())
+    assert json.loads(again.to_json()) == json.loads(problem.to_json())
+
+
+def test_errors_are_python_exceptions():
+    problem = alps.UnoptimizedProblem(
+        variables=[alps.Variable("x", max=1)],
+        objective=alps.Objective("max", "x + y"),
+    )
+    with pytest.raises(alps.ParseError, match="undeclared variable `y`"):
+        alps.solve(problem)
+
+    problem.objective = alps.Objective("max", "x")
+    problem.constraints = [alps.Constraint("too_big", "x >= 2")]
+    with pytest.raises(alps.InfeasibleError):
+        alps.solve(problem)
+
+    with pytest.raises(alps.InvalidInputError):
+        alps.UnoptimizedProblem.from_json("{}")
+
+    assert issubclass(alps.UnboundedError, alps.AlpsError)
+    assert issubclass(alps.StoppedError, alps.AlpsError)
+    assert issubclass(alps.AlpsError, Exception)
+
+
+def test_validate_reports_issues():
+    problem = alps.UnoptimizedProblem(
+        variables=[alps.Variable("x", min=2, max=1)],
+        objective=alps.Objective("max", "x"),
+        constraints=[alps.Constraint("a", "x <= 1"), alps.Constraint("a", "x <= 2")],
+    )
+    pointers = [issue["pointer"] for issue in problem.validate()]
+    assert pointers == ["/variables/x", "/constraints/1/name"]
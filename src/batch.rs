@@ -0,0 +1,111 @@
+use crate::{solve_catching_panics, AlpsError, SolutionResponse, SolveOptions, UnoptimizedProblem};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::sync::{mpsc, Mutex};
+
+/// The result of one line of a batch, either a solution or an error.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchItem {
+    /// 1-based line number in the input
+    pub line: usize,
+    /// The `id` field of the request, if it had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<SolutionResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<AlpsError>,
+}
+
+// solves a single request, turning every failure (including a panic inside
+// the solver) into an error on the item so the rest of the batch continues
+fn solve_line(line: usize, text: &str) -> BatchItem {
+    let mut item = BatchItem {
+        line,
+        id: None,
+        solution: None,
+        error: None,
+    };
+    let mut document: Value = match serde_json::from_str(text) {
+        Ok(document) => document,
+        Err(error) => {
+            item.error = Some(error.into());
+            return item;
+        }
+    };
+    // the id belongs to the request, the problem refuses unknown fields
+    item.id = document
+        .as_object_mut()
+        .and_then(|fields| fields.remove("id"));
+    let result = serde_json::from_value::<UnoptimizedProblem>(document)
+        .map_err(AlpsError::from)
+        .and_then(|problem| solve_catching_panics(problem, &SolveOptions::default()));
+    match result {
+        Ok(solution) => item.solution = Some(solution),
+        Err(error) => item.error = Some(error),
+    }
+    item
+}
+
+/// Solves newline-delimited problems from `input` on `threads` worker
+/// threads, writing one JSON [`BatchItem`] line per request to `output`.
+///
+/// Blank lines are skipped but still counted, so `line` always matches the
+/// input. Output is written in input order as soon as it is available.
+/// Returns the number of items that failed.
+pub fn solve_batch<R: BufRead, W: Write>(
+    input: R,
+    output: &mut W,
+    threads: usize,
+) -> std::io::Result<usize> {
+    let mut requests = vec![];
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            requests.push((index + 1, line));
+        }
+    }
+    let total = requests.len();
+    let queue = Mutex::new(requests.into_iter().enumerate());
+    let (sender, receiver) = mpsc::channel();
+
+    let mut failed = 0;
+    std::thread::scope(|scope| -> std::io::Result<()> {
+        for _ in 0..threads.max(1) {
+            let sender = sender.clone();
+            let queue = &queue;
+            scope.spawn(move || loop {
+                let next = queue.lock().unwrap().next();
+                let Some((position, (line, text))) = next else {
+                    break;
+                };
+                if sender.send((position, solve_line(line, &text))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // results arrive in any order, hold them back until the previous
+        // lines have been written
+        let mut pending = BTreeMap::new();
+        let mut written = 0;
+        for (position, item) in receiver {
+            pending.insert(position, item);
+            while let Some(item) = pending.remove(&written) {
+                if item.error.is_some() {
+                    failed += 1;
+                }
+                serde_json::to_writer(&mut *output, &item)?;
+                writeln!(output)?;
+                written += 1;
+            }
+        }
+        debug_assert_eq!(written, total);
+        output.flush()
+    })?;
+
+    Ok(failed)
+}
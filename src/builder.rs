@@ -0,0 +1,368 @@
+use crate::matrix::{Bound, MatrixProblem, Sense, SparseMatrix};
+use crate::{solve_matrix, AlpsError, SolutionResponse, UnoptimizedProblem};
+use std::collections::BTreeMap;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A handle to a variable of a [`Problem`], combined with numbers and other
+/// handles into a [`LinearExpr`] with the usual operators.
+///
+/// Handles are only meaningful for the problem that created them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Var(usize);
+
+impl Var {
+    /// The position of the variable in declaration order.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// A linear expression, `sum(coefficient * variable) + constant`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinearExpr {
+    terms: BTreeMap<usize, f64>,
+    constant: f64,
+}
+
+impl LinearExpr {
+    /// The coefficient of every variable used, in declaration order.
+    pub fn terms(&self) -> impl Iterator<Item = (Var, f64)> + '_ {
+        self.terms
+            .iter()
+            .filter(|(_, value)| **value != 0.0)
+            .map(|(index, value)| (Var(*index), *value))
+    }
+
+    pub fn constant(&self) -> f64 {
+        self.constant
+    }
+
+    fn scale(mut self, factor: f64) -> LinearExpr {
+        for value in self.terms.values_mut() {
+            *value *= factor;
+        }
+        self.constant *= factor;
+        self
+    }
+}
+
+impl From<Var> for LinearExpr {
+    fn from(var: Var) -> Self {
+        LinearExpr {
+            terms: BTreeMap::from([(var.0, 1.0)]),
+            constant: 0.0,
+        }
+    }
+}
+
+impl From<f64> for LinearExpr {
+    fn from(constant: f64) -> Self {
+        LinearExpr {
+            terms: BTreeMap::new(),
+            constant,
+        }
+    }
+}
+
+impl<T: Into<LinearExpr>> Add<T> for LinearExpr {
+    type Output = LinearExpr;
+
+    fn add(mut self, other: T) -> LinearExpr {
+        let other = other.into();
+        for (index, value) in other.terms {
+            *self.terms.entry(index).or_insert(0.0) += value;
+        }
+        self.constant += other.constant;
+        self
+    }
+}
+
+impl<T: Into<LinearExpr>> Sub<T> for LinearExpr {
+    type Output = LinearExpr;
+
+    fn sub(self, other: T) -> LinearExpr {
+        self + other.into().scale(-1.0)
+    }
+}
+
+impl Mul<f64> for LinearExpr {
+    type Output = LinearExpr;
+
+    fn mul(self, factor: f64) -> LinearExpr {
+        self.scale(factor)
+    }
+}
+
+impl Neg for LinearExpr {
+    type Output = LinearExpr;
+
+    fn neg(self) -> LinearExpr {
+        self.scale(-1.0)
+    }
+}
+
+impl std::iter::Sum for LinearExpr {
+    fn sum<I: Iterator<Item = LinearExpr>>(iter: I) -> LinearExpr {
+        iter.fold(LinearExpr::default(), |sum, expr| sum + expr)
+    }
+}
+
+impl<T: Into<LinearExpr>> Add<T> for Var {
+    type Output = LinearExpr;
+
+    fn add(self, other: T) -> LinearExpr {
+        LinearExpr::from(self) + other
+    }
+}
+
+impl<T: Into<LinearExpr>> Sub<T> for Var {
+    type Output = LinearExpr;
+
+    fn sub(self, other: T) -> LinearExpr {
+        LinearExpr::from(self) - other
+    }
+}
+
+impl Mul<f64> for Var {
+    type Output = LinearExpr;
+
+    fn mul(self, factor: f64) -> LinearExpr {
+        LinearExpr::from(self) * factor
+    }
+}
+
+impl Neg for Var {
+    type Output = LinearExpr;
+
+    fn neg(self) -> LinearExpr {
+        -LinearExpr::from(self)
+    }
+}
+
+// numbers on the left hand side, `3.0 * x`, `1.0 - x`
+macro_rules! impl_f64_ops {
+    ($($rhs:ty),*) => {$(
+        impl Mul<$rhs> for f64 {
+            type Output = LinearExpr;
+
+            fn mul(self, other: $rhs) -> LinearExpr {
+                other * self
+            }
+        }
+
+        impl Add<$rhs> for f64 {
+            type Output = LinearExpr;
+
+            fn add(self, other: $rhs) -> LinearExpr {
+                other + self
+            }
+        }
+
+        impl Sub<$rhs> for f64 {
+            type Output = LinearExpr;
+
+            fn sub(self, other: $rhs) -> LinearExpr {
+                -other + self
+            }
+        }
+    )*};
+}
+
+impl_f64_ops!(Var, LinearExpr);
+
+/// Sets the bounds of a variable being declared, see [`Problem::var`].
+pub struct VarBuilder<'a> {
+    problem: &'a mut Problem,
+    name: String,
+    bound: Bound,
+}
+
+impl VarBuilder<'_> {
+    pub fn min(mut self, min: f64) -> Self {
+        self.bound.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: f64) -> Self {
+        self.bound.max = Some(max);
+        self
+    }
+
+    /// Adds the variable to the problem and returns its handle.
+    pub fn build(self) -> Var {
+        let problem = self.problem;
+        match problem.names.iter().position(|name| *name == self.name) {
+            Some(index) => {
+                problem.bounds[index] = self.bound;
+                Var(index)
+            }
+            None => {
+                problem.names.push(self.name);
+                problem.bounds.push(self.bound);
+                Var(problem.names.len() - 1)
+            }
+        }
+    }
+}
+
+/// Builds a problem from typed variable handles instead of expression
+/// strings.
+///
+/// ```
+/// use alps::Problem;
+/// use alps::Sense::*;
+///
+/// let mut problem = Problem::new();
+/// let bagels = problem.var("bagels").min(12.0).build();
+/// let doughnuts = problem.var("doughnuts").min(14.0).build();
+/// problem
+///     .maximise(3.0 * bagels + 1.25 * doughnuts)
+///     .constraint("flour", 12.0 * bagels + 6.5 * doughnuts, Le, 400.0);
+///
+/// let solution = problem.solve().unwrap();
+/// assert!((solution.objective - 94.75).abs() < 1e-9);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Problem {
+    names: Vec<String>,
+    bounds: Vec<Bound>,
+    goal: String,
+    objective: LinearExpr,
+    // rows as `expression <sense> rhs`, with every variable moved to the left
+    constraints: Vec<(String, LinearExpr, Sense, f64)>,
+}
+
+impl Default for Problem {
+    fn default() -> Self {
+        Problem {
+            names: vec![],
+            bounds: vec![],
+            goal: "max".to_string(),
+            objective: LinearExpr::default(),
+            constraints: vec![],
+        }
+    }
+}
+
+impl Problem {
+    pub fn new() -> Problem {
+        Problem::default()
+    }
+
+    /// Declares a variable, declaring a name again only changes its bounds
+    /// and returns the existing handle.
+    pub fn var(&mut self, name: &str) -> VarBuilder<'_> {
+        VarBuilder {
+            problem: self,
+            name: name.to_string(),
+            bound: Bound::default(),
+        }
+    }
+
+    /// The name a handle was declared with.
+    pub fn name(&self, var: Var) -> &str {
+        &self.names[var.0]
+    }
+
+    pub fn maximise(&mut self, objective: impl Into<LinearExpr>) -> &mut Self {
+        self.goal = "max".to_string();
+        self.objective = self.checked(objective.into());
+        self
+    }
+
+    pub fn minimise(&mut self, objective: impl Into<LinearExpr>) -> &mut Self {
+        self.goal = "min".to_string();
+        self.objective = self.checked(objective.into());
+        self
+    }
+
+    /// Adds the constraint `lhs <sense> rhs`, either side may be a number, a
+    /// variable or an expression.
+    pub fn constraint(
+        &mut self,
+        name: &str,
+        lhs: impl Into<LinearExpr>,
+        sense: Sense,
+        rhs: impl Into<LinearExpr>,
+    ) -> &mut Self {
+        let row = self.checked(lhs.into() - rhs);
+        let rhs = -row.constant;
+        let row = LinearExpr {
+            constant: 0.0,
+            ..row
+        };
+        self.constraints.push((name.to_string(), row, sense, rhs));
+        self
+    }
+
+    // handles are plain indices, catch the ones made by another problem early
+    fn checked(&self, expr: LinearExpr) -> LinearExpr {
+        if let Some((index, _)) = expr.terms.last_key_value() {
+            assert!(
+                *index < self.names.len(),
+                "variable handle {} does not belong to this problem",
+                index
+            );
+        }
+        expr
+    }
+
+    /// The problem in matrix form, columns in declaration order.
+    pub fn to_matrix(&self) -> MatrixProblem {
+        let mut objective = vec![0.0; self.names.len()];
+        for (var, value) in self.objective.terms() {
+            objective[var.0] = value;
+        }
+        let entries = self
+            .constraints
+            .iter()
+            .enumerate()
+            .flat_map(|(row, (_, expr, _, _))| {
+                expr.terms().map(move |(var, value)| (row, var.0, value))
+            })
+            .collect();
+        MatrixProblem {
+            variables: self.names.clone(),
+            goal: self.goal.clone(),
+            objective,
+            objective_constant: self.objective.constant,
+            matrix: SparseMatrix::Triplet {
+                rows: self.constraints.len(),
+                cols: self.names.len(),
+                entries,
+            },
+            senses: self.constraints.iter().map(|row| row.2).collect(),
+            rhs: self.constraints.iter().map(|row| row.3).collect(),
+            bounds: self.bounds.clone(),
+            constraint_names: self.constraints.iter().map(|row| row.0.clone()).collect(),
+        }
+    }
+
+    /// The problem as expression strings, like the JSON input format.
+    pub fn to_problem(&self) -> UnoptimizedProblem {
+        // the builder only makes rows and columns that fit together
+        UnoptimizedProblem::try_from(&self.to_matrix()).expect("the builder's matrix is consistent")
+    }
+
+    /// The problem in the JSON input format.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.to_problem()).unwrap()
+    }
+
+    /// Solves the matrix form directly, without writing expression strings.
+    pub fn solve(&self) -> Result<SolutionResponse, AlpsError> {
+        solve_matrix(&self.to_matrix())
+    }
+}
+
+impl From<&Problem> for UnoptimizedProblem {
+    fn from(problem: &Problem) -> Self {
+        problem.to_problem()
+    }
+}
+
+impl From<&Problem> for MatrixProblem {
+    fn from(problem: &Problem) -> Self {
+        problem.to_matrix()
+    }
+}
@@ -0,0 +1,596 @@
+use crate::exact::{approximate, exact_model, exact_value, ExactModel};
+use crate::limits::Stop;
+use crate::simplex::{optimise, program, Outcome, Scalar};
+use crate::stats::short;
+use crate::verify::VerifyOptions;
+use crate::{AlpsError, MatrixProblem, Sense, SolutionResponse, UnoptimizedProblem};
+use indexmap::IndexMap;
+use num_rational::BigRational;
+use num_traits::{Signed, Zero};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// Evidence for what a solve found that [`certify`] can check on its own.
+///
+/// Duals follow the sign of the rest of the crate, the change of the
+/// objective per unit increase of the right hand side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Certificate {
+    /// A primal and a dual solution with the same objective
+    Optimal {
+        objective: f64,
+        values: IndexMap<String, f64>,
+        duals: IndexMap<String, f64>,
+        dual_objective: f64,
+        duality_gap: f64,
+        /// The largest violation of a constraint or bound by `values`
+        primal_residual: f64,
+        /// The largest violation of dual feasibility by `duals`
+        dual_residual: f64,
+    },
+    /// Farkas multipliers: combining the constraints with them gives one
+    /// that no point within the bounds satisfies
+    Infeasible {
+        farkas: IndexMap<String, f64>,
+        /// How far the combination misses, positive for a proof, with the
+        /// largest multiplier scaled to one
+        margin: f64,
+        dual_residual: f64,
+    },
+}
+
+/// How much [`certify`] lets the residuals and the duality gap be. The
+/// values are held to the constraints and bounds as [`verify`](crate::verify)
+/// holds them, with the same tolerances.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CertifyOptions {
+    pub absolute: f64,
+    /// On top of `absolute`, this share of the larger side of a constraint,
+    /// of the bound, of the largest objective coefficient or of the objective
+    /// is accepted
+    pub relative: f64,
+}
+
+impl Default for CertifyOptions {
+    fn default() -> Self {
+        CertifyOptions {
+            absolute: 1e-6,
+            relative: 1e-9,
+        }
+    }
+}
+
+impl CertifyOptions {
+    // the options `verify` would check the values with
+    fn feasibility(&self) -> VerifyOptions {
+        VerifyOptions {
+            absolute: self.absolute,
+            relative: self.relative,
+            ..VerifyOptions::default()
+        }
+    }
+
+    fn tolerance(&self, size: f64) -> f64 {
+        self.feasibility().tolerance(size)
+    }
+}
+
+/// What [`certify`] found, every number recomputed from the problem.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CertificateCheck {
+    /// Whether the certificate proves its claim within the tolerances
+    pub valid: bool,
+    /// `optimal` or `infeasible`
+    pub claim: String,
+    pub objective: Option<f64>,
+    pub dual_objective: Option<f64>,
+    pub duality_gap: Option<f64>,
+    pub primal_residual: Option<f64>,
+    pub dual_residual: f64,
+    pub margin: Option<f64>,
+    /// Why the certificate fails or what it leaves out
+    pub issues: Vec<String>,
+}
+
+impl fmt::Display for CertificateCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.valid { "valid" } else { "invalid" };
+        write!(f, "{} {} certificate", verdict, self.claim)?;
+        if let (Some(objective), Some(gap)) = (self.objective, self.duality_gap) {
+            write!(f, ", objective {}, gap {}", short(objective), short(gap))?;
+        }
+        if let Some(residual) = self.primal_residual {
+            write!(f, ", primal residual {}", short(residual))?;
+        }
+        write!(f, ", dual residual {}", short(self.dual_residual))?;
+        if let Some(margin) = self.margin {
+            write!(f, ", margin {}", short(margin))?;
+        }
+        writeln!(f)?;
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+// what is wrong with a certificate, and whether any of it breaks the proof
+#[derive(Default)]
+struct Issues {
+    messages: Vec<String>,
+    fatal: bool,
+}
+
+impl Issues {
+    // something the check leaves aside, the certificate can still be valid
+    fn note(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    // something that keeps the certificate from proving its claim
+    fn fail(&mut self, message: String) {
+        self.messages.push(message);
+        self.fatal = true;
+    }
+}
+
+// the value every name of `names` has in `given`, zero and an issue when it
+// is missing or not a finite number
+fn lookup<'a>(
+    names: impl Iterator<Item = &'a String>,
+    given: &IndexMap<String, f64>,
+    what: &str,
+    issues: &mut Issues,
+) -> Vec<BigRational> {
+    let values: Vec<BigRational> = names
+        .map(|name| match given.get(name) {
+            Some(value) => exact_value(*value).unwrap_or_else(|| {
+                issues.fail(format!("{} `{}` is not a finite number", what, name));
+                BigRational::zero()
+            }),
+            None => {
+                issues.fail(format!("{} `{}` is missing, taken as zero", what, name));
+                BigRational::zero()
+            }
+        })
+        .collect();
+    values
+}
+
+fn unknown<'a>(
+    names: impl Iterator<Item = &'a String> + Clone,
+    given: &IndexMap<String, f64>,
+    what: &str,
+    issues: &mut Issues,
+) {
+    for name in given.keys() {
+        if !names.clone().any(|known| known == name) {
+            issues.note(format!(
+                "{} `{}` is not in the problem, ignored",
+                what, name
+            ));
+        }
+    }
+}
+
+fn max(values: impl Iterator<Item = BigRational>) -> BigRational {
+    values.fold(
+        BigRational::zero(),
+        |max, value| {
+            if value > max {
+                value
+            } else {
+                max
+            }
+        },
+    )
+}
+
+// how far multipliers are on the wrong side of zero: `>=` rows take
+// nonnegative ones and `<=` rows nonpositive ones, in minimisation terms
+fn sign_violations(model: &ExactModel, multipliers: &[BigRational]) -> BigRational {
+    max(model
+        .program
+        .senses
+        .iter()
+        .zip(multipliers)
+        .map(|(sense, y)| match sense {
+            Sense::Le if y.is_positive() => y.clone(),
+            Sense::Ge if y.is_negative() => -y.clone(),
+            _ => BigRational::zero(),
+        }))
+}
+
+// A^T y
+fn combine(model: &ExactModel, multipliers: &[BigRational]) -> Vec<BigRational> {
+    let mut combined = vec![BigRational::zero(); model.program.bounds.len()];
+    for (entries, y) in model.program.rows.iter().zip(multipliers) {
+        for (col, value) in entries {
+            combined[*col] += value * y;
+        }
+    }
+    combined
+}
+
+/// Checks a certificate against a problem without trusting whoever made
+/// it. The problem is read into fractions by a parser independent of the
+/// solvers and every residual is computed exactly from the certificate's
+/// numbers; only the results are rounded.
+pub fn certify(
+    problem: &UnoptimizedProblem,
+    certificate: &Certificate,
+    options: &CertifyOptions,
+) -> Result<CertificateCheck, AlpsError> {
+    let model = exact_model(problem)?;
+    let program = &model.program;
+    let constraint_names = || problem.constraints.iter().map(|c| &c.name);
+    let mut issues = Issues::default();
+    match certificate {
+        Certificate::Optimal {
+            objective: claimed,
+            values,
+            duals,
+            ..
+        } => {
+            unknown(problem.variables.keys(), values, "variable", &mut issues);
+            unknown(constraint_names(), duals, "dual of", &mut issues);
+            let x = lookup(problem.variables.keys(), values, "variable", &mut issues);
+            let y = lookup(constraint_names(), duals, "dual of", &mut issues);
+
+            // each row and bound is held to its own tolerance, like `verify`
+            // does, and the worst miss is reported
+            let mut misses = vec![];
+            for ((lhs, rhs), sense) in model.sides(&x).into_iter().zip(&program.senses) {
+                let violation = match sense {
+                    Sense::Le => (&lhs - &rhs).max(BigRational::zero()),
+                    Sense::Ge => (&rhs - &lhs).max(BigRational::zero()),
+                    Sense::Eq => (&lhs - &rhs).abs(),
+                };
+                let size = approximate(&lhs.abs().max(rhs.abs()));
+                misses.push((violation, size));
+            }
+            for ((min, max), value) in program.bounds.iter().zip(&x) {
+                let below = min.as_ref().map(|min| (min - value, min));
+                let above = max.as_ref().map(|max| (value - max, max));
+                for (violation, limit) in below.into_iter().chain(above) {
+                    misses.push((violation, approximate(limit)));
+                }
+            }
+            let feasibility = options.feasibility();
+            let beyond = misses
+                .iter()
+                .any(|(violation, size)| approximate(violation) > feasibility.tolerance(*size));
+            let primal_residual = max(misses.into_iter().map(|(violation, _)| violation));
+
+            // work on the minimisation with the same feasible set
+            let sign = if program.minimise {
+                BigRational::from_integer(1.into())
+            } else {
+                BigRational::from_integer((-1).into())
+            };
+            let y: Vec<BigRational> = y.into_iter().map(|y| y * &sign).collect();
+            let combined = combine(&model, &y);
+            let mut dual_residual = sign_violations(&model, &y);
+            let mut dual_objective = program
+                .rhs
+                .iter()
+                .zip(&y)
+                .fold(BigRational::zero(), |sum, (rhs, y)| sum + rhs * y);
+            for ((cost, column), (min, max)) in
+                program.objective.iter().zip(combined).zip(&program.bounds)
+            {
+                // the reduced cost has to be paid at the bound it points to
+                let reduced = cost * &sign - column;
+                let bound = match () {
+                    _ if reduced.is_positive() => min,
+                    _ if reduced.is_negative() => max,
+                    _ => continue,
+                };
+                match bound {
+                    Some(bound) => dual_objective += &reduced * bound,
+                    None => dual_residual = dual_residual.max(reduced.abs()),
+                }
+            }
+            let dual_objective = dual_objective * &sign + &program.constant;
+            let objective = program
+                .objective
+                .iter()
+                .zip(&x)
+                .fold(program.constant.clone(), |sum, (cost, value)| {
+                    sum + cost * value
+                });
+
+            let objective = approximate(&objective);
+            let dual_objective = approximate(&dual_objective);
+            let duality_gap = (objective - dual_objective).abs();
+            let primal_residual = approximate(&primal_residual);
+            let dual_residual = approximate(&dual_residual);
+            let largest =
+                |values: &[BigRational]| approximate(&max(values.iter().map(|v| v.abs())));
+            if beyond {
+                issues.fail(format!(
+                    "the values miss the constraints or bounds by {}",
+                    short(primal_residual)
+                ));
+            }
+            if dual_residual > options.tolerance(largest(&program.objective)) {
+                issues.fail(format!(
+                    "the duals are infeasible by {}",
+                    short(dual_residual)
+                ));
+            }
+            if duality_gap > options.tolerance(objective) {
+                issues.fail(format!(
+                    "the duality gap is {}, objective {} against dual objective {}",
+                    short(duality_gap),
+                    short(objective),
+                    short(dual_objective)
+                ));
+            }
+            if (objective - claimed).abs() > options.tolerance(objective) {
+                issues.fail(format!(
+                    "the certificate claims objective {} but the values give {}",
+                    short(*claimed),
+                    short(objective)
+                ));
+            }
+            Ok(CertificateCheck {
+                valid: !issues.fatal,
+                claim: "optimal".to_string(),
+                objective: Some(objective),
+                dual_objective: Some(dual_objective),
+                duality_gap: Some(duality_gap),
+                primal_residual: Some(primal_residual),
+                dual_residual,
+                margin: None,
+                issues: issues.messages,
+            })
+        }
+        Certificate::Infeasible { farkas, .. } => {
+            unknown(constraint_names(), farkas, "multiplier of", &mut issues);
+            let y = lookup(constraint_names(), farkas, "multiplier of", &mut issues);
+            // a certificate scaled by a positive number proves the same
+            let largest = max(y.iter().map(|y| y.abs()));
+            if largest.is_zero() {
+                issues.fail("every multiplier is zero".to_string());
+                return Ok(CertificateCheck {
+                    valid: false,
+                    claim: "infeasible".to_string(),
+                    objective: None,
+                    dual_objective: None,
+                    duality_gap: None,
+                    primal_residual: None,
+                    dual_residual: 0.0,
+                    margin: Some(0.0),
+                    issues: issues.messages,
+                });
+            }
+            let y: Vec<BigRational> = y.into_iter().map(|y| y / &largest).collect();
+
+            // every point within the bounds has y^T A x at most `reach`, the
+            // constraints want it at least y^T b
+            let mut dual_residual = sign_violations(&model, &y);
+            let mut reach = BigRational::zero();
+            for (column, (min, max)) in combine(&model, &y).into_iter().zip(&program.bounds) {
+                let bound = match () {
+                    _ if column.is_positive() => max,
+                    _ if column.is_negative() => min,
+                    _ => continue,
+                };
+                match bound {
+                    Some(bound) => reach += &column * bound,
+                    None => dual_residual = dual_residual.max(column.abs()),
+                }
+            }
+            let needed = program
+                .rhs
+                .iter()
+                .zip(&y)
+                .fold(BigRational::zero(), |sum, (rhs, y)| sum + rhs * y);
+            let margin = approximate(&(needed - reach));
+            let dual_residual = approximate(&dual_residual);
+            if dual_residual > options.absolute {
+                issues.fail(format!(
+                    "the multipliers are off by {}, their combination can be met",
+                    short(dual_residual)
+                ));
+            }
+            if margin <= options.absolute {
+                issues.fail(format!(
+                    "the margin is only {}, a point within the bounds can meet the combination",
+                    short(margin)
+                ));
+            }
+            Ok(CertificateCheck {
+                valid: !issues.fatal,
+                claim: "infeasible".to_string(),
+                objective: None,
+                dual_objective: None,
+                duality_gap: None,
+                primal_residual: None,
+                dual_residual,
+                margin: Some(margin),
+                issues: issues.messages,
+            })
+        }
+    }
+}
+
+// fills in the residuals a certificate reports from `certify` itself
+fn measured(
+    problem: &UnoptimizedProblem,
+    certificate: Certificate,
+) -> Result<Certificate, AlpsError> {
+    let check = certify(problem, &certificate, &CertifyOptions::default())?;
+    Ok(match certificate {
+        Certificate::Optimal {
+            objective,
+            values,
+            duals,
+            ..
+        } => Certificate::Optimal {
+            objective,
+            values,
+            duals,
+            dual_objective: check.dual_objective.unwrap_or_default(),
+            duality_gap: check.duality_gap.unwrap_or_default(),
+            primal_residual: check.primal_residual.unwrap_or_default(),
+            dual_residual: check.dual_residual,
+        },
+        Certificate::Infeasible { farkas, .. } => Certificate::Infeasible {
+            farkas,
+            margin: check.margin.unwrap_or_default(),
+            dual_residual: check.dual_residual,
+        },
+    })
+}
+
+// the certificate of what the simplex found, its numbers as f64, an error
+// when a limit stopped it short of the optimum
+fn found<T: Scalar>(
+    outcome: Outcome<T>,
+    variables: &[String],
+    constraints: &[String],
+) -> Result<Certificate, AlpsError> {
+    let names = |values: &[T], names: &[String]| {
+        names
+            .iter()
+            .cloned()
+            .zip(
+                values
+                    .iter()
+                    .map(|value| value.to_f64().unwrap_or(f64::NAN)),
+            )
+            .collect()
+    };
+    Ok(match outcome {
+        Outcome::Optimal(optimum) if !optimum.status.is_optimal() => {
+            return Err(AlpsError::Stopped(format!(
+                "{} before the certificate was proven",
+                optimum.status
+            )))
+        }
+        Outcome::Optimal(optimum) => Certificate::Optimal {
+            objective: optimum.objective.to_f64().unwrap_or(f64::NAN),
+            values: names(&optimum.values, variables),
+            duals: names(&optimum.duals, constraints),
+            dual_objective: 0.0,
+            duality_gap: 0.0,
+            primal_residual: 0.0,
+            dual_residual: 0.0,
+        },
+        Outcome::Infeasible(farkas) => Certificate::Infeasible {
+            farkas: names(&farkas, constraints),
+            margin: 0.0,
+            dual_residual: 0.0,
+        },
+    })
+}
+
+// the certificate of the simplex on fractions, which doesn't round on the
+// way and so proves what it finds
+fn prove_exact(problem: &UnoptimizedProblem, stop: &Stop) -> Result<Certificate, AlpsError> {
+    let model = exact_model(problem)?;
+    let variables: Vec<String> = problem.variables.keys().cloned().collect();
+    let constraints: Vec<String> = problem.constraints.iter().map(|c| c.name.clone()).collect();
+    let outcome = optimise(&model.program, None, stop)?;
+    measured(problem, found(outcome, &variables, &constraints)?)
+}
+
+/// Solves a problem with the simplex and returns a certificate of what it
+/// found: optimal values with duals, or Farkas multipliers when nothing is
+/// feasible. When rounding leaves the `f64` certificate short of a proof,
+/// the simplex runs again on fractions. An unbounded problem is an error.
+pub fn prove(problem: &UnoptimizedProblem) -> Result<Certificate, AlpsError> {
+    prove_within(problem, &Stop::default())
+}
+
+// like `prove`, stopping with an error when a limit runs out
+pub(crate) fn prove_within(
+    problem: &UnoptimizedProblem,
+    stop: &Stop,
+) -> Result<Certificate, AlpsError> {
+    let matrix = MatrixProblem::try_from(problem)?;
+    matrix.check()?;
+    let outcome = optimise(&program(&matrix), None, stop)?;
+    let certificate = measured(
+        problem,
+        found(outcome, &matrix.variables, &matrix.constraint_names)?,
+    )?;
+    if certify(problem, &certificate, &CertifyOptions::default())?.valid {
+        Ok(certificate)
+    } else {
+        prove_exact(problem, stop)
+    }
+}
+
+/// The optimality certificate of a solution, with its duals when the solver
+/// reported them and otherwise duals from the simplex. When those don't make
+/// the certificate valid, the duals of the simplex on fractions are taken
+/// instead. A solution a limit stopped short of the optimum has no
+/// optimality to prove and is an error.
+pub fn certificate(
+    problem: &UnoptimizedProblem,
+    solution: &SolutionResponse,
+) -> Result<Certificate, AlpsError> {
+    certificate_within(problem, solution, &Stop::default())
+}
+
+// like `certificate`, stopping with an error when a limit runs out
+pub(crate) fn certificate_within(
+    problem: &UnoptimizedProblem,
+    solution: &SolutionResponse,
+    stop: &Stop,
+) -> Result<Certificate, AlpsError> {
+    if !solution.status.is_optimal() {
+        return Err(AlpsError::Stopped(format!(
+            "{}, only an optimal solution has a certificate",
+            solution.status
+        )));
+    }
+    let duals: Option<IndexMap<String, f64>> = solution
+        .const_values
+        .iter()
+        .map(|constraint| Some((constraint.name.clone(), constraint.dual?)))
+        .collect();
+    let duals = match duals {
+        Some(duals) => duals,
+        None => optimal_duals(prove_within(problem, stop)?)?,
+    };
+    let values: IndexMap<String, f64> = problem
+        .variables
+        .keys()
+        .filter_map(|name| Some((name.clone(), *solution.variable_solutions.get(name)?)))
+        .collect();
+    let with = |duals| {
+        measured(
+            problem,
+            Certificate::Optimal {
+                objective: solution.objective,
+                values: values.clone(),
+                duals,
+                dual_objective: 0.0,
+                duality_gap: 0.0,
+                primal_residual: 0.0,
+                dual_residual: 0.0,
+            },
+        )
+    };
+    let certificate = with(duals)?;
+    if certify(problem, &certificate, &CertifyOptions::default())?.valid {
+        Ok(certificate)
+    } else {
+        with(optimal_duals(prove_exact(problem, stop)?)?)
+    }
+}
+
+// the duals of an optimality certificate
+fn optimal_duals(certificate: Certificate) -> Result<IndexMap<String, f64>, AlpsError> {
+    match certificate {
+        Certificate::Optimal { duals, .. } => Ok(duals),
+        Certificate::Infeasible { .. } => Err(AlpsError::Infeasible),
+    }
+}
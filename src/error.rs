@@ -0,0 +1,76 @@
+use good_lp::ResolutionError;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// Everything that can go wrong between reading a problem and returning a
+/// solution.
+///
+/// Serializes as `{"kind": "parse", "message": "..."}` so it can be embedded
+/// in machine readable output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum AlpsError {
+    /// The input is not a valid problem document
+    InvalidInput(String),
+    /// An expression or constraint could not be parsed
+    Parse(String),
+    /// The problem is inconsistent, e.g. vectors that don't match the matrix
+    InvalidModel(String),
+    /// No assignment satisfies all the constraints
+    Infeasible,
+    /// The objective can be improved without limit
+    Unbounded,
+    /// Any other failure reported by the solver
+    Solver(String),
+    /// A limit or cancellation stopped the solve before it found a feasible
+    /// solution
+    Stopped(String),
+}
+
+impl AlpsError {
+    /// A short stable identifier for the kind of error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlpsError::InvalidInput(_) => "invalid_input",
+            AlpsError::Parse(_) => "parse",
+            AlpsError::InvalidModel(_) => "invalid_model",
+            AlpsError::Infeasible => "infeasible",
+            AlpsError::Unbounded => "unbounded",
+            AlpsError::Solver(_) => "solver",
+            AlpsError::Stopped(_) => "stopped",
+        }
+    }
+}
+
+impl fmt::Display for AlpsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlpsError::InvalidInput(message) => write!(f, "invalid input: {}", message),
+            AlpsError::Parse(message) => write!(f, "parse error: {}", message),
+            AlpsError::InvalidModel(message) => write!(f, "invalid model: {}", message),
+            AlpsError::Infeasible => write!(f, "the problem is infeasible"),
+            AlpsError::Unbounded => write!(f, "the problem is unbounded"),
+            AlpsError::Solver(message) => write!(f, "solver error: {}", message),
+            AlpsError::Stopped(message) => write!(f, "stopped: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AlpsError {}
+
+impl From<ResolutionError> for AlpsError {
+    fn from(error: ResolutionError) -> Self {
+        match error {
+            ResolutionError::Infeasible => AlpsError::Infeasible,
+            ResolutionError::Unbounded => AlpsError::Unbounded,
+            other => AlpsError::Solver(other.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AlpsError {
+    fn from(error: serde_json::Error) -> Self {
+        AlpsError::InvalidInput(error.to_string())
+    }
+}
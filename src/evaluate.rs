@@ -0,0 +1,115 @@
+use crate::stats::short;
+use crate::{
+    constraint_solutions, create_expression, create_variables, find_inequality,
+    parse_objective_expression, verify, AlpsError, ConstraintSolution, UnoptimizedProblem,
+    VerifyOptions,
+};
+use good_lp::Variable as GoodVariable;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A candidate assignment scored against a problem by [`evaluate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Evaluation {
+    /// Whether every constraint and bound holds
+    pub feasible: bool,
+    pub objective: f64,
+    pub const_values: Vec<ConstraintSolution>,
+    /// Names of the constraints the assignment breaks
+    pub violated: Vec<String>,
+    /// Variables outside their bounds
+    pub out_of_bounds: Vec<String>,
+    /// Variables of `integers` away from a whole value
+    pub not_whole: Vec<String>,
+    /// Declared variables without a value, counted as zero
+    pub missing: Vec<String>,
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}, objective {}",
+            if self.feasible {
+                "feasible"
+            } else {
+                "infeasible"
+            },
+            short(self.objective)
+        )?;
+        for constraint in &self.const_values {
+            if self.violated.contains(&constraint.name) {
+                writeln!(
+                    f,
+                    "constraint `{}` is violated: {} {} {}",
+                    constraint.name,
+                    short(constraint.lhs),
+                    find_inequality(&constraint.expression).unwrap_or("?"),
+                    short(constraint.rhs)
+                )?;
+            }
+        }
+        for name in &self.out_of_bounds {
+            writeln!(f, "variable `{}` is outside its bounds", name)?;
+        }
+        for name in &self.not_whole {
+            writeln!(f, "variable `{}` is not a whole number", name)?;
+        }
+        for name in &self.missing {
+            writeln!(f, "variable `{}` has no value, counted as zero", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Scores a partial or complete assignment, say a plan made by hand: the
+/// objective and both sides of every constraint are computed like for a
+/// solution, and broken constraints, crossed bounds and variables without
+/// a value are listed. What counts as broken is decided by [`verify`] with
+/// the same `options`, so `alps evaluate` and `alps check` agree.
+pub fn evaluate(
+    problem: &UnoptimizedProblem,
+    values: &HashMap<String, f64>,
+    options: &VerifyOptions,
+) -> Result<Evaluation, AlpsError> {
+    let const_values = constraint_solutions(problem, values)?;
+    let report = verify(problem, values, options)?;
+    let violated = report
+        .constraints
+        .iter()
+        .filter(|check| !check.satisfied)
+        .map(|check| check.name.clone())
+        .collect();
+    let mut out_of_bounds: Vec<String> = report
+        .bounds
+        .iter()
+        .map(|bound| bound.name.clone())
+        .collect();
+    out_of_bounds.dedup();
+    let not_whole = report
+        .integrality
+        .iter()
+        .map(|integer| integer.name.clone())
+        .collect();
+
+    let (_problem_variables, _variable_names, variable_hashmap) =
+        create_variables(problem.variables.clone());
+    let parsed_expression = parse_objective_expression(&problem.objective.expression)?;
+    let objective = create_expression(&parsed_expression, &variable_hashmap)?;
+    let assignment: HashMap<GoodVariable, f64> = variable_hashmap
+        .iter()
+        .map(|(name, var)| (*var, values.get(name).copied().unwrap_or(0.0)))
+        .collect();
+
+    Ok(Evaluation {
+        feasible: report.feasible,
+        objective: objective.eval_with(&assignment),
+        const_values,
+        violated,
+        out_of_bounds,
+        not_whole,
+        missing: report.missing,
+    })
+}
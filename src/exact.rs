@@ -0,0 +1,367 @@
+use crate::limits::Stop;
+use crate::progress::Phase;
+use crate::simplex::{optimise, LinearProgram, Outcome};
+use crate::verify::{lex, Token};
+use crate::warm::{basis, WarmStart};
+use crate::{
+    find_inequality, objective_direction, AlpsError, ConstraintSolution, Sense, SolutionResponse,
+    UnoptimizedProblem,
+};
+use good_lp::solvers::ObjectiveDirection;
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, ToPrimitive, Zero};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The exact side of a solution from [`Solver::Exact`](crate::Solver::Exact),
+/// every number a fraction such as `103/4`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExactSolution {
+    pub objective: String,
+    /// Variables in declaration order
+    pub variable_solutions: IndexMap<String, String>,
+    /// Duals of the constraints, in order
+    pub duals: IndexMap<String, String>,
+    pub reduced_costs: IndexMap<String, String>,
+}
+
+/// The rational a decimal literal such as `6.5`, `.25` or `1e-3` stands for,
+/// without going through `f64`.
+pub fn parse_rational(text: &str) -> Option<BigRational> {
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().ok()?),
+        None => (text, 0),
+    };
+    let (negative, mantissa) = match mantissa.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let digits = format!("{}{}", whole, fraction);
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut value = BigRational::from_integer(digits.parse::<BigInt>().ok()?);
+    let shift = exponent - fraction.len() as i32;
+    let power = BigRational::from_integer(BigInt::from(10).pow(shift.unsigned_abs()));
+    value = if shift >= 0 {
+        value * power
+    } else {
+        value / power
+    };
+    Some(if negative { -value } else { value })
+}
+
+// an `f64` read back through its shortest decimal form so `0.1` becomes 1/10
+pub(crate) fn exact_value(value: f64) -> Option<BigRational> {
+    Some(value)
+        .filter(|value| value.is_finite())
+        .and_then(|value| parse_rational(&value.to_string()))
+}
+
+// a linear expression over the columns of the problem
+#[derive(Clone, Default)]
+struct Linear {
+    terms: BTreeMap<usize, BigRational>,
+    constant: BigRational,
+}
+
+impl Linear {
+    fn constant(value: BigRational) -> Linear {
+        Linear {
+            terms: BTreeMap::new(),
+            constant: value,
+        }
+    }
+
+    fn add(mut self, other: Linear, sign: &BigRational) -> Linear {
+        for (col, value) in other.terms {
+            let entry = self.terms.entry(col).or_insert_with(BigRational::zero);
+            *entry += value * sign;
+        }
+        self.terms.retain(|_, value| !value.is_zero());
+        self.constant += other.constant * sign;
+        self
+    }
+
+    fn scale(mut self, factor: &BigRational) -> Linear {
+        for value in self.terms.values_mut() {
+            *value *= factor;
+        }
+        self.terms.retain(|_, value| !value.is_zero());
+        self.constant *= factor;
+        self
+    }
+
+    fn eval(&self, values: &[BigRational]) -> BigRational {
+        self.terms
+            .iter()
+            .fold(self.constant.clone(), |sum, (col, value)| {
+                sum + value * &values[*col]
+            })
+    }
+}
+
+// reads + - * / and parentheses into a linear expression, products and
+// quotients need a constant on one side
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    problem: &'a UnoptimizedProblem,
+}
+
+impl Parser<'_> {
+    fn sum(&mut self) -> Result<Linear, String> {
+        let mut value = self.product()?;
+        while let Some(Token::Operator(operator @ ('+' | '-'))) = self.tokens.get(self.position) {
+            let sign = if *operator == '+' {
+                BigRational::one()
+            } else {
+                -BigRational::one()
+            };
+            self.position += 1;
+            value = value.add(self.product()?, &sign);
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<Linear, String> {
+        let mut value = self.factor()?;
+        while let Some(Token::Operator(operator @ ('*' | '/'))) = self.tokens.get(self.position) {
+            let operator = *operator;
+            self.position += 1;
+            let right = self.factor()?;
+            value = match operator {
+                '*' if right.terms.is_empty() => value.scale(&right.constant),
+                '*' if value.terms.is_empty() => right.scale(&value.constant),
+                '/' if right.terms.is_empty() && !right.constant.is_zero() => {
+                    value.scale(&right.constant.recip())
+                }
+                '/' if right.terms.is_empty() => return Err("it divides by zero".to_string()),
+                _ => return Err("it is not linear".to_string()),
+            };
+        }
+        Ok(value)
+    }
+
+    fn factor(&mut self) -> Result<Linear, String> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        match token {
+            Some(Token::Operator('-')) => Ok(self.factor()?.scale(&-BigRational::one())),
+            Some(Token::Operator('+')) => self.factor(),
+            Some(Token::Number(literal)) => parse_rational(&literal)
+                .map(Linear::constant)
+                .ok_or_else(|| format!("`{}` is not a number", literal)),
+            Some(Token::Name(name)) => {
+                let col = self
+                    .problem
+                    .variables
+                    .get_index_of(&name)
+                    .ok_or_else(|| format!("`{}` is not a declared variable", name))?;
+                Ok(Linear {
+                    terms: BTreeMap::from([(col, BigRational::one())]),
+                    constant: BigRational::zero(),
+                })
+            }
+            Some(Token::Open) => {
+                let value = self.sum()?;
+                match self.tokens.get(self.position) {
+                    Some(Token::Close) => {
+                        self.position += 1;
+                        Ok(value)
+                    }
+                    _ => Err("a parenthesis is not closed".to_string()),
+                }
+            }
+            _ => Err("it ends early or has a stray operator".to_string()),
+        }
+    }
+}
+
+fn parse_linear(text: &str, problem: &UnoptimizedProblem) -> Result<Linear, String> {
+    let mut parser = Parser {
+        tokens: lex(text),
+        position: 0,
+        problem,
+    };
+    let value = parser.sum()?;
+    if parser.position < parser.tokens.len() {
+        return Err("it has a stray token".to_string());
+    }
+    Ok(value)
+}
+
+pub(crate) fn approximate(value: &BigRational) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+// a problem read straight from its text into fractions, with both sides of
+// every constraint kept for reporting
+pub(crate) struct ExactModel {
+    pub program: LinearProgram<BigRational>,
+    sides: Vec<(Linear, Linear)>,
+}
+
+impl ExactModel {
+    // both sides of every constraint at `values`
+    pub(crate) fn sides(&self, values: &[BigRational]) -> Vec<(BigRational, BigRational)> {
+        self.sides
+            .iter()
+            .map(|(lhs, rhs)| (lhs.eval(values), rhs.eval(values)))
+            .collect()
+    }
+}
+
+// reads a problem without good_lp, so the checks built on it don't share
+// mistakes with the model the solvers get
+pub(crate) fn exact_model(problem: &UnoptimizedProblem) -> Result<ExactModel, AlpsError> {
+    let objective = parse_linear(&problem.objective.expression, problem)
+        .map_err(|reason| AlpsError::Parse(format!("the objective is not valid, {}", reason)))?;
+    let mut sides = vec![];
+    let mut program = LinearProgram {
+        rows: vec![],
+        senses: vec![],
+        rhs: vec![],
+        objective: (0..problem.variables.len())
+            .map(|col| {
+                objective
+                    .terms
+                    .get(&col)
+                    .cloned()
+                    .unwrap_or_else(BigRational::zero)
+            })
+            .collect(),
+        constant: objective.constant.clone(),
+        bounds: problem
+            .variables
+            .values()
+            .map(|variable| {
+                (
+                    variable.min.and_then(exact_value),
+                    variable.max.and_then(exact_value),
+                )
+            })
+            .collect(),
+        minimise: objective_direction(&problem.objective.goal) == ObjectiveDirection::Minimisation,
+    };
+    for constraint in &problem.constraints {
+        let invalid = |reason: String| {
+            AlpsError::Parse(format!(
+                "constraint `{}` is not valid, {}",
+                constraint.name, reason
+            ))
+        };
+        let operator = find_inequality(&constraint.expression)
+            .ok_or_else(|| invalid("it has no inequality".to_string()))?;
+        let sense = Sense::from_operator(operator)
+            .ok_or_else(|| invalid(format!("it uses the unsupported inequality `{}`", operator)))?;
+        let (lhs, rhs) = constraint.expression.split_once(operator).unwrap();
+        let lhs = parse_linear(lhs, problem).map_err(invalid)?;
+        let rhs = parse_linear(rhs, problem).map_err(invalid)?;
+        let row = lhs.clone().add(rhs.clone(), &-BigRational::one());
+        program.rows.push(row.terms.into_iter().collect());
+        program.senses.push(sense);
+        program.rhs.push(-row.constant);
+        sides.push((lhs, rhs));
+    }
+    Ok(ExactModel { program, sides })
+}
+
+/// Solves a problem in exact rational arithmetic: every literal is read as
+/// the fraction it denotes and the simplex of [`solve_simplex`](crate::solve_simplex)
+/// runs on fractions, so `94.75` comes out as `379/4`. The response holds
+/// `f64` approximations as usual and the fractions in `exact`.
+pub fn solve_exact(problem: &UnoptimizedProblem) -> Result<SolutionResponse, AlpsError> {
+    solve_exact_from(problem, None, &Stop::default())
+}
+
+// the exact solve, starting from a warm start when it is feasible and
+// stopping at the limits of `stop`
+pub(crate) fn solve_exact_from(
+    problem: &UnoptimizedProblem,
+    warm_start: Option<&WarmStart>,
+    stop: &Stop,
+) -> Result<SolutionResponse, AlpsError> {
+    stop.phase(Phase::Parse);
+    let ExactModel { program, sides } = exact_model(problem)?;
+    let variables: Vec<String> = problem.variables.keys().cloned().collect();
+    let constraints: Vec<String> = problem.constraints.iter().map(|c| c.name.clone()).collect();
+    let start = warm_start.map(|warm| warm.start(&variables, &constraints, exact_value));
+    let outcome = tracing::info_span!("solve", solver = "exact")
+        .in_scope(|| optimise(&program, start.as_ref(), stop))?;
+    let Outcome::Optimal(optimum) = outcome else {
+        return Err(AlpsError::Infeasible);
+    };
+    stop.phase(Phase::Extract);
+    let _span = tracing::info_span!("extract").entered();
+    let accepted = warm_start.is_some() && optimum.rejected.is_none();
+    let baseline = warm_start.map_or(optimum.iterations, |warm| {
+        warm.baseline(optimum.iterations, accepted)
+    });
+    let basis = basis(
+        &variables,
+        &constraints,
+        &optimum.statuses,
+        &optimum.slack,
+        baseline,
+    );
+    let warm_start =
+        warm_start.map(|warm| warm.report(optimum.iterations, optimum.rejected.clone()));
+    let names = || problem.variables.keys().cloned();
+    let variable_solutions = names()
+        .zip(optimum.values.iter().map(approximate))
+        .collect();
+    let const_values = problem
+        .constraints
+        .iter()
+        .zip(&sides)
+        .zip(&optimum.duals)
+        .map(|((constraint, (lhs, rhs)), dual)| ConstraintSolution {
+            name: constraint.name.clone(),
+            expression: constraint.expression.clone(),
+            lhs: approximate(&lhs.eval(&optimum.values)),
+            rhs: approximate(&rhs.eval(&optimum.values)),
+            dual: Some(approximate(dual)),
+        })
+        .collect::<Vec<_>>();
+    let constraint_names = || problem.constraints.iter().map(|c| c.name.clone());
+    let exact = ExactSolution {
+        objective: optimum.objective.to_string(),
+        variable_solutions: names()
+            .zip(optimum.values.iter().map(ToString::to_string))
+            .collect(),
+        duals: constraint_names()
+            .zip(optimum.duals.iter().map(ToString::to_string))
+            .collect(),
+        reduced_costs: names()
+            .zip(optimum.reduced_costs.iter().map(ToString::to_string))
+            .collect(),
+    };
+    Ok(SolutionResponse {
+        num_constraints: const_values.len(),
+        const_values,
+        objective: approximate(&optimum.objective),
+        variable_solutions,
+        reduced_costs: Some(
+            names()
+                .zip(optimum.reduced_costs.iter().map(approximate))
+                .collect(),
+        ),
+        presolve: None,
+        scaling: None,
+        verification: None,
+        exact: Some(exact),
+        certificate: None,
+        basis: Some(basis),
+        warm_start,
+        status: optimum.status,
+        metrics: None,
+    })
+}
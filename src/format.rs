@@ -0,0 +1,109 @@
+use crate::{add_spaces, parse_alps, to_alps, AlpsError, MatrixProblem, UnoptimizedProblem};
+use serde_json::Value;
+use std::path::Path;
+
+/// The formats a model can be read from and written to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelFormat {
+    /// The JSON input format, [`UnoptimizedProblem`]
+    Json,
+    /// The `.alps` text format, see [`parse_alps`]
+    Alps,
+    /// A [`MatrixProblem`] as JSON
+    Matrix,
+}
+
+impl ModelFormat {
+    pub const ALL: [ModelFormat; 3] = [ModelFormat::Json, ModelFormat::Alps, ModelFormat::Matrix];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelFormat::Json => "json",
+            ModelFormat::Alps => "alps",
+            ModelFormat::Matrix => "matrix",
+        }
+    }
+
+    /// Guesses the format from a file extension, only `.alps` is certain
+    /// since both JSON formats share `.json`.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<ModelFormat> {
+        match path.as_ref().extension()?.to_str()? {
+            "alps" => Some(ModelFormat::Alps),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the text itself: JSON documents with a
+    /// `matrix` field are matrices, other JSON is the input format and
+    /// anything else is read as `.alps` text.
+    pub fn detect(text: &str) -> ModelFormat {
+        match serde_json::from_str::<Value>(text) {
+            Ok(Value::Object(object)) if object.contains_key("matrix") => ModelFormat::Matrix,
+            Ok(_) => ModelFormat::Json,
+            Err(_) if text.trim_start().starts_with('{') => ModelFormat::Json,
+            Err(_) => ModelFormat::Alps,
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> Result<UnoptimizedProblem, AlpsError> {
+        match self {
+            ModelFormat::Json => Ok(serde_json::from_str(text)?),
+            ModelFormat::Alps => parse_alps(text),
+            ModelFormat::Matrix => {
+                let matrix: MatrixProblem = serde_json::from_str(text)?;
+                UnoptimizedProblem::try_from(&matrix)
+            }
+        }
+    }
+
+    /// Writes a problem, JSON is pretty printed. Variables keep the order
+    /// they are declared in.
+    pub fn write(&self, problem: &UnoptimizedProblem) -> Result<String, AlpsError> {
+        match self {
+            ModelFormat::Json => Ok(serde_json::to_string_pretty(problem)? + "\n"),
+            ModelFormat::Alps => Ok(to_alps(problem)),
+            ModelFormat::Matrix => {
+                let matrix = MatrixProblem::try_from(problem)?;
+                Ok(serde_json::to_string_pretty(&matrix)? + "\n")
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for ModelFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ModelFormat::ALL
+            .into_iter()
+            .find(|format| format.as_str() == name)
+            .ok_or_else(|| format!("unknown format `{}`, expected json, alps or matrix", name))
+    }
+}
+
+/// Reads a model in an explicit format, or one guessed from the text.
+pub fn read_model(
+    text: &str,
+    format: Option<ModelFormat>,
+) -> Result<UnoptimizedProblem, AlpsError> {
+    format
+        .unwrap_or_else(|| ModelFormat::detect(text))
+        .parse(text)
+}
+
+/// A canonical copy of a problem: expressions spaced the way the parser
+/// writes them, constraint names trimmed and the redundant variable names
+/// dropped.
+pub fn canonicalize(problem: &UnoptimizedProblem) -> UnoptimizedProblem {
+    let mut canonical = problem.clone();
+    for variable in canonical.variables.values_mut() {
+        variable.name.clear();
+    }
+    canonical.objective.goal = canonical.objective.goal.trim().to_lowercase();
+    canonical.objective.expression = add_spaces(&canonical.objective.expression);
+    for constraint in &mut canonical.constraints {
+        constraint.name = constraint.name.trim().to_string();
+        constraint.expression = add_spaces(&constraint.expression);
+    }
+    canonical
+}
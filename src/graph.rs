@@ -0,0 +1,120 @@
+use crate::report::number;
+use crate::{MatrixProblem, Sense, SimplexSolution};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+const BINDING: &str = "#f4a6a6";
+const SLACK: &str = "#d9ead3";
+const BASIC: &str = "#9fc5e8";
+const NONBASIC: &str = "#eeeeee";
+
+/// What [`to_dot`] draws.
+#[derive(Debug, Clone, Default)]
+pub struct GraphOptions {
+    /// Only draw the constraints and variables whose name matches, along
+    /// with everything they are connected to
+    pub filter: Option<Regex>,
+    /// Fill constraints by whether they bind (red) or have slack (green),
+    /// and variables by whether they are basic (blue) or not (grey)
+    pub solution: Option<SimplexSolution>,
+}
+
+// double quoted DOT ids and labels
+fn quote(text: &str) -> String {
+    format!("\"{}\"", escape(text))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The constraint–variable structure of a problem as an undirected
+/// bipartite graph in Graphviz DOT: a box per constraint, an ellipse per
+/// variable and an edge labelled with the coefficient for every nonzero.
+pub fn to_dot(problem: &MatrixProblem, options: &GraphOptions) -> String {
+    let rows = problem.matrix.row_entries();
+    let matches = |name: &str| options.filter.as_ref().is_none_or(|f| f.is_match(name));
+
+    let mut edges = vec![];
+    let mut constraints = BTreeSet::new();
+    let mut variables = BTreeSet::new();
+    for (row, entries) in rows.iter().enumerate() {
+        let row_matches = matches(&problem.constraint_names[row]);
+        if row_matches {
+            constraints.insert(row);
+        }
+        for (col, value) in entries {
+            if row_matches || matches(&problem.variables[*col]) {
+                edges.push((row, *col, *value));
+                constraints.insert(row);
+                variables.insert(*col);
+            }
+        }
+    }
+    for (col, name) in problem.variables.iter().enumerate() {
+        if matches(name) {
+            variables.insert(col);
+        }
+    }
+
+    let fill = |color: &str| format!(", style=filled, fillcolor={}", quote(color));
+    let mut dot =
+        String::from("graph alps {\n    rankdir=LR;\n    node [fontname=\"sans-serif\"];\n");
+    for row in &constraints {
+        let style = options
+            .solution
+            .as_ref()
+            .map(|solution| {
+                let activity: f64 = rows[*row]
+                    .iter()
+                    .map(|(col, value)| value * solution.values[*col])
+                    .sum();
+                let rhs = problem.rhs[*row];
+                let binding = problem.senses[*row] == Sense::Eq
+                    || (activity - rhs).abs() <= 1e-6 * (1.0 + rhs.abs());
+                fill(if binding { BINDING } else { SLACK })
+            })
+            .unwrap_or_default();
+        let _ = writeln!(
+            dot,
+            "    {} [shape=box, label=\"{}\\n{} {}\"{}];",
+            quote(&format!("c{}", row)),
+            escape(&problem.constraint_names[*row]),
+            problem.senses[*row].as_str(),
+            number(problem.rhs[*row]),
+            style
+        );
+    }
+    for col in &variables {
+        let style = options
+            .solution
+            .as_ref()
+            .map(|solution| {
+                fill(if solution.basic[*col] {
+                    BASIC
+                } else {
+                    NONBASIC
+                })
+            })
+            .unwrap_or_default();
+        let _ = writeln!(
+            dot,
+            "    {} [shape=ellipse, label={}{}];",
+            quote(&format!("v{}", col)),
+            quote(&problem.variables[*col]),
+            style
+        );
+    }
+    for (row, col, value) in edges {
+        let _ = writeln!(
+            dot,
+            "    {} -- {} [label={}];",
+            quote(&format!("c{}", row)),
+            quote(&format!("v{}", col)),
+            quote(&number(value))
+        );
+    }
+    dot += "}\n";
+    dot
+}
@@ -0,0 +1,415 @@
+use crate::{
+    solve_catching_panics, AlpsError, CancelToken, Observer, SolutionResponse, SolveEvent,
+    SolveOptions, Solver, UnoptimizedProblem,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Where a job is in its life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Succeeded | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// What the API reports about a job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobView {
+    pub id: String,
+    pub status: JobStatus,
+    /// Objective of the best solution found so far, updated while the job
+    /// runs
+    pub incumbent: Option<f64>,
+    /// Relative distance between the incumbent and the best possible bound,
+    /// known once the solver proves a bound
+    pub gap: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<SolutionResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<AlpsError>,
+}
+
+// a job as kept in memory and written to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Job {
+    #[serde(flatten)]
+    view: JobView,
+    problem: UnoptimizedProblem,
+    // stops the solve when the job is cancelled while it runs
+    #[serde(skip)]
+    cancel: CancelToken,
+}
+
+/// How jobs are run and where they are kept.
+#[derive(Debug, Clone)]
+pub struct JobsConfig {
+    /// Number of jobs solved at the same time, `0` only queues them
+    pub workers: usize,
+    /// Most jobs waiting for a worker, further submissions are refused
+    pub max_queued: usize,
+    /// Most finished jobs kept, in memory and on disk, the oldest are
+    /// forgotten first
+    pub max_finished: usize,
+    /// Directory jobs are persisted to, unfinished jobs found there are
+    /// queued again on start
+    pub directory: Option<PathBuf>,
+    /// The algorithm jobs are solved with
+    pub solver: Solver,
+    /// Longest a job may take, it then succeeds with the best solution so
+    /// far or fails when it has none
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        JobsConfig {
+            workers: 2,
+            max_queued: 64,
+            max_finished: 256,
+            directory: None,
+            solver: Solver::default(),
+            time_limit: None,
+        }
+    }
+}
+
+/// Why a job could not be submitted or cancelled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobError {
+    /// The queue already holds `max_queued` jobs
+    QueueFull,
+    /// No job has this id
+    NotFound,
+    /// The job already finished with this status
+    Finished(JobStatus),
+    /// The job could not be written to disk
+    Persistence(String),
+}
+
+#[derive(Default)]
+struct State {
+    jobs: HashMap<String, Job>,
+    queue: VecDeque<String>,
+    // finished jobs, oldest first
+    finished: VecDeque<String>,
+    next_id: u64,
+    shutdown: bool,
+}
+
+impl State {
+    // records that a job finished, returning the jobs that no longer fit
+    fn finish(&mut self, id: &str, max_finished: usize) -> Vec<String> {
+        self.finished.push_back(id.to_string());
+        let excess = self.finished.len().saturating_sub(max_finished);
+        let evicted: Vec<String> = self.finished.drain(..excess).collect();
+        for id in &evicted {
+            self.jobs.remove(id);
+        }
+        evicted
+    }
+}
+
+struct Shared {
+    state: Mutex<State>,
+    available: Condvar,
+    // held while a file is written or removed, so the file of a job always
+    // ends up with its latest state
+    disk: Mutex<()>,
+    directory: Option<PathBuf>,
+    max_finished: usize,
+    options: SolveOptions,
+}
+
+impl Shared {
+    // writes a job as it is now, the state is only locked to copy it
+    fn persist(&self, id: &str) -> Result<(), JobError> {
+        let Some(directory) = &self.directory else {
+            return Ok(());
+        };
+        let _disk = self.disk.lock().unwrap();
+        let Some(job) = self.state.lock().unwrap().jobs.get(id).cloned() else {
+            // forgotten in the meantime
+            return Ok(());
+        };
+        let path = directory.join(format!("{}.json", id));
+        let json = serde_json::to_vec(&job).map_err(|e| JobError::Persistence(e.to_string()))?;
+        // write next to the real file first so a crash never leaves half a job
+        let partial = path.with_extension("json.partial");
+        std::fs::write(&partial, json)
+            .and_then(|_| std::fs::rename(&partial, &path))
+            .map_err(|e| JobError::Persistence(e.to_string()))
+    }
+
+    // a worker can't hand the failure to anyone, so it is logged and the
+    // job carries on in memory
+    fn persist_or_log(&self, id: &str) {
+        if let Err(JobError::Persistence(message)) = self.persist(id) {
+            tracing::error!("job {} could not be persisted: {}", id, message);
+        }
+    }
+
+    fn forget(&self, evicted: Vec<String>) {
+        let Some(directory) = &self.directory else {
+            return;
+        };
+        let _disk = self.disk.lock().unwrap();
+        for id in evicted {
+            let path = directory.join(format!("{}.json", id));
+            if let Err(error) = std::fs::remove_file(&path) {
+                if error.kind() != std::io::ErrorKind::NotFound {
+                    tracing::error!("job {} could not be removed: {}", id, error);
+                }
+            }
+        }
+    }
+
+    // keeps the incumbent and gap of a running job up to date
+    fn observe(self: &Arc<Self>, id: &str) -> Observer {
+        let shared = Arc::downgrade(self);
+        let id = id.to_string();
+        Observer::new(move |event| {
+            // pivots come by the thousand, only these reach the state
+            let (incumbent, bound) = match event {
+                SolveEvent::Incumbent { objective } => (Some(*objective), None),
+                SolveEvent::Bound { bound } => (None, Some(*bound)),
+                _ => return,
+            };
+            let Some(shared) = shared.upgrade() else {
+                return;
+            };
+            let mut state = shared.state.lock().unwrap();
+            let Some(job) = state.jobs.get_mut(&id) else {
+                return;
+            };
+            if job.view.status != JobStatus::Running {
+                return;
+            }
+            if incumbent.is_some() {
+                job.view.incumbent = incumbent;
+            }
+            if let Some(bound) = bound {
+                job.view.gap = job.view.incumbent.map(|incumbent| gap(incumbent, bound));
+            }
+        })
+    }
+
+    fn work(self: &Arc<Self>) {
+        loop {
+            let (id, problem, cancel) = {
+                let mut state = self.state.lock().unwrap();
+                let id = loop {
+                    if state.shutdown {
+                        return;
+                    }
+                    match state.queue.pop_front() {
+                        Some(id) => break id,
+                        None => state = self.available.wait(state).unwrap(),
+                    }
+                };
+                let Some(job) = state.jobs.get_mut(&id) else {
+                    continue;
+                };
+                job.view.status = JobStatus::Running;
+                (id, job.problem.clone(), job.cancel.clone())
+            };
+            self.persist_or_log(&id);
+
+            let options = SolveOptions {
+                cancel: Some(cancel),
+                observer: Some(self.observe(&id)),
+                ..self.options.clone()
+            };
+            let result = solve_catching_panics(problem, &options);
+
+            let evicted = {
+                let mut state = self.state.lock().unwrap();
+                let Some(job) = state.jobs.get_mut(&id) else {
+                    continue;
+                };
+                // a job cancelled while running keeps its cancelled status
+                if job.view.status != JobStatus::Running {
+                    continue;
+                }
+                match result {
+                    Ok(solution) => {
+                        job.view.status = JobStatus::Succeeded;
+                        job.view.incumbent = Some(solution.objective);
+                        job.view.solution = Some(solution);
+                    }
+                    Err(error) => {
+                        job.view.status = JobStatus::Failed;
+                        job.view.error = Some(error);
+                    }
+                }
+                state.finish(&id, self.max_finished)
+            };
+            self.persist_or_log(&id);
+            self.forget(evicted);
+        }
+    }
+}
+
+// the distance between an objective and a bound, relative to the objective
+// and absolute near zero
+fn gap(incumbent: f64, bound: f64) -> f64 {
+    (incumbent - bound).abs() / incumbent.abs().max(1.0)
+}
+
+/// A queue of solves run in the background by a fixed pool of workers.
+///
+/// Dropping the queue stops the workers once their current job is done.
+pub struct JobQueue {
+    shared: Arc<Shared>,
+    max_queued: usize,
+}
+
+impl JobQueue {
+    /// Starts the workers, loading persisted jobs from `config.directory`.
+    pub fn new(config: JobsConfig) -> std::io::Result<JobQueue> {
+        let mut state = State::default();
+        let mut evicted = vec![];
+        if let Some(directory) = &config.directory {
+            std::fs::create_dir_all(directory)?;
+            let mut loaded = vec![];
+            for entry in std::fs::read_dir(directory)? {
+                let path = entry?.path();
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "json")
+                {
+                    let bytes = std::fs::read(&path)?;
+                    if let Ok(job) = serde_json::from_slice::<Job>(&bytes) {
+                        loaded.push(job);
+                    }
+                }
+            }
+            // ids are increasing numbers, so this restores the submission order
+            loaded.sort_by_key(|job| job.view.id.parse::<u64>().unwrap_or(0));
+            for mut job in loaded {
+                let id = job.view.id.clone();
+                let number = id.parse::<u64>().unwrap_or(0);
+                state.next_id = state.next_id.max(number + 1);
+                // a job that was running when we stopped has to start over
+                let finished = job.view.status.is_finished();
+                if !finished {
+                    job.view.status = JobStatus::Queued;
+                    state.queue.push_back(id.clone());
+                }
+                state.jobs.insert(id.clone(), job);
+                if finished {
+                    evicted.extend(state.finish(&id, config.max_finished));
+                }
+            }
+        }
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(state),
+            available: Condvar::new(),
+            disk: Mutex::new(()),
+            directory: config.directory,
+            max_finished: config.max_finished,
+            options: SolveOptions {
+                solver: config.solver,
+                time_limit: config.time_limit.map(|limit| limit.as_secs_f64()),
+                ..SolveOptions::default()
+            },
+        });
+        shared.forget(evicted);
+        for _ in 0..config.workers {
+            let shared = shared.clone();
+            std::thread::spawn(move || shared.work());
+        }
+        Ok(JobQueue {
+            shared,
+            max_queued: config.max_queued,
+        })
+    }
+
+    /// Queues a problem, returning the id of the new job.
+    pub fn submit(&self, problem: UnoptimizedProblem) -> Result<String, JobError> {
+        let id = {
+            let mut state = self.shared.state.lock().unwrap();
+            if state.queue.len() >= self.max_queued {
+                return Err(JobError::QueueFull);
+            }
+            let id = state.next_id.to_string();
+            let job = Job {
+                view: JobView {
+                    id: id.clone(),
+                    status: JobStatus::Queued,
+                    incumbent: None,
+                    gap: None,
+                    solution: None,
+                    error: None,
+                },
+                problem,
+                cancel: CancelToken::new(),
+            };
+            state.next_id += 1;
+            state.jobs.insert(id.clone(), job);
+            state.queue.push_back(id.clone());
+            id
+        };
+        // a job that can't be written is taken back before anyone hears of it
+        if let Err(error) = self.shared.persist(&id) {
+            let mut state = self.shared.state.lock().unwrap();
+            if let Some(job) = state.jobs.remove(&id) {
+                job.cancel.cancel();
+            }
+            state.queue.retain(|queued| *queued != id);
+            return Err(error);
+        }
+        self.shared.available.notify_one();
+        Ok(id)
+    }
+
+    /// The current state of a job, `None` once it is forgotten.
+    pub fn get(&self, id: &str) -> Option<JobView> {
+        let state = self.shared.state.lock().unwrap();
+        state.jobs.get(id).map(|job| job.view.clone())
+    }
+
+    /// Cancels a queued or running job. A running solve is stopped at its
+    /// next pivot, or given up on when it is minilp, and its result is
+    /// thrown away.
+    pub fn cancel(&self, id: &str) -> Result<JobView, JobError> {
+        let (view, evicted) = {
+            let mut state = self.shared.state.lock().unwrap();
+            let job = state.jobs.get_mut(id).ok_or(JobError::NotFound)?;
+            if job.view.status.is_finished() {
+                return Err(JobError::Finished(job.view.status));
+            }
+            job.view.status = JobStatus::Cancelled;
+            job.cancel.cancel();
+            let view = job.view.clone();
+            state.queue.retain(|queued| queued != id);
+            (view, state.finish(id, self.shared.max_finished))
+        };
+        self.shared.persist(id)?;
+        self.shared.forget(evicted);
+        Ok(view)
+    }
+}
+
+impl Drop for JobQueue {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.available.notify_all();
+    }
+}
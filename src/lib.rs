@@ -1,17 +1,84 @@
+use crate::certificate::certificate_within;
+use crate::limits::Stop;
+use crate::simplex::simplex_within;
+use good_lp::solvers::ObjectiveDirection;
 use good_lp::IntoAffineExpression;
 use good_lp::{constraint, Expression, Variable as GoodVariable};
 use good_lp::{default_solver, Solution, SolverModel};
 use good_lp::{ProblemVariables, VariableDefinition};
+use indexmap::IndexMap;
+use schemars::JsonSchema;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 use std::collections::HashMap;
-use std::error::Error;
+use std::fmt;
 use std::ops::Div;
 use std::ops::Mul;
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+// lets `alps!` refer to `::alps` from inside this crate too
+extern crate self as alps;
+
+pub mod batch;
+pub mod builder;
+pub mod certificate;
+pub mod error;
+pub mod evaluate;
+pub mod exact;
+pub mod format;
+pub mod graph;
+pub mod jobs;
+pub mod limits;
+pub mod matrix;
+pub mod metrics;
+pub mod plot;
+pub mod presolve;
+pub mod progress;
+pub mod report;
+pub mod scaling;
+pub mod server;
+pub mod simplex;
+pub mod stats;
+#[cfg(test)]
+mod tests;
+pub mod text;
+pub mod validate;
+pub mod verify;
+pub mod warm;
+
+pub use alps_macros::alps;
+pub use batch::{solve_batch, BatchItem};
+pub use builder::{LinearExpr, Problem, Var, VarBuilder};
+pub use certificate::{certificate, certify, prove, Certificate, CertificateCheck, CertifyOptions};
+pub use error::AlpsError;
+pub use evaluate::{evaluate, Evaluation};
+pub use exact::{parse_rational, solve_exact, ExactSolution};
+pub use format::{canonicalize, read_model, ModelFormat};
+pub use graph::{to_dot, GraphOptions};
+pub use jobs::{JobError, JobQueue, JobStatus, JobView, JobsConfig};
+pub use limits::{CancelToken, SolveStatus};
+pub use matrix::{solve_matrix, Bound, MatrixProblem, Sense, SparseMatrix};
+pub use metrics::{tokens, Metrics, PeakAllocator};
+pub use plot::plot_svg;
+pub use presolve::{presolve, PresolveOptions, PresolveReport, Presolved};
+pub use progress::{Observer, Phase, SolveEvent};
+pub use report::{explain, ConstraintReport, SolutionReport, TermContribution, VariableReport};
+pub use scaling::{scale, Scaled, ScalingMethod, ScalingReport};
+pub use server::{Server, ServerConfig};
+pub use simplex::{solve_simplex, solve_simplex_from, SimplexSolution};
+pub use stats::{Density, ModelStats, ValueRange};
+pub use text::{parse_alps, to_alps};
+pub use validate::{problem_schema, solution_schema, validate, validate_str, ValidationIssue};
+pub use verify::{
+    parse_assignment, verify, ConstraintCheck, VariableViolation, VerificationReport, VerifyOptions,
+};
+pub use warm::{Basis, VariableStatus, WarmStart, WarmStartReport};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct UnoptimizedProblem {
-    pub variables: HashMap<String, Variable>,
+    /// Variables in declaration order
+    pub variables: IndexMap<String, Variable>,
     pub objective: Objective,
     pub constraints: Vec<Constraint>,
 }
@@ -23,20 +90,26 @@ impl From<&str> for UnoptimizedProblem {
     }
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Variable {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub name: String,
-    pub max: Option<i64>,
-    pub min: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Objective {
     pub goal: String,
     pub expression: String,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Constraint {
     pub name: String,
     pub expression: String,
@@ -268,34 +341,33 @@ pub fn check_valid(tokens: &[InfixToken]) -> bool {
     }
 }
 
-// add To trait to PostfixToken so it can be .to_string()
-impl ToString for PostfixToken {
-    fn to_string(&self) -> String {
+// add Display to PostfixToken so it can be .to_string()
+impl fmt::Display for PostfixToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PostfixToken::Operator(Operator::Add) => "+".to_string(),
-            PostfixToken::Operator(Operator::Sub) => "-".to_string(),
-            PostfixToken::Operator(Operator::Mul) => "*".to_string(),
-            PostfixToken::Operator(Operator::Div) => "/".to_string(),
-            PostfixToken::Operand(value) => value.to_string(),
-            PostfixToken::Variable(name) => name.to_string(),
+            PostfixToken::Operator(Operator::Add) => write!(f, "+"),
+            PostfixToken::Operator(Operator::Sub) => write!(f, "-"),
+            PostfixToken::Operator(Operator::Mul) => write!(f, "*"),
+            PostfixToken::Operator(Operator::Div) => write!(f, "/"),
+            PostfixToken::Operand(value) => write!(f, "{}", value),
+            PostfixToken::Variable(name) => write!(f, "{}", name),
         }
     }
 }
 
-///
-
 pub fn create_variables(
-    variables: HashMap<String, Variable>,
+    variables: IndexMap<String, Variable>,
 ) -> (ProblemVariables, Vec<String>, HashMap<String, GoodVariable>) {
+    let _span = tracing::info_span!("create_variables", variables = variables.len()).entered();
     let mut variable_names = vec![];
     let mut problem_variables = ProblemVariables::new();
     for (name, variable) in variables {
         let mut variable_definition = VariableDefinition::new();
         if let Some(min) = variable.min {
-            variable_definition = variable_definition.min(min as f64);
+            variable_definition = variable_definition.min(min);
         }
         if let Some(max) = variable.max {
-            variable_definition = variable_definition.max(max as f64);
+            variable_definition = variable_definition.max(max);
         }
         problem_variables.add(variable_definition);
         variable_names.push(name);
@@ -316,14 +388,15 @@ pub fn create_variables(
 pub fn parse_postfix_expression(
     postfix_tokens: Vec<&str>,
     variable_hashmap: &HashMap<String, GoodVariable>,
-) -> Expression {
+) -> Result<Expression, AlpsError> {
     let mut stack: Vec<Expression> = Vec::new();
+    let underflow = || AlpsError::Parse("operator is missing an operand".to_string());
 
     for token in postfix_tokens {
         match token {
             "+" | "-" | "*" | "/" => {
-                let right = stack.pop().expect("Stack underflow");
-                let left = stack.pop().expect("Stack underflow");
+                let right = stack.pop().ok_or_else(underflow)?;
+                let left = stack.pop().ok_or_else(underflow)?;
 
                 let result = match token {
                     "+" => left + right,
@@ -350,7 +423,7 @@ pub fn parse_postfix_expression(
                             left.div(right_constant)
                         }
                     }
-                    _ => panic!("Unsupported operator"),
+                    _ => unreachable!(),
                 };
                 stack.push(result);
             }
@@ -358,14 +431,19 @@ pub fn parse_postfix_expression(
                 let operand = if let Some(var) = variable_hashmap.get(token) {
                     Expression::from(*var)
                 } else {
-                    Expression::from(token.parse::<f64>().expect("Failed to parse float"))
+                    let value = token.parse::<f64>().map_err(|_| {
+                        AlpsError::Parse(format!("undeclared variable `{}`", token))
+                    })?;
+                    Expression::from(value)
                 };
                 stack.push(operand);
             }
         }
     }
 
-    stack.pop().expect("No expression was created")
+    stack
+        .pop()
+        .ok_or_else(|| AlpsError::Parse("empty expression".to_string()))
 }
 
 // parse postfix expressions into good_lp expressions
@@ -374,35 +452,51 @@ pub fn parse_postfix_expression(
 pub fn create_expression(
     problem_expression: &str,
     variable_hashmap: &HashMap<String, GoodVariable>,
-) -> Expression {
+) -> Result<Expression, AlpsError> {
     let postfix_tokens: Vec<&str> = problem_expression.split_whitespace().collect();
     parse_postfix_expression(postfix_tokens, variable_hashmap)
 }
 
+// the inequalities a constraint can use, in the order they are searched for
+const INEQUALITIES: [&str; 5] = ["<=", ">=", "==", "<", ">"];
+
+// finds the inequality used by a constraint expression
+pub fn find_inequality(expression: &str) -> Option<&'static str> {
+    INEQUALITIES
+        .iter()
+        .find(|x| expression.contains(*x))
+        .copied()
+}
+
 // similar to parsing the expression but we need to map the variable names to the actual variables
 // and then create the constraints and apply the correct operator
 // handles inequality along with operators
 pub fn create_constraints(
     problem_constraints: &Vec<Constraint>,
     variable_hashmap: &HashMap<String, GoodVariable>,
-) -> Vec<(constraint::Constraint, Expression, Expression)> {
+) -> Result<Vec<(constraint::Constraint, Expression, Expression)>, AlpsError> {
+    let _span = tracing::info_span!(
+        "create_constraints",
+        constraints = problem_constraints.len()
+    )
+    .entered();
     let mut constraints = vec![];
     for constraint in problem_constraints {
         let f = constraint.expression.clone();
 
-        let inequalities = ["<=", ">=", "==", "<", ">"];
-
-        // check that f contains an inequality
-        let contains_inequality = inequalities.iter().any(|x| f.contains(x));
-        if !contains_inequality {
-            panic!("Constraint does not contain an inequality");
-        }
-
         // find the inequality that is in the string
-        let my_inequality = inequalities
-            .iter()
-            .find(|x| f.contains(*x))
-            .expect("Failed to find inequality");
+        let my_inequality = find_inequality(&f).ok_or_else(|| {
+            AlpsError::Parse(format!(
+                "constraint `{}` does not contain an inequality",
+                constraint.name
+            ))
+        })?;
+        let invalid = || {
+            AlpsError::Parse(format!(
+                "constraint `{}` is not a valid expression",
+                constraint.name
+            ))
+        };
 
         // split on the inequality
         let split = f.split(my_inequality).collect::<Vec<&str>>();
@@ -416,8 +510,8 @@ pub fn create_constraints(
         let lhs_postfix = tokenize(&lhs);
         let rhs_postfix = tokenize(&rhs);
 
-        let lhs_expression = infix_to_postfix(&lhs_postfix).unwrap();
-        let rhs_expression = infix_to_postfix(&rhs_postfix).unwrap();
+        let lhs_expression = infix_to_postfix(&lhs_postfix).ok_or_else(invalid)?;
+        let rhs_expression = infix_to_postfix(&rhs_postfix).ok_or_else(invalid)?;
 
         let lhs_postfix_string = lhs_expression
             .iter()
@@ -434,42 +528,72 @@ pub fn create_constraints(
         let lhs_postfix_tokens: Vec<&str> = lhs_postfix_string.split_whitespace().collect();
         let rhs_postfix_tokens: Vec<&str> = rhs_postfix_string.split_whitespace().collect();
 
-        let lhs_expression = parse_postfix_expression(lhs_postfix_tokens, variable_hashmap);
-        let rhs_expression = parse_postfix_expression(rhs_postfix_tokens, variable_hashmap);
+        let lhs_expression = parse_postfix_expression(lhs_postfix_tokens, variable_hashmap)?;
+        let rhs_expression = parse_postfix_expression(rhs_postfix_tokens, variable_hashmap)?;
 
         // use the my_inequality to create the constraint
-        let constraint = match *my_inequality {
+        let constraint = match my_inequality {
             "<=" => good_lp::constraint!(lhs_expression.clone() <= rhs_expression.clone()),
             ">=" => good_lp::constraint!(lhs_expression.clone() >= rhs_expression.clone()),
             "==" => good_lp::constraint!(lhs_expression.clone() == rhs_expression.clone()),
             // throw an error if the inequality is not supported
-            _ => panic!("Unsupported inequality"),
+            unsupported => {
+                return Err(AlpsError::Parse(format!(
+                    "constraint `{}` uses the unsupported inequality `{}`",
+                    constraint.name, unsupported
+                )))
+            }
         };
 
         constraints.push((constraint, lhs_expression, rhs_expression));
     }
-    constraints
+    Ok(constraints)
 }
 
-pub fn parse_objective_expression(objective: &str) -> String {
+pub fn parse_objective_expression(objective: &str) -> Result<String, AlpsError> {
     let objective = add_spaces(objective);
     let original_tokens = tokenize(&objective);
-    let result = infix_to_postfix(&original_tokens).unwrap();
+    let result = infix_to_postfix(&original_tokens).ok_or_else(|| {
+        AlpsError::Parse(format!(
+            "objective `{}` is not a valid expression",
+            objective
+        ))
+    })?;
     let postfix_string = result
         .iter()
         .map(|x| x.to_string())
         .collect::<Vec<String>>()
         .join(" ");
-    postfix_string
+    Ok(postfix_string)
+}
+
+// maps the objective goal onto a solver direction, anything that isn't
+// spelled like a minimisation is treated as a maximisation
+pub fn objective_direction(goal: &str) -> ObjectiveDirection {
+    match goal.trim().to_lowercase().as_str() {
+        "min" | "minimize" | "minimise" => ObjectiveDirection::Minimisation,
+        _ => ObjectiveDirection::Maximisation,
+    }
 }
 
-pub fn solve(problem: UnoptimizedProblem) -> Result<SolutionResponse, Box<dyn Error>> {
+pub fn solve(problem: UnoptimizedProblem) -> Result<SolutionResponse, AlpsError> {
+    minilp(problem, &Stop::default())
+}
+
+// `solve`, telling the observer of `stop` how far it got
+fn minilp(problem: UnoptimizedProblem, stop: &Stop) -> Result<SolutionResponse, AlpsError> {
+    stop.phase(Phase::Parse);
     let (problem_variables, _variable_names, variable_hashmap) =
         create_variables(problem.variables);
-    let parsed_expression = parse_objective_expression(&problem.objective.expression);
-    let expression = create_expression(&parsed_expression, &variable_hashmap);
-    let constraints = create_constraints(&problem.constraints, &variable_hashmap);
-    let mut solution = problem_variables.maximise(expression).using(default_solver);
+    let parsed_expression = parse_objective_expression(&problem.objective.expression)?;
+    let expression = create_expression(&parsed_expression, &variable_hashmap)?;
+    let constraints = create_constraints(&problem.constraints, &variable_hashmap)?;
+    let direction = objective_direction(&problem.objective.goal);
+    // minilp leaves the constant out of its objective value
+    let objective = expression.clone();
+    let mut solution = problem_variables
+        .optimise(direction, expression)
+        .using(default_solver);
 
     let mut exprs = vec![];
     for constraint in constraints {
@@ -477,7 +601,16 @@ pub fn solve(problem: UnoptimizedProblem) -> Result<SolutionResponse, Box<dyn Er
         solution = solution.with(c);
         exprs.push((lhs, rhs));
     }
-    let solution = solution.solve().unwrap();
+    stop.phase(Phase::Lp);
+    let span = tracing::info_span!("solve", solver = "minilp");
+    let solution = stop.watch(move || span.in_scope(|| solution.solve()))??;
+
+    let value = solution.eval(&objective);
+    stop.emit(SolveEvent::Incumbent { objective: value });
+    stop.emit(SolveEvent::Bound { bound: value });
+
+    stop.phase(Phase::Extract);
+    let _span = tracing::info_span!("extract").entered();
 
     let mut values = HashMap::new();
     for var in variable_hashmap.keys() {
@@ -497,6 +630,7 @@ pub fn solve(problem: UnoptimizedProblem) -> Result<SolutionResponse, Box<dyn Er
             expression: constr.expression,
             lhs: lhs_solution_value,
             rhs: rhs_solution_value,
+            dual: None,
         })
     }
 
@@ -509,40 +643,457 @@ pub fn solve(problem: UnoptimizedProblem) -> Result<SolutionResponse, Box<dyn Er
     let num_constraints = const_values.len();
     let serializable_solution = SolutionResponse {
         const_values,
-        objective: sol.objective(),
+        objective: objective.eval_with(&values),
         num_constraints,
         variable_solutions,
+        reduced_costs: None,
+        presolve: None,
+        scaling: None,
+        verification: None,
+        exact: None,
+        certificate: None,
+        basis: None,
+        warm_start: None,
+        status: SolveStatus::Optimal,
+        metrics: None,
     };
 
     Ok(serializable_solution)
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// The algorithm used by [`solve_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Solver {
+    /// minilp through good_lp, what [`solve`] uses
+    #[default]
+    Minilp,
+    /// The built in dense simplex, see [`solve_simplex`]
+    Simplex,
+    /// The same simplex on exact fractions, see [`solve_exact`]
+    Exact,
+}
+
+impl Solver {
+    pub const ALL: [Solver; 3] = [Solver::Minilp, Solver::Simplex, Solver::Exact];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Solver::Minilp => "minilp",
+            Solver::Simplex => "simplex",
+            Solver::Exact => "exact",
+        }
+    }
+}
+
+impl fmt::Display for Solver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for Solver {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Solver::ALL
+            .into_iter()
+            .find(|solver| solver.as_str() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown solver `{}`, expected minilp, simplex or exact",
+                    name
+                )
+            })
+    }
+}
+
+/// Like [`solve`], with a choice of algorithm.
+pub fn solve_with(
+    problem: UnoptimizedProblem,
+    solver: Solver,
+) -> Result<SolutionResponse, AlpsError> {
+    solve_from(problem, solver, None, &Stop::default())
+}
+
+// solves with a warm start for the solvers that take one, minilp is given
+// up on at the time limit or the token and ignores the iteration limit
+fn solve_from(
+    problem: UnoptimizedProblem,
+    solver: Solver,
+    warm_start: Option<&WarmStart>,
+    stop: &Stop,
+) -> Result<SolutionResponse, AlpsError> {
+    match solver {
+        Solver::Minilp => {
+            stop.before_start()?;
+            let mut solution = minilp(problem, stop)?;
+            solution.warm_start = warm_start
+                .map(|_| WarmStartReport::rejected("minilp does not take a starting point"));
+            Ok(solution)
+        }
+        Solver::Exact => exact::solve_exact_from(&problem, warm_start, stop),
+        Solver::Simplex => {
+            stop.phase(Phase::Parse);
+            let matrix = MatrixProblem::try_from(&problem)?;
+            let solution = tracing::info_span!("solve", solver = "simplex")
+                .in_scope(|| simplex_within(&matrix, warm_start, stop))?;
+            stop.phase(Phase::Extract);
+            let _span = tracing::info_span!("extract").entered();
+            let variable_solutions: HashMap<String, f64> = matrix
+                .variables
+                .iter()
+                .cloned()
+                .zip(solution.values)
+                .collect();
+            let mut const_values = constraint_solutions(&problem, &variable_solutions)?;
+            for (constraint, dual) in const_values.iter_mut().zip(solution.duals) {
+                constraint.dual = Some(dual);
+            }
+            Ok(SolutionResponse {
+                num_constraints: const_values.len(),
+                const_values,
+                objective: solution.objective,
+                variable_solutions,
+                reduced_costs: Some(
+                    matrix
+                        .variables
+                        .into_iter()
+                        .zip(solution.reduced_costs)
+                        .collect(),
+                ),
+                presolve: None,
+                scaling: None,
+                verification: None,
+                exact: None,
+                certificate: None,
+                basis: Some(solution.basis),
+                warm_start: solution.warm_start,
+                status: solution.status,
+                metrics: None,
+            })
+        }
+    }
+}
+
+/// How [`solve_with_options`] solves a problem.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SolveOptions {
+    pub solver: Solver,
+    /// Simplify the problem before solving it, see [`presolve`]
+    pub presolve: Option<PresolveOptions>,
+    /// Scale rows and columns before solving, see [`scale`]
+    pub scaling: Option<ScalingMethod>,
+    /// Check the solution against the problem, see [`verify`]
+    pub verify: Option<VerifyOptions>,
+    /// Attach an optimality certificate, see [`certificate`]. Finding its
+    /// duals is held to the same limits, and a solution a limit stopped
+    /// early can't be certified, both are an error
+    pub certificate: bool,
+    /// Start from an earlier solution, used by the simplex and exact solvers
+    /// on the problem as written
+    pub warm_start: Option<WarmStart>,
+    /// Seconds the solve may take. The simplex and exact solvers return the
+    /// best solution found so far when they run out, minilp has none to
+    /// give and fails with [`AlpsError::Stopped`], left to finish on a thread
+    /// of its own. A negative or NaN limit is an error
+    pub time_limit: Option<f64>,
+    /// Pivots the simplex and exact solvers may take
+    pub iteration_limit: Option<usize>,
+    /// Stops the solve from another thread like the time limit does
+    #[serde(skip)]
+    pub cancel: Option<CancelToken>,
+    /// Hears about the phases, pivots and incumbents of the solve
+    #[serde(skip)]
+    pub observer: Option<Observer>,
+    /// Time each stage and count the size of the solve, see [`Metrics`]
+    pub metrics: bool,
+}
+
+/// Like [`solve_with`], optionally presolving and scaling the problem,
+/// verifying the solution, certifying it and measuring the solve. The
+/// solution is mapped back onto the original problem and says what was
+/// removed, which scale factors were applied, what the verification found,
+/// how it can be certified and where the time went.
+pub fn solve_with_options(
+    problem: UnoptimizedProblem,
+    options: &SolveOptions,
+) -> Result<SolutionResponse, AlpsError> {
+    let stop = Stop::new(options)?;
+    if options.verify.is_none() && !options.certificate && !options.metrics {
+        return solve_transformed(problem, options, &stop);
+    }
+    let mut solution = solve_transformed(problem.clone(), options, &stop)?;
+    if let Some(verify_options) = &options.verify {
+        solution.verification = Some(verify(
+            &problem,
+            &solution.variable_solutions,
+            verify_options,
+        )?);
+    }
+    if options.certificate {
+        solution.certificate = Some(certificate_within(&problem, &solution, &stop.limits())?);
+    }
+    // counted after the clock stops
+    solution.metrics = match stop.metrics(options.solver != Solver::Minilp) {
+        Some(metrics) => Some(Metrics {
+            tokens: tokens(&problem),
+            nonzeros: ModelStats::of(&problem)?.nonzeros,
+            ..metrics
+        }),
+        None => None,
+    };
+    Ok(solution)
+}
+
+// presolves and scales as asked, then solves
+fn solve_transformed(
+    problem: UnoptimizedProblem,
+    options: &SolveOptions,
+    stop: &Stop,
+) -> Result<SolutionResponse, AlpsError> {
+    if options.presolve.is_none() && options.scaling.is_none() {
+        return solve_from(problem, options.solver, options.warm_start.as_ref(), stop);
+    }
+
+    stop.phase(Phase::Parse);
+    let matrix = MatrixProblem::try_from(&problem)?;
+    let presolved = options
+        .presolve
+        .map(|presolve_options| {
+            stop.phase(Phase::Presolve);
+            let _span = tracing::info_span!("presolve").entered();
+            presolve(&matrix, &presolve_options)
+        })
+        .transpose()?;
+    let reduced = presolved
+        .as_ref()
+        .map_or(&matrix, |presolved| &presolved.problem);
+    let scaled = options
+        .scaling
+        .map(|method| {
+            stop.phase(Phase::Scaling);
+            let _span = tracing::info_span!("scaling").entered();
+            scale(reduced, method)
+        })
+        .transpose()?;
+    let solved = scaled.as_ref().map_or(reduced, |scaled| &scaled.problem);
+
+    let mut status = SolveStatus::Optimal;
+    let (values, duals) =
+        if solved.variables.is_empty() && solved.senses.is_empty() {
+            (vec![], (options.solver == Solver::Simplex).then(Vec::new))
+        } else {
+            let _span = tracing::info_span!("solve", solver = options.solver.as_str()).entered();
+            match options.solver {
+                Solver::Minilp => {
+                    stop.before_start()?;
+                    stop.phase(Phase::Lp);
+                    let solution = solve_matrix(solved)?;
+                    stop.emit(SolveEvent::Incumbent {
+                        objective: solution.objective,
+                    });
+                    stop.emit(SolveEvent::Bound {
+                        bound: solution.objective,
+                    });
+                    let values = solved
+                        .variables
+                        .iter()
+                        .map(|name| solution.variable_solutions[name])
+                        .collect();
+                    (values, None)
+                }
+                Solver::Simplex => {
+                    let solution = simplex_within(solved, None, stop)?;
+                    status = solution.status;
+                    (solution.values, Some(solution.duals))
+                }
+                Solver::Exact => return Err(AlpsError::InvalidInput(
+                    "the exact solver works on the model as written, without presolve or scaling"
+                        .to_string(),
+                )),
+            }
+        };
+    stop.phase(Phase::Extract);
+    let _span = tracing::info_span!("extract").entered();
+    let (values, duals) = match &scaled {
+        Some(scaled) => scaled.unscale(&values, duals.as_deref()),
+        None => (values, duals),
+    };
+    let (values, duals) = match &presolved {
+        Some(presolved) => presolved.postsolve(&values, duals.as_deref()),
+        None => (values, duals),
+    };
+    // rounding in the scaled or reduced problem can leave a value a hair
+    // past or short of a bound, those go onto it before the constraints
+    // and the objective are evaluated
+    let tolerance = VerifyOptions::default();
+    let values: Vec<f64> = values
+        .into_iter()
+        .zip(&matrix.bounds)
+        .map(|(value, bound)| snap(value, bound, &tolerance))
+        .collect();
+
+    let variable_solutions: HashMap<String, f64> = matrix
+        .variables
+        .iter()
+        .cloned()
+        .zip(values.iter().copied())
+        .collect();
+    let mut const_values = constraint_solutions(&problem, &variable_solutions)?;
+    let reduced_costs = duals.map(|duals| {
+        for (constraint, dual) in const_values.iter_mut().zip(&duals) {
+            constraint.dual = Some(*dual);
+        }
+        let mut reduced_costs = matrix.objective.clone();
+        for (row, entries) in matrix.matrix.row_entries().iter().enumerate() {
+            for (col, value) in entries {
+                reduced_costs[*col] -= value * duals[row];
+            }
+        }
+        matrix
+            .variables
+            .iter()
+            .cloned()
+            .zip(reduced_costs)
+            .collect()
+    });
+    Ok(SolutionResponse {
+        num_constraints: const_values.len(),
+        const_values,
+        objective: matrix.objective_constant
+            + matrix
+                .objective
+                .iter()
+                .zip(&values)
+                .map(|(cost, value)| cost * value)
+                .sum::<f64>(),
+        variable_solutions,
+        reduced_costs,
+        presolve: presolved.map(|presolved| presolved.report),
+        scaling: scaled.map(|scaled| scaled.report),
+        verification: None,
+        exact: None,
+        certificate: None,
+        basis: None,
+        warm_start: options.warm_start.as_ref().map(|_| {
+            WarmStartReport::rejected(
+                "warm starts apply to the problem as written, without presolve or scaling",
+            )
+        }),
+        status,
+        metrics: None,
+    })
+}
+
+// `value` on the bound it is within the tolerance of, if any
+fn snap(value: f64, bound: &Bound, tolerance: &VerifyOptions) -> f64 {
+    [bound.min, bound.max]
+        .into_iter()
+        .flatten()
+        .find(|limit| (value - limit).abs() <= tolerance.tolerance(*limit))
+        .unwrap_or(value)
+}
+
+/// Evaluates both sides of every constraint for the given variable values,
+/// variables without a value count as zero.
+pub fn constraint_solutions(
+    problem: &UnoptimizedProblem,
+    values: &HashMap<String, f64>,
+) -> Result<Vec<ConstraintSolution>, AlpsError> {
+    let (_problem_variables, _variable_names, variable_hashmap) =
+        create_variables(problem.variables.clone());
+    let constraints = create_constraints(&problem.constraints, &variable_hashmap)?;
+    let assignment: HashMap<GoodVariable, f64> = variable_hashmap
+        .iter()
+        .map(|(name, var)| (*var, values.get(name).copied().unwrap_or(0.0)))
+        .collect();
+    Ok(problem
+        .constraints
+        .iter()
+        .zip(constraints)
+        .map(|(constraint, (_, lhs, rhs))| ConstraintSolution {
+            name: constraint.name.clone(),
+            expression: constraint.expression.clone(),
+            lhs: lhs.eval_with(&assignment),
+            rhs: rhs.eval_with(&assignment),
+            dual: None,
+        })
+        .collect())
+}
+
+// like `solve_with_options`, but a panic inside the parser or the solver is
+// reported as an error instead of unwinding into the caller's thread
+pub(crate) fn solve_catching_panics(
+    problem: UnoptimizedProblem,
+    options: &SolveOptions,
+) -> Result<SolutionResponse, AlpsError> {
+    catch_unwind(AssertUnwindSafe(|| solve_with_options(problem, options)))
+        .unwrap_or_else(|_| Err(AlpsError::Solver("the solver panicked".to_string())))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ConstraintSolution {
     pub name: String,
     pub expression: String,
     pub lhs: f64,
     pub rhs: f64,
+    /// Change of the objective per unit increase of the right hand side,
+    /// only reported by solvers that compute it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dual: Option<f64>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct SolutionResponse {
     pub const_values: Vec<ConstraintSolution>,
     pub objective: f64,
     pub num_constraints: usize,
     pub variable_solutions: HashMap<String, f64>,
+    /// Change of the objective per unit increase of each variable, only
+    /// reported by solvers that compute it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduced_costs: Option<HashMap<String, f64>>,
+    /// What presolve removed, when the problem was presolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presolve: Option<PresolveReport>,
+    /// The scale factors, when the problem was scaled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scaling: Option<ScalingReport>,
+    /// What checking the solution found, when it was checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationReport>,
+    /// The solution as fractions, from the exact solver
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exact: Option<ExactSolution>,
+    /// Primal and dual solution with their residuals, when asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<Certificate>,
+    /// The final simplex basis, to warm start the next solve
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub basis: Option<Basis>,
+    /// What became of the warm start, when there was one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warm_start: Option<WarmStartReport>,
+    /// Whether the solution is optimal or the best found before a limit
+    #[serde(default, skip_serializing_if = "SolveStatus::is_optimal")]
+    pub status: SolveStatus,
+    /// Where the time went and how large the solve was, when asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Metrics>,
 }
 
-// add ToString
-impl ToString for SolutionResponse {
-    fn to_string(&self) -> String {
+// add Display so it can be .to_string()
+impl fmt::Display for SolutionResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // use serde_json to convert the struct to a string
-
-        serde_json::to_string(&self).unwrap()
+        let json = serde_json::to_string(&self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", json)
     }
 }
 
-fn add_spaces(input: &str) -> String {
+pub(crate) fn add_spaces(input: &str) -> String {
     let mut result = String::new();
     let mut chars = input.chars().peekable();
 
@@ -552,6 +1103,20 @@ fn add_spaces(input: &str) -> String {
             '0'..='9' | '.' => {
                 result.push(c);
             }
+            // A minus sign directly in front of a number that can't be a
+            // subtraction (start of input, after `(` or another operator)
+            // is kept attached to the number, e.g. `( -1 * b )`
+            '-' if chars.peek().is_some_and(|next| next.is_ascii_digit())
+                && matches!(
+                    result.trim_end().chars().last(),
+                    None | Some('(' | '+' | '-' | '*' | '/' | '<' | '>' | '=')
+                ) =>
+            {
+                if !result.is_empty() && !result.ends_with(' ') {
+                    result.push(' ');
+                }
+                result.push(c);
+            }
             // Check for operators
             '+' | '*' | '<' | '=' | '>' | '-' | '(' | ')' => {
                 if !result.ends_with(' ') {
@@ -0,0 +1,208 @@
+use crate::metrics::{Metrics, Recorder};
+use crate::progress::{Observer, Phase, SolveEvent};
+use crate::{AlpsError, SolveOptions};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+// how often a solve that can't check its limits itself is looked in on
+const POLL: Duration = Duration::from_millis(10);
+
+/// Asks a running solve to stop, from any thread. Clones share the flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// two tokens are the same when they share the flag
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Whether a solution is optimal or the best one found before a limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SolveStatus {
+    #[default]
+    Optimal,
+    /// Feasible, stopped by the time limit
+    TimeLimit,
+    /// Feasible, stopped by the iteration limit
+    IterationLimit,
+    /// Feasible, stopped by a [`CancelToken`]
+    Interrupted,
+}
+
+impl SolveStatus {
+    pub fn is_optimal(&self) -> bool {
+        *self == SolveStatus::Optimal
+    }
+}
+
+impl fmt::Display for SolveStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SolveStatus::Optimal => "optimal",
+            SolveStatus::TimeLimit => "the time limit was reached",
+            SolveStatus::IterationLimit => "the iteration limit was reached",
+            SolveStatus::Interrupted => "the solve was cancelled",
+        })
+    }
+}
+
+// the limits of one solve, who hears about it and what it measures, the
+// clock starts when it is made
+#[derive(Debug, Default)]
+pub(crate) struct Stop {
+    deadline: Option<Instant>,
+    iterations: Option<usize>,
+    cancel: Option<CancelToken>,
+    observer: Option<Observer>,
+    recorder: Option<RefCell<Recorder>>,
+}
+
+impl Stop {
+    // an error when the time limit isn't a number of seconds
+    pub fn new(options: &SolveOptions) -> Result<Stop, AlpsError> {
+        let limit = match options.time_limit {
+            Some(limit) => Some(Duration::try_from_secs_f64(limit).map_err(|_| {
+                AlpsError::InvalidInput(format!(
+                    "the time limit must be a number of seconds, not {}",
+                    limit
+                ))
+            })?),
+            None => None,
+        };
+        Ok(Stop {
+            deadline: limit.map(|limit| Instant::now() + limit),
+            iterations: options.iteration_limit,
+            cancel: options.cancel.clone(),
+            observer: options.observer.clone(),
+            recorder: options.metrics.then(|| RefCell::new(Recorder::start())),
+        })
+    }
+
+    // the same limits and token, without the observer and the measurements,
+    // for work done on the side of the solve
+    pub fn limits(&self) -> Stop {
+        Stop {
+            deadline: self.deadline,
+            iterations: self.iterations,
+            cancel: self.cancel.clone(),
+            ..Stop::default()
+        }
+    }
+
+    // tells the observer and the tracing subscriber, pivots only at trace
+    pub fn emit(&self, event: SolveEvent) {
+        match &event {
+            SolveEvent::Phase { phase } => {
+                tracing::info!("{}", event);
+                if let Some(recorder) = &self.recorder {
+                    recorder.borrow_mut().enter(*phase);
+                }
+            }
+            SolveEvent::Iteration { .. } => {
+                tracing::trace!("{}", event);
+                if let Some(recorder) = &self.recorder {
+                    recorder.borrow_mut().pivot();
+                }
+            }
+            SolveEvent::Incumbent { .. } | SolveEvent::Bound { .. } => {
+                tracing::debug!("{}", event)
+            }
+        }
+        if let Some(observer) = &self.observer {
+            observer.notify(&event);
+        }
+    }
+
+    pub fn phase(&self, phase: Phase) {
+        self.emit(SolveEvent::Phase { phase });
+    }
+
+    // the times so far when they were asked for, with the pivots counted
+    // when the solver reports them
+    pub fn metrics(&self, pivots: bool) -> Option<Metrics> {
+        let recorder = self.recorder.as_ref()?;
+        let metrics = recorder.borrow_mut().finish(pivots);
+        Some(metrics)
+    }
+
+    // why the solve should stop after `iterations` pivots, if it should
+    pub fn check(&self, iterations: usize) -> Option<SolveStatus> {
+        if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+            Some(SolveStatus::Interrupted)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(SolveStatus::TimeLimit)
+        } else if self.iterations.is_some_and(|limit| iterations >= limit) {
+            Some(SolveStatus::IterationLimit)
+        } else {
+            None
+        }
+    }
+
+    // an error when the solve is stopped before anything was found
+    pub fn before_start(&self) -> Result<(), AlpsError> {
+        self.stopped("before the solve started")
+    }
+
+    // an error saying `when` if the time limit or the token stops the solve
+    fn stopped(&self, when: &str) -> Result<(), AlpsError> {
+        match self.check(0) {
+            Some(status @ (SolveStatus::Interrupted | SolveStatus::TimeLimit)) => {
+                Err(AlpsError::Stopped(format!("{} {}", status, when)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // runs `work`, which can't check the limits itself, on a thread of its
+    // own and gives up on it with an error when the time limit or the token
+    // stops the solve. The thread is left to finish in the background
+    pub fn watch<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, AlpsError> {
+        if self.deadline.is_none() && self.cancel.is_none() {
+            return Ok(work());
+        }
+        let (sender, receiver) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            let _ = sender.send(work());
+        });
+        loop {
+            match receiver.recv_timeout(POLL) {
+                Ok(result) => return Ok(result),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    self.stopped("before the solve finished")?
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => match handle.join() {
+                    Err(panic) => std::panic::resume_unwind(panic),
+                    Ok(()) => unreachable!("the result is sent before the thread ends"),
+                },
+            }
+        }
+    }
+}
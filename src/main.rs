@@ -1,10 +1,20 @@
-#![allow(special_module_name)]
-
+use alps::{
+    canonicalize, certify, evaluate, explain, parse_assignment, plot_svg, problem_schema, prove,
+    solution_schema, solve, solve_batch, solve_simplex, solve_with, solve_with_options, to_dot,
+    validate, validate_str, verify, AlpsError, Certificate, CertifyOptions, GraphOptions,
+    JobsConfig, MatrixProblem, ModelFormat, ModelStats, PeakAllocator, PresolveOptions,
+    ScalingMethod, Server, ServerConfig, SolutionReport, SolveOptions, Solver, UnoptimizedProblem,
+    VerifyOptions, WarmStart,
+};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::error::Error;
-mod lib;
-mod tests;
-use clap::Parser;
-use lib::{solve, UnoptimizedProblem};
+use std::io::{BufReader, BufWriter, IsTerminal, Read, Write};
+use std::time::{Duration, Instant};
+
+// counts allocations once `solve --metrics` turns it on, so it can report
+// the peak
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
 
 /// App Configuration
 #[derive(Parser, Debug)]
@@ -12,20 +22,577 @@ use lib::{solve, UnoptimizedProblem};
 struct Args {
     // The name of the file to read from
     #[clap(short, long)]
+    input: Option<String>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+/// Where a model is read from and where the result is written to.
+#[derive(clap::Args, Debug)]
+struct Io {
+    // The model to read, `-` for stdin
+    #[clap(short, long, default_value = "-")]
     input: String,
+    // The format of the model (json, alps or matrix), guessed from the file
+    // extension or the contents when omitted
+    #[clap(long)]
+    from: Option<ModelFormat>,
+    // The file to write to, stdout when omitted
+    #[clap(short, long)]
+    output: Option<String>,
+}
+
+impl Io {
+    // the text of the model and its format
+    fn read_text(&self) -> Result<(String, ModelFormat), Box<dyn Error>> {
+        let mut text = String::new();
+        if self.input == "-" {
+            std::io::stdin().read_to_string(&mut text)?;
+        } else {
+            text = std::fs::read_to_string(&self.input)?;
+        }
+        let format = self
+            .from
+            .or_else(|| ModelFormat::from_path(&self.input))
+            .unwrap_or_else(|| ModelFormat::detect(&text));
+        Ok((text, format))
+    }
+
+    fn read(&self) -> Result<UnoptimizedProblem, Box<dyn Error>> {
+        let (text, format) = self.read_text()?;
+        Ok(format.parse(&text)?)
+    }
+
+    fn write(&self, text: &str) -> Result<(), Box<dyn Error>> {
+        let mut writer = open_output(&self.output)?;
+        writer.write_all(text.as_bytes())?;
+        Ok(writer.flush()?)
+    }
+}
+
+fn open_output(path: &Option<String>) -> std::io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) if path != "-" => Box::new(BufWriter::new(std::fs::File::create(path)?)),
+        _ => Box::new(std::io::stdout().lock()),
+    })
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Solve a model and print the solution
+    Solve {
+        #[clap(flatten)]
+        io: Io,
+        // How the solution is printed
+        #[clap(short, long, value_enum, default_value = "json")]
+        format: OutputFormat,
+        // The algorithm to use, minilp, simplex or exact
+        #[clap(long, default_value_t = Solver::default())]
+        solver: Solver,
+        // Seconds the solve may take, the simplex and exact solvers then
+        // return the best solution so far and minilp fails
+        #[clap(long)]
+        time_limit: Option<f64>,
+        // Pivots the simplex and exact solvers may take
+        #[clap(long)]
+        iteration_limit: Option<usize>,
+        // Simplify the model before solving it and report what was removed
+        #[clap(long)]
+        presolve: bool,
+        // Scale rows and columns before solving, equilibrate or geometric_mean
+        #[clap(long)]
+        scaling: Option<ScalingMethod>,
+        // Check the solution against the model with the default tolerances
+        #[clap(long)]
+        verify: bool,
+        // Write a certificate of optimality, or of infeasibility when the
+        // model has no solution, to this file
+        #[clap(long)]
+        certificate: Option<String>,
+        // Start from an earlier solution as `solve` printed it, its basis
+        // or variable values, used by the simplex and exact solvers
+        #[clap(long)]
+        warm_start: Option<String>,
+        // Log the stages of the solve to stderr, -vv adds incumbents and
+        // -vvv every pivot
+        #[clap(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+        // Add the time each stage took, the size of the model and the peak
+        // allocation to the solution
+        #[clap(long)]
+        metrics: bool,
+    },
+    /// Check a model and report every issue found, without solving it
+    Validate {
+        #[clap(flatten)]
+        io: Io,
+    },
+    /// Check a solution against a model and report every violation
+    Check {
+        #[clap(flatten)]
+        io: Io,
+        // The solution: JSON as `solve` prints it, a JSON object of values,
+        // or `name,value` lines
+        #[clap(long)]
+        solution: String,
+        // Violations up to this much are accepted
+        #[clap(long, default_value = "1e-6")]
+        absolute: f64,
+        // On top of --absolute, this share of the size of each side is accepted
+        #[clap(long, default_value = "1e-9")]
+        relative: f64,
+        // A variable that must take a whole value, can be repeated
+        #[clap(long = "integer")]
+        integers: Vec<String>,
+        // Print the report as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Check a certificate written by `solve --certificate` against a model
+    Certify {
+        #[clap(flatten)]
+        io: Io,
+        // The certificate file
+        #[clap(long)]
+        certificate: String,
+        // Residuals and gap up to this much are accepted
+        #[clap(long, default_value = "1e-6")]
+        absolute: f64,
+        // On top of --absolute, this share of the size of the model is accepted
+        #[clap(long, default_value = "1e-9")]
+        relative: f64,
+        // Print the check as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Score a plan, a partial or complete assignment, against a model
+    Evaluate {
+        #[clap(flatten)]
+        io: Io,
+        // The plan: `name = value` or `name,value` lines, or a JSON object
+        // of values
+        #[clap(long)]
+        plan: String,
+        // Violations up to this much are accepted
+        #[clap(long, default_value = "1e-6")]
+        absolute: f64,
+        // On top of --absolute, this share of the size of each side is accepted
+        #[clap(long, default_value = "1e-9")]
+        relative: f64,
+        // A variable that must take a whole value, can be repeated
+        #[clap(long = "integer")]
+        integers: Vec<String>,
+        // Print the evaluation as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Translate a model between the json, alps and matrix formats
+    Convert {
+        #[clap(flatten)]
+        io: Io,
+        // The format to write
+        #[clap(long)]
+        to: ModelFormat,
+    },
+    /// Rewrite a model in its canonical form, keeping its format
+    Fmt {
+        #[clap(flatten)]
+        io: Io,
+    },
+    /// Print a summary of the size and shape of a model
+    Stats {
+        #[clap(flatten)]
+        io: Io,
+        // Print the summary as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Solve a model and print a report meant for people
+    Explain {
+        #[clap(flatten)]
+        io: Io,
+        // The algorithm to use, minilp, simplex or exact
+        #[clap(long, default_value_t = Solver::default())]
+        solver: Solver,
+    },
+    /// Draw the feasible region of a two-variable model as an SVG
+    Plot {
+        #[clap(flatten)]
+        io: Io,
+    },
+    /// Print which constraints use which variables as a Graphviz DOT graph
+    Graph {
+        #[clap(flatten)]
+        io: Io,
+        // Only draw constraints and variables whose name matches this regex,
+        // with their neighbours
+        #[clap(long)]
+        filter: Option<regex::Regex>,
+        // Solve with the simplex and colour constraints by binding and
+        // variables by basic status
+        #[clap(long)]
+        status: bool,
+    },
+    /// Solve newline-delimited problems in parallel, one result line per request
+    Batch {
+        // The file to read requests from, `-` for stdin
+        #[clap(short, long, default_value = "-")]
+        input: String,
+        // The file to write results to, stdout when omitted
+        #[clap(short, long)]
+        output: Option<String>,
+        // Number of worker threads, defaults to the number of CPUs
+        #[clap(short, long)]
+        threads: Option<usize>,
+    },
+    /// Serve solve and validate as a JSON API over HTTP
+    Serve {
+        // The address to listen on
+        #[clap(long, default_value = "127.0.0.1")]
+        host: String,
+        #[clap(short, long, default_value_t = 8080)]
+        port: u16,
+        // Largest accepted request body in bytes
+        #[clap(long, default_value_t = 4 * 1024 * 1024)]
+        max_body_bytes: usize,
+        // The algorithm to use, minilp, simplex or exact
+        #[clap(long, default_value_t = Solver::default())]
+        solver: Solver,
+        // Seconds a single solve may take, 0 for no limit
+        #[clap(long, default_value_t = 60)]
+        time_limit: u64,
+        // Number of requests handled at the same time
+        #[clap(short, long, default_value_t = 4)]
+        threads: usize,
+        // Number of background jobs solved at the same time
+        #[clap(long, default_value_t = 2)]
+        job_workers: usize,
+        // Most jobs waiting for a worker
+        #[clap(long, default_value_t = 64)]
+        max_queued_jobs: usize,
+        // Most finished jobs kept, the oldest are forgotten first
+        #[clap(long, default_value_t = 256)]
+        max_finished_jobs: usize,
+        // Seconds a background job may take, 0 for no limit
+        #[clap(long, default_value_t = 0)]
+        job_time_limit: u64,
+        // Directory to persist jobs to, so queued jobs survive a restart
+        #[clap(long)]
+        jobs_dir: Option<std::path::PathBuf>,
+    },
+    /// Print the JSON Schema of the problem or solution format
+    Schema {
+        #[clap(value_enum, default_value = "problem")]
+        format: SchemaFormat,
+        // The file to write to, stdout when omitted
+        #[clap(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    Json,
+    Pretty,
+    // the report printed by `explain`
+    Table,
+    // variables, then constraints, as two CSV sections
+    Csv,
+    // the report as Markdown tables
+    Markdown,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum SchemaFormat {
+    Problem,
+    Solution,
+}
+
+// the log view of `solve --verbose`, with the time each stage took
+fn log_to_stderr(verbose: u8) {
+    let level = match verbose {
+        1 => tracing::Level::INFO,
+        2 => tracing::Level::DEBUG,
+        _ => tracing::Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_max_level(level)
+        .with_target(false)
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .init();
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
 
-    let json_problem = std::fs::read_to_string(args.input)?;
+    match args.command {
+        Some(Command::Solve {
+            io,
+            format,
+            solver,
+            time_limit,
+            iteration_limit,
+            presolve,
+            scaling,
+            verify,
+            certificate,
+            warm_start,
+            verbose,
+            metrics,
+        }) => {
+            if verbose > 0 {
+                log_to_stderr(verbose);
+            }
+            if metrics {
+                PeakAllocator::track();
+            }
+            let reading = Instant::now();
+            let problem = io.read()?;
+            let parse = reading.elapsed().as_secs_f64();
+            let warm_start = match warm_start {
+                Some(path) => Some(WarmStart::parse(&std::fs::read_to_string(path)?)?),
+                None => None,
+            };
+            let options = SolveOptions {
+                solver,
+                presolve: presolve.then(PresolveOptions::default),
+                scaling,
+                verify: verify.then(VerifyOptions::default),
+                certificate: certificate.is_some(),
+                warm_start,
+                time_limit,
+                iteration_limit,
+                cancel: None,
+                observer: None,
+                metrics,
+            };
+            let mut solution = match solve_with_options(problem.clone(), &options) {
+                Ok(solution) => solution,
+                Err(error) => {
+                    // an infeasible model still gets its certificate
+                    if let (Some(path), AlpsError::Infeasible) = (&certificate, &error) {
+                        let proof = prove(&problem)?;
+                        std::fs::write(path, serde_json::to_string_pretty(&proof)? + "\n")?;
+                    }
+                    return Err(error.into());
+                }
+            };
+            if let Some(metrics) = &mut solution.metrics {
+                metrics.parse = Some(parse);
+            }
+            if let (Some(path), Some(proof)) = (&certificate, solution.certificate.take()) {
+                std::fs::write(path, serde_json::to_string_pretty(&proof)? + "\n")?;
+            }
+            if !solution.status.is_optimal() {
+                eprintln!("{}, the solution is the best found so far", solution.status);
+            }
+            if let Some(report) = solution.verification.as_ref().filter(|r| !r.feasible) {
+                eprint!("{}", report);
+            }
+            let text = match format {
+                OutputFormat::Json => serde_json::to_string(&solution)? + "\n",
+                OutputFormat::Pretty => serde_json::to_string_pretty(&solution)? + "\n",
+                OutputFormat::Table => explain(&problem, &solution)?,
+                OutputFormat::Csv => SolutionReport::new(&problem, &solution)?.to_csv(),
+                OutputFormat::Markdown => SolutionReport::new(&problem, &solution)?.to_markdown(),
+            };
+            io.write(&text)?;
+        }
+        Some(Command::Validate { io }) => {
+            // json is checked as written, other formats once they are read
+            let issues = match io.read_text()? {
+                (text, ModelFormat::Json) => validate_str(&text),
+                (text, format) => validate(&serde_json::to_value(format.parse(&text)?)?),
+            };
+            let text: String = issues.iter().map(|issue| format!("{}\n", issue)).collect();
+            io.write(&text)?;
+            if !issues.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Check {
+            io,
+            solution,
+            absolute,
+            relative,
+            integers,
+            json,
+        }) => {
+            let problem = io.read()?;
+            let values = parse_assignment(&std::fs::read_to_string(solution)?)?;
+            let options = VerifyOptions {
+                absolute,
+                relative,
+                integers,
+            };
+            let report = verify(&problem, &values, &options)?;
+            let text = if json {
+                serde_json::to_string_pretty(&report)? + "\n"
+            } else {
+                report.to_string()
+            };
+            io.write(&text)?;
+            if !report.feasible {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Certify {
+            io,
+            certificate,
+            absolute,
+            relative,
+            json,
+        }) => {
+            let problem = io.read()?;
+            let certificate: Certificate =
+                serde_json::from_str(&std::fs::read_to_string(certificate)?)?;
+            let options = CertifyOptions { absolute, relative };
+            let check = certify(&problem, &certificate, &options)?;
+            let text = if json {
+                serde_json::to_string_pretty(&check)? + "\n"
+            } else {
+                check.to_string()
+            };
+            io.write(&text)?;
+            if !check.valid {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Evaluate {
+            io,
+            plan,
+            absolute,
+            relative,
+            integers,
+            json,
+        }) => {
+            let problem = io.read()?;
+            let values = parse_assignment(&std::fs::read_to_string(plan)?)?;
+            let options = VerifyOptions {
+                absolute,
+                relative,
+                integers,
+            };
+            let evaluation = evaluate(&problem, &values, &options)?;
+            let text = if json {
+                serde_json::to_string_pretty(&evaluation)? + "\n"
+            } else {
+                evaluation.to_string()
+            };
+            io.write(&text)?;
+        }
+        Some(Command::Convert { io, to }) => {
+            io.write(&to.write(&io.read()?)?)?;
+        }
+        Some(Command::Fmt { io }) => {
+            let (text, format) = io.read_text()?;
+            io.write(&format.write(&canonicalize(&format.parse(&text)?))?)?;
+        }
+        Some(Command::Stats { io, json }) => {
+            let stats = ModelStats::of(&io.read()?)?;
+            let text = if json {
+                serde_json::to_string_pretty(&stats)? + "\n"
+            } else {
+                stats.to_string()
+            };
+            io.write(&text)?;
+        }
+        Some(Command::Explain { io, solver }) => {
+            let problem = io.read()?;
+            let solution = solve_with(problem.clone(), solver)?;
+            io.write(&explain(&problem, &solution)?)?;
+        }
+        Some(Command::Plot { io }) => {
+            io.write(&plot_svg(&io.read()?)?)?;
+        }
+        Some(Command::Graph { io, filter, status }) => {
+            let matrix = MatrixProblem::try_from(&io.read()?)?;
+            let solution = if status {
+                Some(solve_simplex(&matrix)?)
+            } else {
+                None
+            };
+            io.write(&to_dot(&matrix, &GraphOptions { filter, solution }))?;
+        }
+        Some(Command::Batch {
+            input,
+            output,
+            threads,
+        }) => {
+            let threads = threads.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, |threads| threads.get())
+            });
+            let reader: Box<dyn std::io::BufRead> = if input == "-" {
+                Box::new(std::io::stdin().lock())
+            } else {
+                Box::new(BufReader::new(std::fs::File::open(input)?))
+            };
+            let mut writer = open_output(&output)?;
+            let failed = solve_batch(reader, &mut writer, threads)?;
+            if failed > 0 {
+                eprintln!("{} request(s) failed", failed);
+            }
+        }
+        Some(Command::Serve {
+            host,
+            port,
+            max_body_bytes,
+            solver,
+            time_limit,
+            threads,
+            job_workers,
+            max_queued_jobs,
+            max_finished_jobs,
+            job_time_limit,
+            jobs_dir,
+        }) => {
+            let limit = |seconds: u64| (seconds > 0).then(|| Duration::from_secs(seconds));
+            let config = ServerConfig {
+                max_body_bytes,
+                solver,
+                time_limit: limit(time_limit),
+                threads,
+                jobs: JobsConfig {
+                    workers: job_workers,
+                    max_queued: max_queued_jobs,
+                    max_finished: max_finished_jobs,
+                    directory: jobs_dir,
+                    solver,
+                    time_limit: limit(job_time_limit),
+                },
+            };
+            let server = Server::bind(&format!("{}:{}", host, port), config)?;
+            if let Some(address) = server.local_addr() {
+                eprintln!("listening on http://{}", address);
+            }
+            server.run();
+        }
+        Some(Command::Schema { format, output }) => {
+            let schema = match format {
+                SchemaFormat::Problem => problem_schema(),
+                SchemaFormat::Solution => solution_schema(),
+            };
+            let mut writer = open_output(&output)?;
+            writeln!(writer, "{}", serde_json::to_string_pretty(&schema)?)?;
+            writer.flush()?;
+        }
+        None => {
+            let Some(input) = args.input else {
+                return Err("either --input or a subcommand is required".into());
+            };
+            let json_problem = std::fs::read_to_string(input)?;
 
-    let problem: UnoptimizedProblem = serde_json::from_str(&json_problem).unwrap();
+            let problem: UnoptimizedProblem = serde_json::from_str(&json_problem).unwrap();
 
-    let solution = solve(problem).unwrap();
+            let solution = solve(problem).unwrap();
 
-    let solution = serde_json::to_string(&solution).unwrap();
-    println!("{}", solution);
+            let solution = serde_json::to_string(&solution).unwrap();
+            println!("{}", solution);
+        }
+    }
 
     Ok(())
 }
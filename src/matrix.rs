@@ -0,0 +1,569 @@
+use crate::{
+    create_constraints, create_expression, create_variables, find_inequality, objective_direction,
+    parse_objective_expression, AlpsError, Constraint, ConstraintSolution, Objective,
+    SolutionResponse, SolveStatus, UnoptimizedProblem, Variable,
+};
+use good_lp::{default_solver, Expression, IntoAffineExpression, Solution, SolverModel};
+use good_lp::{ProblemVariables, Variable as GoodVariable, VariableDefinition};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The direction of a constraint row, `lhs <sense> rhs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sense {
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "==")]
+    Eq,
+}
+
+impl Sense {
+    /// The operator used for this sense in expression strings.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sense::Le => "<=",
+            Sense::Ge => ">=",
+            Sense::Eq => "==",
+        }
+    }
+
+    /// Parses one of the supported constraint operators.
+    pub fn from_operator(operator: &str) -> Option<Sense> {
+        match operator {
+            "<=" => Some(Sense::Le),
+            ">=" => Some(Sense::Ge),
+            "==" => Some(Sense::Eq),
+            _ => None,
+        }
+    }
+}
+
+/// Lower and upper bound of a column, `None` meaning unbounded.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bound {
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+}
+
+/// A sparse constraint matrix, either as `(row, col, value)` triplets or in
+/// compressed sparse row form.
+///
+/// Triplets may repeat a position, repeated values are summed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum SparseMatrix {
+    Triplet {
+        rows: usize,
+        cols: usize,
+        entries: Vec<(usize, usize, f64)>,
+    },
+    Csr {
+        rows: usize,
+        cols: usize,
+        row_ptr: Vec<usize>,
+        col_idx: Vec<usize>,
+        values: Vec<f64>,
+    },
+}
+
+impl SparseMatrix {
+    /// Builds a triplet matrix from dense rows, dropping zeros.
+    pub fn from_dense(dense: &[Vec<f64>], cols: usize) -> SparseMatrix {
+        let mut entries = vec![];
+        for (row, values) in dense.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                if *value != 0.0 {
+                    entries.push((row, col, *value));
+                }
+            }
+        }
+        SparseMatrix::Triplet {
+            rows: dense.len(),
+            cols,
+            entries,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        match self {
+            SparseMatrix::Triplet { rows, .. } | SparseMatrix::Csr { rows, .. } => *rows,
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        match self {
+            SparseMatrix::Triplet { cols, .. } | SparseMatrix::Csr { cols, .. } => *cols,
+        }
+    }
+
+    /// Checks that every index is inside the declared shape.
+    pub fn check(&self) -> Result<(), AlpsError> {
+        match self {
+            SparseMatrix::Triplet {
+                rows,
+                cols,
+                entries,
+            } => {
+                for (row, col, _) in entries {
+                    if row >= rows || col >= cols {
+                        return Err(AlpsError::InvalidModel(format!(
+                            "entry ({}, {}) is outside of a {}x{} matrix",
+                            row, col, rows, cols
+                        )));
+                    }
+                }
+            }
+            SparseMatrix::Csr {
+                rows,
+                cols,
+                row_ptr,
+                col_idx,
+                values,
+            } => {
+                if row_ptr.len() != rows + 1 {
+                    return Err(AlpsError::InvalidModel(format!(
+                        "row_ptr has {} entries, expected {}",
+                        row_ptr.len(),
+                        rows + 1
+                    )));
+                }
+                if col_idx.len() != values.len() {
+                    return Err(AlpsError::InvalidModel(
+                        "col_idx and values have different lengths".to_string(),
+                    ));
+                }
+                if row_ptr.windows(2).any(|w| w[0] > w[1]) || row_ptr.last() != Some(&values.len())
+                {
+                    return Err(AlpsError::InvalidModel(
+                        "row_ptr is not a valid row pointer".to_string(),
+                    ));
+                }
+                if let Some(col) = col_idx.iter().find(|col| *col >= cols) {
+                    return Err(AlpsError::InvalidModel(format!(
+                        "column {} is outside of {} columns",
+                        col, cols
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The nonzeros of every row, ordered by column with duplicates summed.
+    pub fn row_entries(&self) -> Vec<Vec<(usize, f64)>> {
+        let mut rows: Vec<Vec<(usize, f64)>> = vec![vec![]; self.rows()];
+        match self {
+            SparseMatrix::Triplet { entries, .. } => {
+                for (row, col, value) in entries {
+                    rows[*row].push((*col, *value));
+                }
+            }
+            SparseMatrix::Csr {
+                row_ptr,
+                col_idx,
+                values,
+                ..
+            } => {
+                for (row, entries) in rows.iter_mut().enumerate() {
+                    for k in row_ptr[row]..row_ptr[row + 1] {
+                        entries.push((col_idx[k], values[k]));
+                    }
+                }
+            }
+        }
+        for entries in rows.iter_mut() {
+            entries.sort_by_key(|(col, _)| *col);
+            let mut merged: Vec<(usize, f64)> = vec![];
+            for (col, value) in entries.drain(..) {
+                match merged.last_mut() {
+                    Some((last, sum)) if *last == col => *sum += value,
+                    _ => merged.push((col, value)),
+                }
+            }
+            merged.retain(|(_, value)| *value != 0.0);
+            *entries = merged;
+        }
+        rows
+    }
+
+    /// The same matrix in compressed sparse row form.
+    pub fn to_csr(&self) -> SparseMatrix {
+        let mut row_ptr = vec![0];
+        let mut col_idx = vec![];
+        let mut values = vec![];
+        for entries in self.row_entries() {
+            for (col, value) in entries {
+                col_idx.push(col);
+                values.push(value);
+            }
+            row_ptr.push(values.len());
+        }
+        SparseMatrix::Csr {
+            rows: self.rows(),
+            cols: self.cols(),
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+
+    /// The same matrix as `(row, col, value)` triplets.
+    pub fn to_triplet(&self) -> SparseMatrix {
+        let mut entries = vec![];
+        for (row, row_entries) in self.row_entries().into_iter().enumerate() {
+            for (col, value) in row_entries {
+                entries.push((row, col, value));
+            }
+        }
+        SparseMatrix::Triplet {
+            rows: self.rows(),
+            cols: self.cols(),
+            entries,
+        }
+    }
+}
+
+/// A problem in matrix form:
+///
+/// ```text
+/// goal   objective · x + objective_constant
+/// s.t.   matrix x <senses> rhs
+///        bounds.min <= x <= bounds.max
+/// ```
+///
+/// Columns are named by `variables` and rows by `constraint_names`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatrixProblem {
+    pub variables: Vec<String>,
+    pub goal: String,
+    pub objective: Vec<f64>,
+    #[serde(default)]
+    pub objective_constant: f64,
+    pub matrix: SparseMatrix,
+    pub senses: Vec<Sense>,
+    pub rhs: Vec<f64>,
+    pub bounds: Vec<Bound>,
+    pub constraint_names: Vec<String>,
+}
+
+impl MatrixProblem {
+    /// Checks that all the vectors agree with the shape of the matrix.
+    pub fn check(&self) -> Result<(), AlpsError> {
+        self.matrix.check()?;
+        let (rows, cols) = (self.matrix.rows(), self.matrix.cols());
+        let lengths = [
+            ("variables", self.variables.len(), cols),
+            ("objective", self.objective.len(), cols),
+            ("bounds", self.bounds.len(), cols),
+            ("senses", self.senses.len(), rows),
+            ("rhs", self.rhs.len(), rows),
+            ("constraint_names", self.constraint_names.len(), rows),
+        ];
+        for (field, length, expected) in lengths {
+            if length != expected {
+                return Err(AlpsError::InvalidModel(format!(
+                    "`{}` has {} entries but the matrix needs {}",
+                    field, length, expected
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The expression string of a row, e.g. `12 * bagels + 6.5 * doughnuts <= 400`.
+    pub fn row_expression(&self, entries: &[(usize, f64)], row: usize) -> String {
+        format!(
+            "{} {} {}",
+            linear_expression(entries, &self.variables, 0.0),
+            self.senses[row].as_str(),
+            format_number(self.rhs[row])
+        )
+    }
+}
+
+// formats a number so that the tokenizer reads it back unchanged,
+// f64's Display never uses an exponent
+fn format_number(value: f64) -> String {
+    format!("{}", value)
+}
+
+// writes `sum(value * name) + constant` using only the operators the
+// expression parser understands
+fn linear_expression(entries: &[(usize, f64)], names: &[String], constant: f64) -> String {
+    let mut expression = String::new();
+    for (col, value) in entries {
+        let magnitude = value.abs();
+        let term = if magnitude == 1.0 {
+            names[*col].clone()
+        } else {
+            format!("{} * {}", format_number(magnitude), names[*col])
+        };
+        if expression.is_empty() {
+            // a leading minus is only understood when attached to a number
+            if *value < 0.0 {
+                let term = format!("-{} * {}", format_number(magnitude), names[*col]);
+                expression.push_str(&term);
+            } else {
+                expression.push_str(&term);
+            }
+        } else if *value < 0.0 {
+            expression.push_str(&format!(" - {}", term));
+        } else {
+            expression.push_str(&format!(" + {}", term));
+        }
+    }
+    if expression.is_empty() {
+        return format_number(constant);
+    }
+    if constant > 0.0 {
+        expression.push_str(&format!(" + {}", format_number(constant)));
+    } else if constant < 0.0 {
+        expression.push_str(&format!(" - {}", format_number(-constant)));
+    }
+    expression
+}
+
+// collects the coefficients of an expression by column, returning the constant
+fn coefficients(
+    expression: Expression,
+    columns: &HashMap<GoodVariable, usize>,
+    row: &mut HashMap<usize, f64>,
+    sign: f64,
+) -> f64 {
+    let constant = expression.constant();
+    for (var, value) in expression.linear_coefficients() {
+        *row.entry(columns[&var]).or_insert(0.0) += sign * value;
+    }
+    sign * constant
+}
+
+fn sorted_entries(row: HashMap<usize, f64>) -> Vec<(usize, f64)> {
+    let mut entries: Vec<(usize, f64)> = row.into_iter().filter(|(_, v)| *v != 0.0).collect();
+    entries.sort_by_key(|(col, _)| *col);
+    entries
+}
+
+impl TryFrom<&UnoptimizedProblem> for MatrixProblem {
+    type Error = AlpsError;
+
+    /// Parses every expression and collects the coefficients, constraints
+    /// are rewritten as `lhs - rhs <sense> -constant`.
+    fn try_from(problem: &UnoptimizedProblem) -> Result<Self, Self::Error> {
+        let names: Vec<String> = problem.variables.keys().cloned().collect();
+        let (_problem_variables, _variable_names, variable_hashmap) =
+            create_variables(problem.variables.clone());
+
+        let columns: HashMap<GoodVariable, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(col, name)| (variable_hashmap[name], col))
+            .collect();
+        let bounds = names
+            .iter()
+            .map(|name| Bound {
+                min: problem.variables[name].min,
+                max: problem.variables[name].max,
+            })
+            .collect();
+
+        let parsed_expression = parse_objective_expression(&problem.objective.expression)?;
+        let objective_expression = create_expression(&parsed_expression, &variable_hashmap)?;
+        let mut objective_row = HashMap::new();
+        let objective_constant =
+            coefficients(objective_expression, &columns, &mut objective_row, 1.0);
+        let mut objective = vec![0.0; names.len()];
+        for (col, value) in objective_row {
+            objective[col] = value;
+        }
+
+        let constraints = create_constraints(&problem.constraints, &variable_hashmap)?;
+        let mut entries = vec![];
+        let mut senses = vec![];
+        let mut rhs = vec![];
+        let mut constraint_names = vec![];
+        for (index, (_, lhs, right)) in constraints.into_iter().enumerate() {
+            let constraint = &problem.constraints[index];
+            let sense = find_inequality(&constraint.expression)
+                .and_then(Sense::from_operator)
+                .ok_or_else(|| {
+                    AlpsError::Parse(format!("unsupported inequality in `{}`", constraint.name))
+                })?;
+            let mut row = HashMap::new();
+            let constant = coefficients(lhs, &columns, &mut row, 1.0)
+                + coefficients(right, &columns, &mut row, -1.0);
+            for (col, value) in sorted_entries(row) {
+                entries.push((index, col, value));
+            }
+            senses.push(sense);
+            rhs.push(-constant);
+            constraint_names.push(constraint.name.clone());
+        }
+
+        Ok(MatrixProblem {
+            matrix: SparseMatrix::Triplet {
+                rows: senses.len(),
+                cols: names.len(),
+                entries,
+            },
+            variables: names,
+            goal: problem.objective.goal.clone(),
+            objective,
+            objective_constant,
+            senses,
+            rhs,
+            bounds,
+            constraint_names,
+        })
+    }
+}
+
+impl TryFrom<&MatrixProblem> for UnoptimizedProblem {
+    type Error = AlpsError;
+
+    /// Writes the matrix back as expression strings, one term per nonzero,
+    /// after checking that its parts fit together.
+    fn try_from(matrix: &MatrixProblem) -> Result<Self, Self::Error> {
+        matrix.check()?;
+        let variables = matrix
+            .variables
+            .iter()
+            .zip(&matrix.bounds)
+            .map(|(name, bound)| {
+                let variable = Variable {
+                    name: name.clone(),
+                    min: bound.min,
+                    max: bound.max,
+                };
+                (name.clone(), variable)
+            })
+            .collect();
+
+        let objective_entries: Vec<(usize, f64)> = matrix
+            .objective
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != 0.0)
+            .map(|(col, value)| (col, *value))
+            .collect();
+        let objective = Objective {
+            goal: matrix.goal.clone(),
+            expression: linear_expression(
+                &objective_entries,
+                &matrix.variables,
+                matrix.objective_constant,
+            ),
+        };
+
+        let constraints = matrix
+            .matrix
+            .row_entries()
+            .iter()
+            .enumerate()
+            .map(|(row, entries)| Constraint {
+                name: matrix.constraint_names[row].clone(),
+                expression: matrix.row_expression(entries, row),
+            })
+            .collect();
+
+        Ok(UnoptimizedProblem {
+            variables,
+            objective,
+            constraints,
+        })
+    }
+}
+
+/// Solves a problem in matrix form without going through expression strings.
+///
+/// The `const_values` of the response report each row as
+/// `matrix x <sense> rhs`.
+pub fn solve_matrix(problem: &MatrixProblem) -> Result<SolutionResponse, AlpsError> {
+    problem.check()?;
+
+    let mut problem_variables = ProblemVariables::new();
+    let mut columns = vec![];
+    for bound in &problem.bounds {
+        let mut variable_definition = VariableDefinition::new();
+        if let Some(min) = bound.min {
+            variable_definition = variable_definition.min(min);
+        }
+        if let Some(max) = bound.max {
+            variable_definition = variable_definition.max(max);
+        }
+        columns.push(problem_variables.add(variable_definition));
+    }
+
+    let mut objective = Expression::from(problem.objective_constant);
+    for (col, value) in problem.objective.iter().enumerate() {
+        if *value != 0.0 {
+            objective.add_mul(*value, columns[col]);
+        }
+    }
+
+    let rows = problem.matrix.row_entries();
+    let mut expressions = vec![];
+    let direction = objective_direction(&problem.goal);
+    let mut model = problem_variables
+        .optimise(direction, objective)
+        .using(default_solver);
+    for (row, entries) in rows.iter().enumerate() {
+        let mut lhs = Expression::with_capacity(entries.len());
+        for (col, value) in entries {
+            lhs.add_mul(*value, columns[*col]);
+        }
+        let rhs = problem.rhs[row];
+        let constraint = match problem.senses[row] {
+            Sense::Le => lhs.clone().leq(rhs),
+            Sense::Ge => lhs.clone().geq(rhs),
+            Sense::Eq => lhs.clone().eq(rhs),
+        };
+        model = model.with(constraint);
+        expressions.push(lhs);
+    }
+    let solution = model.solve()?;
+
+    let mut variable_solutions = HashMap::new();
+    for (col, name) in problem.variables.iter().enumerate() {
+        variable_solutions.insert(name.clone(), solution.value(columns[col]));
+    }
+
+    let mut const_values = vec![];
+    for (row, lhs) in expressions.iter().enumerate() {
+        const_values.push(ConstraintSolution {
+            name: problem.constraint_names[row].clone(),
+            expression: problem.row_expression(&rows[row], row),
+            lhs: lhs.eval_with(&solution),
+            rhs: problem.rhs[row],
+            dual: None,
+        });
+    }
+
+    let objective = problem.objective_constant
+        + problem
+            .objective
+            .iter()
+            .enumerate()
+            .map(|(col, value)| value * solution.value(columns[col]))
+            .sum::<f64>();
+
+    Ok(SolutionResponse {
+        num_constraints: const_values.len(),
+        const_values,
+        objective,
+        variable_solutions,
+        reduced_costs: None,
+        presolve: None,
+        scaling: None,
+        verification: None,
+        exact: None,
+        certificate: None,
+        basis: None,
+        warm_start: None,
+        status: SolveStatus::Optimal,
+        metrics: None,
+    })
+}
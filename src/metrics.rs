@@ -0,0 +1,233 @@
+use crate::progress::Phase;
+use crate::{add_spaces, tokenize, UnoptimizedProblem};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Where the time of a solve went and how large it was, in
+/// [`SolutionResponse::metrics`](crate::SolutionResponse::metrics) when
+/// [`SolveOptions::metrics`](crate::SolveOptions::metrics) asks for it.
+/// Times are in seconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Metrics {
+    /// Reading the model text, known when alps read it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse: Option<f64>,
+    /// Turning the expressions into the solver's model
+    pub build: f64,
+    /// Presolving and scaling
+    pub presolve: f64,
+    pub solve: f64,
+    /// Mapping the solution back onto the model, evaluating the constraints,
+    /// verifying and certifying
+    pub postprocess: f64,
+    /// Tokens in the objective and the constraints, inequalities included
+    pub tokens: usize,
+    /// Nonzero coefficients in the constraints
+    pub nonzeros: usize,
+    /// Pivots of the simplex and exact solvers, minilp doesn't report them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<usize>,
+    /// Most bytes allocated at once during the solve on top of what was
+    /// allocated before it, known when the program installs
+    /// [`PeakAllocator`] and turns it on with [`PeakAllocator::track`], and
+    /// fewer than 64 solves are measured at the same time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_allocation: Option<usize>,
+}
+
+/// Tokens in the expressions of a problem, as the parser splits them.
+pub fn tokens(problem: &UnoptimizedProblem) -> usize {
+    let expressions = problem
+        .constraints
+        .iter()
+        .map(|constraint| &constraint.expression);
+    std::iter::once(&problem.objective.expression)
+        .chain(expressions)
+        .map(|expression| tokenize(&add_spaces(expression)).len())
+        .sum()
+}
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+// bytes allocated since tracking started, negative once more is freed than
+// was counted
+static ALLOCATED: AtomicIsize = AtomicIsize::new(0);
+
+// the high-water marks of the solves measured right now, one slot each, and
+// a bit per slot that is taken
+const SLOTS: usize = 64;
+#[allow(clippy::declare_interior_mutable_const)]
+const PEAK: AtomicIsize = AtomicIsize::new(0);
+static PEAKS: [AtomicIsize; SLOTS] = [PEAK; SLOTS];
+static TAKEN: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, able to count the bytes allocated so [`Metrics`]
+/// can report the peak. The library doesn't install it: a program that
+/// wants the peak declares
+/// `#[global_allocator] static ALLOCATOR: PeakAllocator = PeakAllocator;`
+/// and calls [`PeakAllocator::track`] before the solves it measures. Until
+/// then it only passes allocations on to the system allocator. Each solve
+/// keeps its own peak, but the count is for the whole process, so solves
+/// running at the same time see each other's allocations.
+pub struct PeakAllocator;
+
+impl PeakAllocator {
+    /// Starts counting allocations.
+    pub fn track() {
+        TRACKING.store(true, Ordering::Relaxed);
+    }
+
+    /// Stops counting allocations, solves measured from then on report no
+    /// peak and those running only count up to here.
+    pub fn untrack() {
+        TRACKING.store(false, Ordering::Relaxed);
+    }
+
+    fn grow(size: usize) {
+        if TRACKING.load(Ordering::Relaxed) {
+            let size = size as isize;
+            let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+            let mut taken = TAKEN.load(Ordering::Relaxed);
+            while taken != 0 {
+                PEAKS[taken.trailing_zeros() as usize].fetch_max(allocated, Ordering::Relaxed);
+                taken &= taken - 1;
+            }
+        }
+    }
+
+    fn shrink(size: usize) {
+        if TRACKING.load(Ordering::Relaxed) {
+            ALLOCATED.fetch_sub(size as isize, Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            PeakAllocator::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            PeakAllocator::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        PeakAllocator::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            PeakAllocator::shrink(layout.size());
+            PeakAllocator::grow(new_size);
+        }
+        new
+    }
+}
+
+// a free slot of `PEAKS` marked as taken, its mark starting at `allocated`
+fn claim(allocated: isize) -> Option<usize> {
+    let mut taken = TAKEN.load(Ordering::Relaxed);
+    loop {
+        let slot = (!taken).trailing_zeros() as usize;
+        if slot >= SLOTS {
+            return None;
+        }
+        match TAKEN.compare_exchange(
+            taken,
+            taken | 1 << slot,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {
+                PEAKS[slot].store(allocated, Ordering::Relaxed);
+                return Some(slot);
+            }
+            Err(now) => taken = now,
+        }
+    }
+}
+
+// times the phases of one solve as the pipeline announces them
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    metrics: Metrics,
+    current: Option<(Phase, Instant)>,
+    pivots: usize,
+    // bytes allocated when the solve started and the slot of its peak, `None`
+    // unless `PeakAllocator` is tracking and a slot was free
+    allocated: Option<(isize, usize)>,
+}
+
+impl Recorder {
+    pub fn start() -> Recorder {
+        let allocated = if TRACKING.load(Ordering::Relaxed) {
+            let allocated = ALLOCATED.load(Ordering::Relaxed);
+            claim(allocated).map(|slot| (allocated, slot))
+        } else {
+            None
+        };
+        Recorder {
+            metrics: Metrics::default(),
+            current: None,
+            pivots: 0,
+            allocated,
+        }
+    }
+
+    pub fn enter(&mut self, phase: Phase) {
+        self.close();
+        self.current = Some((phase, Instant::now()));
+    }
+
+    pub fn pivot(&mut self) {
+        self.pivots += 1;
+    }
+
+    // adds the time of the current phase to its stage
+    fn close(&mut self) {
+        let Some((phase, started)) = self.current.take() else {
+            return;
+        };
+        let seconds = started.elapsed().as_secs_f64();
+        let stage = match phase {
+            Phase::Parse => &mut self.metrics.build,
+            Phase::Presolve | Phase::Scaling => &mut self.metrics.presolve,
+            Phase::Feasibility | Phase::Lp => &mut self.metrics.solve,
+            Phase::Extract => &mut self.metrics.postprocess,
+        };
+        *stage += seconds;
+    }
+
+    // the times so far, `pivots` when the solver reports them
+    pub fn finish(&mut self, pivots: bool) -> Metrics {
+        self.close();
+        Metrics {
+            iterations: pivots.then_some(self.pivots),
+            peak_allocation: self.allocated.map(|(allocated, slot)| {
+                (PEAKS[slot].load(Ordering::Relaxed) - allocated).max(0) as usize
+            }),
+            ..self.metrics.clone()
+        }
+    }
+}
+
+// frees the slot of the peak for the next solve
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some((_, slot)) = self.allocated {
+            TAKEN.fetch_and(!(1 << slot), Ordering::Release);
+        }
+    }
+}
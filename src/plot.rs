@@ -0,0 +1,462 @@
+use crate::report::number;
+use crate::{solve_simplex, AlpsError, MatrixProblem, Sense, UnoptimizedProblem};
+use std::fmt::Write;
+
+const WIDTH: f64 = 820.0;
+const HEIGHT: f64 = 480.0;
+// the plot on the left, the legend to its right
+const PLOT_WIDTH: f64 = 640.0;
+const MARGIN: f64 = 56.0;
+const COLORS: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#9467bd", "#8c564b", "#e377c2", "#17becf", "#bcbd22",
+];
+
+type Point = [f64; 2];
+
+// `a · p <sense> b`, from a constraint or a variable bound
+struct HalfPlane {
+    a: Point,
+    b: f64,
+    sense: Sense,
+    label: String,
+    bound: bool,
+}
+
+impl HalfPlane {
+    // the signed distances, in units of `a`, that must stay nonnegative
+    fn sides(&self, p: Point) -> Vec<f64> {
+        let value = dot(self.a, p);
+        match self.sense {
+            Sense::Le => vec![self.b - value],
+            Sense::Ge => vec![value - self.b],
+            Sense::Eq => vec![self.b - value, value - self.b],
+        }
+    }
+
+    fn holds(&self, p: Point) -> bool {
+        let tolerance = 1e-7 * (1.0 + self.b.abs());
+        self.sides(p).iter().all(|side| *side >= -tolerance)
+    }
+}
+
+// maps problem coordinates onto the drawing
+struct View {
+    min: Point,
+    max: Point,
+}
+
+impl View {
+    fn x(&self, x: f64) -> f64 {
+        MARGIN + (x - self.min[0]) / (self.max[0] - self.min[0]) * (PLOT_WIDTH - 2.0 * MARGIN)
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        HEIGHT - MARGIN - (y - self.min[1]) / (self.max[1] - self.min[1]) * (HEIGHT - 2.0 * MARGIN)
+    }
+
+    fn corners(&self) -> Vec<Point> {
+        vec![
+            [self.min[0], self.min[1]],
+            [self.max[0], self.min[1]],
+            [self.max[0], self.max[1]],
+            [self.min[0], self.max[1]],
+        ]
+    }
+
+    // the part of the line `a · p = b` inside the view
+    fn segment(&self, a: Point, b: f64) -> Option<(Point, Point)> {
+        let corners = self.corners();
+        let mut points: Vec<Point> = vec![];
+        for (index, start) in corners.iter().enumerate() {
+            let end = corners[(index + 1) % corners.len()];
+            let (from, to) = (b - dot(a, *start), b - dot(a, end));
+            if from == 0.0 {
+                points.push(*start);
+            } else if from * to < 0.0 {
+                points.push(lerp(*start, end, from / (from - to)));
+            }
+        }
+        let mut best: Option<(Point, Point)> = None;
+        for (i, p) in points.iter().enumerate() {
+            for q in &points[i + 1..] {
+                if best.is_none_or(|(s, t)| distance(*p, *q) > distance(s, t)) {
+                    best = Some((*p, *q));
+                }
+            }
+        }
+        best.filter(|(p, q)| distance(*p, *q) > 0.0)
+    }
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn lerp(p: Point, q: Point, t: f64) -> Point {
+    [p[0] + t * (q[0] - p[0]), p[1] + t * (q[1] - p[1])]
+}
+
+fn distance(p: Point, q: Point) -> f64 {
+    (p[0] - q[0]).hypot(p[1] - q[1])
+}
+
+// where the lines of two half-planes cross, if they are not parallel
+fn intersection(first: &HalfPlane, second: &HalfPlane) -> Option<Point> {
+    let det = first.a[0] * second.a[1] - first.a[1] * second.a[0];
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    Some([
+        (first.b * second.a[1] - first.a[1] * second.b) / det,
+        (first.a[0] * second.b - first.b * second.a[0]) / det,
+    ])
+}
+
+// Sutherland-Hodgman, keeps the part of the polygon where `side >= 0`
+fn clip(polygon: Vec<Point>, side: impl Fn(Point) -> f64) -> Vec<Point> {
+    let mut clipped = vec![];
+    for (index, current) in polygon.iter().enumerate() {
+        let next = polygon[(index + 1) % polygon.len()];
+        let (from, to) = (side(*current), side(next));
+        if from >= 0.0 {
+            clipped.push(*current);
+        }
+        if from * to < 0.0 {
+            clipped.push(lerp(*current, next, from / (from - to)));
+        }
+    }
+    clipped
+}
+
+// about five round steps across `span`
+fn tick_step(span: f64) -> f64 {
+    let raw = span / 5.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Draws a problem with exactly two variables as an SVG: every constraint
+/// as a labelled line with a tick on its feasible side, the feasible region
+/// shaded, and the optimal vertex with the objective line through it.
+///
+/// The first declared variable is the horizontal axis. Infeasible and
+/// unbounded problems are drawn without an optimum.
+pub fn plot_svg(problem: &UnoptimizedProblem) -> Result<String, AlpsError> {
+    let matrix = MatrixProblem::try_from(problem)?;
+    if matrix.variables.len() != 2 {
+        return Err(AlpsError::InvalidModel(format!(
+            "only problems with two variables can be plotted, this one has {}",
+            matrix.variables.len()
+        )));
+    }
+    let optimum = match solve_simplex(&matrix) {
+        Ok(solution) => Some(solution),
+        Err(AlpsError::Infeasible | AlpsError::Unbounded) => None,
+        Err(error) => return Err(error),
+    };
+
+    let mut planes = vec![];
+    for (row, entries) in matrix.matrix.row_entries().iter().enumerate() {
+        let mut a = [0.0; 2];
+        for (col, value) in entries {
+            a[*col] += value;
+        }
+        planes.push(HalfPlane {
+            a,
+            b: matrix.rhs[row],
+            sense: matrix.senses[row],
+            label: matrix.constraint_names[row].clone(),
+            bound: false,
+        });
+    }
+    for (col, bound) in matrix.bounds.iter().enumerate() {
+        let mut a = [0.0; 2];
+        a[col] = 1.0;
+        let name = &matrix.variables[col];
+        for (value, sense, operator) in [(bound.min, Sense::Ge, "≥"), (bound.max, Sense::Le, "≤")]
+        {
+            if let Some(value) = value.filter(|value| value.is_finite()) {
+                planes.push(HalfPlane {
+                    a,
+                    b: value,
+                    sense,
+                    label: format!("{} {} {}", name, operator, number(value)),
+                    bound: true,
+                });
+            }
+        }
+    }
+
+    // frame the vertices of the feasible region, or every line when there
+    // are none, together with the origin
+    let mut crossings = vec![];
+    for (i, first) in planes.iter().enumerate() {
+        for second in &planes[i + 1..] {
+            crossings.extend(intersection(first, second));
+        }
+    }
+    let vertices: Vec<Point> = crossings
+        .iter()
+        .copied()
+        .filter(|p| planes.iter().all(|plane| plane.holds(*p)))
+        .collect();
+    let mut framed = if vertices.is_empty() {
+        // the point of each line nearest the origin keeps parallel lines apart
+        crossings.extend(planes.iter().map(|plane| {
+            let scale = plane.b / dot(plane.a, plane.a);
+            [scale * plane.a[0], scale * plane.a[1]]
+        }));
+        crossings
+    } else {
+        vertices
+    };
+    framed.push([0.0, 0.0]);
+    framed.extend(
+        optimum
+            .as_ref()
+            .map(|solution| [solution.values[0], solution.values[1]]),
+    );
+    let mut view = View {
+        min: [f64::INFINITY; 2],
+        max: [f64::NEG_INFINITY; 2],
+    };
+    for p in framed
+        .iter()
+        .filter(|p| p[0].is_finite() && p[1].is_finite())
+    {
+        for (axis, value) in p.iter().enumerate() {
+            view.min[axis] = view.min[axis].min(*value);
+            view.max[axis] = view.max[axis].max(*value);
+        }
+    }
+    for axis in 0..2 {
+        let span = (view.max[axis] - view.min[axis]).max(1.0);
+        view.min[axis] -= 0.15 * span;
+        view.max[axis] += 0.15 * span;
+    }
+
+    let mut region = view.corners();
+    for plane in &planes {
+        for index in 0..plane.sides([0.0; 2]).len() {
+            // a little slack so the region of an equality is not lost
+            region = clip(region, |p| {
+                plane.sides(p)[index] + 1e-9 * (1.0 + plane.b.abs())
+            });
+        }
+    }
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    // grid, axes and their names
+    let _ = writeln!(svg, r##"<g stroke="#e5e5e5">"##);
+    let mut labels = String::new();
+    for axis in 0..2 {
+        let step = tick_step(view.max[axis] - view.min[axis]);
+        let mut tick = (view.min[axis] / step).ceil() * step;
+        while tick <= view.max[axis] {
+            let text = number(tick);
+            if axis == 0 {
+                let x = view.x(tick);
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{x:.2}" y1="{:.2}" x2="{x:.2}" y2="{:.2}"/>"#,
+                    MARGIN,
+                    HEIGHT - MARGIN
+                );
+                let _ = writeln!(
+                    labels,
+                    r#"<text x="{x:.2}" y="{:.2}" text-anchor="middle">{text}</text>"#,
+                    HEIGHT - MARGIN + 16.0
+                );
+            } else {
+                let y = view.y(tick);
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{:.2}" y1="{y:.2}" x2="{:.2}" y2="{y:.2}"/>"#,
+                    MARGIN,
+                    PLOT_WIDTH - MARGIN
+                );
+                let _ = writeln!(
+                    labels,
+                    r#"<text x="{:.2}" y="{:.2}" text-anchor="end">{text}</text>"#,
+                    MARGIN - 6.0,
+                    y + 4.0
+                );
+            }
+            tick += step;
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+    svg += &labels;
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+        PLOT_WIDTH / 2.0,
+        HEIGHT - 12.0,
+        escape(&matrix.variables[0])
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="16" y="{:.2}" text-anchor="middle" transform="rotate(-90 16 {:.2})">{}</text>"#,
+        HEIGHT / 2.0,
+        HEIGHT / 2.0,
+        escape(&matrix.variables[1])
+    );
+    let _ = writeln!(svg, r##"<g stroke="#999999">"##);
+    if view.min[0] < 0.0 && view.max[0] > 0.0 {
+        let x = view.x(0.0);
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{x:.2}" y1="{MARGIN}" x2="{x:.2}" y2="{:.2}"/>"#,
+            HEIGHT - MARGIN
+        );
+    }
+    if view.min[1] < 0.0 && view.max[1] > 0.0 {
+        let y = view.y(0.0);
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{MARGIN}" y1="{y:.2}" x2="{:.2}" y2="{y:.2}"/>"#,
+            PLOT_WIDTH - MARGIN
+        );
+    }
+    let _ = writeln!(svg, "</g>");
+
+    if region.len() >= 3 {
+        let points: Vec<String> = region
+            .iter()
+            .map(|p| format!("{:.2},{:.2}", view.x(p[0]), view.y(p[1])))
+            .collect();
+        let _ = writeln!(
+            svg,
+            r##"<polygon class="feasible" points="{}" fill="#4c9be8" fill-opacity="0.25" stroke="#4c9be8"/>"##,
+            points.join(" ")
+        );
+    }
+
+    // each line with its name and a tick pointing into its feasible side
+    let mut colors = COLORS.iter().cycle();
+    let mut legend = vec![];
+    for plane in &planes {
+        let color = if plane.bound {
+            "#777777"
+        } else {
+            colors.next().unwrap()
+        };
+        let segment = view.segment(plane.a, plane.b);
+        legend.push((color, &plane.label, segment.is_some()));
+        let Some((start, end)) = segment else {
+            continue;
+        };
+        let (x1, y1, x2, y2) = (
+            view.x(start[0]),
+            view.y(start[1]),
+            view.x(end[0]),
+            view.y(end[1]),
+        );
+        let _ = writeln!(
+            svg,
+            r#"<line class="constraint" x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="{color}" stroke-width="1.5"/>"#
+        );
+        if plane.sense != Sense::Eq {
+            let middle = lerp(start, end, 0.5);
+            let inward = if plane.sense == Sense::Le { -1.0 } else { 1.0 };
+            let scale = 1e-3 * distance(view.min, view.max) / dot(plane.a, plane.a).sqrt();
+            let inside = [
+                middle[0] + inward * scale * plane.a[0],
+                middle[1] + inward * scale * plane.a[1],
+            ];
+            let (mx, my) = (view.x(middle[0]), view.y(middle[1]));
+            let (dx, dy) = (view.x(inside[0]) - mx, view.y(inside[1]) - my);
+            let length = dx.hypot(dy);
+            if length > 0.0 {
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{mx:.2}" y1="{my:.2}" x2="{:.2}" y2="{:.2}" stroke="{color}" stroke-width="1.5"/>"#,
+                    mx + 10.0 * dx / length,
+                    my + 10.0 * dy / length
+                );
+            }
+        }
+        let anchor = lerp(start, end, 0.08);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" fill="{color}">{}</text>"#,
+            view.x(anchor[0]) + 4.0,
+            view.y(anchor[1]) - 4.0,
+            escape(&plane.label)
+        );
+    }
+
+    if let Some(solution) = &optimum {
+        let vertex = [solution.values[0], solution.values[1]];
+        let level = dot([matrix.objective[0], matrix.objective[1]], vertex);
+        if let Some((start, end)) = view.segment([matrix.objective[0], matrix.objective[1]], level)
+        {
+            let _ = writeln!(
+                svg,
+                r##"<line class="objective" x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="#d62728" stroke-width="1.5" stroke-dasharray="6 4"/>"##,
+                view.x(start[0]),
+                view.y(start[1]),
+                view.x(end[0]),
+                view.y(end[1])
+            );
+        }
+        let (x, y) = (view.x(vertex[0]), view.y(vertex[1]));
+        // keep the label inside the plot
+        let right = x > PLOT_WIDTH / 2.0;
+        let _ = writeln!(
+            svg,
+            r##"<circle class="optimum" cx="{x:.2}" cy="{y:.2}" r="5" fill="#d62728"/>"##
+        );
+        let _ = writeln!(
+            svg,
+            r##"<text x="{:.2}" y="{:.2}" fill="#d62728" text-anchor="{}">{} ({}, {}) = {}</text>"##,
+            if right { x - 8.0 } else { x + 8.0 },
+            y - 8.0,
+            if right { "end" } else { "start" },
+            escape(&problem.objective.goal),
+            number(vertex[0]),
+            number(vertex[1]),
+            number(solution.objective)
+        );
+    }
+
+    // every line by name, including the ones that miss the view
+    let x = PLOT_WIDTH + 8.0;
+    for (index, (color, label, visible)) in legend.iter().enumerate() {
+        let y = MARGIN + 18.0 * index as f64;
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{x:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{color}" stroke-width="1.5"/>"#,
+            y - 4.0,
+            x + 16.0,
+            y - 4.0
+        );
+        let note = if *visible { "" } else { " (outside the view)" };
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{y:.2}">{}{note}</text>"#,
+            x + 22.0,
+            escape(label)
+        );
+    }
+
+    svg += "</svg>\n";
+    Ok(svg)
+}
@@ -0,0 +1,536 @@
+use crate::matrix::{Bound, MatrixProblem, Sense, SparseMatrix};
+use crate::AlpsError;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// slack allowed when comparing activities, bounds and right hand sides
+const TOLERANCE: f64 = 1e-9;
+
+// coefficients smaller than this are dropped after a substitution
+const DROP: f64 = 1e-12;
+
+/// Which reductions [`presolve`] applies, all of them by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PresolveOptions {
+    /// Turn rows on a single variable into bounds
+    pub singleton_rows: bool,
+    /// Substitute variables whose bounds are equal
+    pub fixed_variables: bool,
+    /// Drop rows without variables and rows the bounds already imply
+    pub redundant_rows: bool,
+    /// Substitute one variable of each `a * x + b * y == c` row
+    pub doubleton_equalities: bool,
+}
+
+impl Default for PresolveOptions {
+    fn default() -> Self {
+        PresolveOptions {
+            singleton_rows: true,
+            fixed_variables: true,
+            redundant_rows: true,
+            doubleton_equalities: true,
+        }
+    }
+}
+
+/// What [`presolve`] removed from a problem.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PresolveReport {
+    pub rows_before: usize,
+    pub columns_before: usize,
+    pub rows_after: usize,
+    pub columns_after: usize,
+    pub singleton_rows: usize,
+    pub fixed_variables: usize,
+    pub empty_rows: usize,
+    pub redundant_rows: usize,
+    pub doubleton_equalities: usize,
+}
+
+// a column as it was when a reduction removed it or one of its rows: its
+// cost and its coefficients in the other rows still in the problem
+#[derive(Debug, Clone)]
+struct ColumnState {
+    col: usize,
+    cost: f64,
+    entries: Vec<(usize, f64)>,
+    bound: Bound,
+}
+
+impl ColumnState {
+    // the reduced cost once every row in `entries` has its dual
+    fn reduced_cost(&self, duals: &[f64]) -> f64 {
+        self.cost
+            - self
+                .entries
+                .iter()
+                .map(|(row, value)| value * duals[*row])
+                .sum::<f64>()
+    }
+}
+
+// undone in reverse order by `Presolved::postsolve`
+#[derive(Debug, Clone)]
+enum Reduction {
+    // an empty or redundant row, its dual is zero
+    DroppedRow,
+    // `coefficient * x <sense> rhs` became a bound of `column`
+    SingletonRow {
+        row: usize,
+        coefficient: f64,
+        value: f64,
+        column: ColumnState,
+    },
+    FixedColumn {
+        col: usize,
+        value: f64,
+    },
+    // `kept_coefficient * kept + removed_coefficient * removed == rhs`
+    // solved for `removed`
+    Doubleton {
+        row: usize,
+        rhs: f64,
+        kept_coefficient: f64,
+        removed_coefficient: f64,
+        kept: ColumnState,
+        removed: ColumnState,
+    },
+}
+
+/// A problem after [`presolve`], with what is needed to map a solution of
+/// the smaller problem back onto the original one.
+#[derive(Debug, Clone)]
+pub struct Presolved {
+    /// The reduced problem, columns and rows keep their original names
+    pub problem: MatrixProblem,
+    pub report: PresolveReport,
+    // original index of every row and column of `problem`
+    rows: Vec<usize>,
+    columns: Vec<usize>,
+    original_rows: usize,
+    original_columns: usize,
+    reductions: Vec<Reduction>,
+}
+
+impl Presolved {
+    /// Maps values, and duals when the solver gave them, of the reduced
+    /// problem onto the original rows and columns.
+    pub fn postsolve(&self, values: &[f64], duals: Option<&[f64]>) -> (Vec<f64>, Option<Vec<f64>>) {
+        let mut x = vec![0.0; self.original_columns];
+        for (reduced, col) in self.columns.iter().enumerate() {
+            x[*col] = values[reduced];
+        }
+        let mut y = duals.map(|duals| {
+            let mut y = vec![0.0; self.original_rows];
+            for (reduced, row) in self.rows.iter().enumerate() {
+                y[*row] = duals[reduced];
+            }
+            y
+        });
+
+        for reduction in self.reductions.iter().rev() {
+            match reduction {
+                Reduction::DroppedRow => {}
+                Reduction::FixedColumn { col, value } => x[*col] = *value,
+                Reduction::SingletonRow {
+                    row,
+                    coefficient,
+                    value,
+                    column,
+                } => {
+                    // the row takes over the reduced cost when it is the
+                    // bound holding the variable
+                    if let Some(y) = &mut y {
+                        if at(x[column.col], *value) {
+                            y[*row] = column.reduced_cost(y) / coefficient;
+                        }
+                    }
+                }
+                Reduction::Doubleton {
+                    row,
+                    rhs,
+                    kept_coefficient,
+                    removed_coefficient,
+                    kept,
+                    removed,
+                } => {
+                    x[removed.col] = (rhs - kept_coefficient * x[kept.col]) / removed_coefficient;
+                    if let Some(y) = &mut y {
+                        // the removed variable is basic unless one of its
+                        // bounds, moved onto the kept one, holds both
+                        let removed_at_bound = at_bound(x[removed.col], removed.bound);
+                        let kept_at_bound = at_bound(x[kept.col], kept.bound);
+                        y[*row] = if removed_at_bound && !kept_at_bound {
+                            kept.reduced_cost(y) / kept_coefficient
+                        } else {
+                            removed.reduced_cost(y) / removed_coefficient
+                        };
+                    }
+                }
+            }
+        }
+        (x, y)
+    }
+}
+
+fn at(value: f64, target: f64) -> bool {
+    (value - target).abs() <= 1e-7 * (1.0 + target.abs())
+}
+
+fn at_bound(value: f64, bound: Bound) -> bool {
+    bound.min.is_some_and(|min| at(value, min)) || bound.max.is_some_and(|max| at(value, max))
+}
+
+struct Row {
+    entries: BTreeMap<usize, f64>,
+    sense: Sense,
+    rhs: f64,
+}
+
+struct Workspace {
+    rows: Vec<Option<Row>>,
+    active: Vec<bool>,
+    bounds: Vec<Bound>,
+    objective: Vec<f64>,
+    constant: f64,
+    reductions: Vec<Reduction>,
+    report: PresolveReport,
+}
+
+impl Workspace {
+    fn column(&self, col: usize, skip: usize) -> ColumnState {
+        let entries = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(row, _)| *row != skip)
+            .filter_map(|(row, entries)| Some((row, *entries.as_ref()?.entries.get(&col)?)))
+            .collect();
+        ColumnState {
+            col,
+            cost: self.objective[col],
+            entries,
+            bound: self.bounds[col],
+        }
+    }
+
+    // the smallest and largest value the row can take within the bounds
+    fn activity(&self, row: &Row) -> (f64, f64) {
+        let mut range = (0.0, 0.0);
+        for (col, value) in &row.entries {
+            let bound = self.bounds[*col];
+            let low = bound.min.unwrap_or(f64::NEG_INFINITY);
+            let high = bound.max.unwrap_or(f64::INFINITY);
+            let (low, high) = if *value >= 0.0 {
+                (value * low, value * high)
+            } else {
+                (value * high, value * low)
+            };
+            range.0 += low;
+            range.1 += high;
+        }
+        range
+    }
+
+    fn tighten(&mut self, col: usize, min: Option<f64>, max: Option<f64>) -> Result<(), AlpsError> {
+        let bound = &mut self.bounds[col];
+        if let Some(min) = min {
+            if bound.min.is_none_or(|current| min > current) {
+                bound.min = Some(min);
+            }
+        }
+        if let Some(max) = max {
+            if bound.max.is_none_or(|current| max < current) {
+                bound.max = Some(max);
+            }
+        }
+        if let (Some(min), Some(max)) = (bound.min, bound.max) {
+            if min > max + TOLERANCE * (1.0 + max.abs()) {
+                return Err(AlpsError::Infeasible);
+            }
+            if min > max {
+                bound.max = Some(min);
+            }
+        }
+        Ok(())
+    }
+
+    fn drop_row(&mut self, row: usize) {
+        self.rows[row] = None;
+        self.reductions.push(Reduction::DroppedRow);
+    }
+
+    // returns whether the row was removed
+    fn reduce_row(&mut self, index: usize, options: &PresolveOptions) -> Result<bool, AlpsError> {
+        let Some(row) = &self.rows[index] else {
+            return Ok(false);
+        };
+        let tolerance = TOLERANCE * (1.0 + row.rhs.abs());
+        if row.entries.is_empty() && options.redundant_rows {
+            let holds = match row.sense {
+                Sense::Le => 0.0 <= row.rhs + tolerance,
+                Sense::Ge => 0.0 >= row.rhs - tolerance,
+                Sense::Eq => row.rhs.abs() <= tolerance,
+            };
+            if !holds {
+                return Err(AlpsError::Infeasible);
+            }
+            self.drop_row(index);
+            self.report.empty_rows += 1;
+            return Ok(true);
+        }
+
+        if row.entries.len() == 1 && options.singleton_rows {
+            let (col, coefficient) = row.entries.iter().next().map(|(c, v)| (*c, *v)).unwrap();
+            let (sense, value) = (row.sense, row.rhs / coefficient);
+            // dividing by a negative coefficient turns the inequality around
+            let lower = match sense {
+                Sense::Eq => true,
+                Sense::Ge => coefficient > 0.0,
+                Sense::Le => coefficient < 0.0,
+            };
+            let upper = sense == Sense::Eq || !lower;
+            let column = self.column(col, index);
+            self.tighten(col, lower.then_some(value), upper.then_some(value))?;
+            self.rows[index] = None;
+            self.reductions.push(Reduction::SingletonRow {
+                row: index,
+                coefficient,
+                value,
+                column,
+            });
+            self.report.singleton_rows += 1;
+            return Ok(true);
+        }
+
+        if options.redundant_rows {
+            let (low, high) = self.activity(row);
+            let infeasible = match row.sense {
+                Sense::Le => low > row.rhs + tolerance,
+                Sense::Ge => high < row.rhs - tolerance,
+                Sense::Eq => low > row.rhs + tolerance || high < row.rhs - tolerance,
+            };
+            if infeasible {
+                return Err(AlpsError::Infeasible);
+            }
+            let redundant = match row.sense {
+                Sense::Le => high <= row.rhs,
+                Sense::Ge => low >= row.rhs,
+                Sense::Eq => false,
+            };
+            if redundant {
+                self.drop_row(index);
+                self.report.redundant_rows += 1;
+                return Ok(true);
+            }
+        }
+
+        if row.entries.len() == 2 && row.sense == Sense::Eq && options.doubleton_equalities {
+            self.eliminate_doubleton(index)?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn eliminate_doubleton(&mut self, index: usize) -> Result<(), AlpsError> {
+        let row = self.rows[index].take().unwrap();
+        let mut entries = row.entries.into_iter();
+        let (first, second) = (entries.next().unwrap(), entries.next().unwrap());
+        // solve for the larger coefficient, it divides
+        let ((kept, a), (removed, b)) = if second.1.abs() >= first.1.abs() {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let kept_state = self.column(kept, index);
+        let removed_state = self.column(removed, index);
+
+        // removed = (rhs - a * kept) / b, its bounds move onto `kept`
+        let ends = [
+            self.bounds[removed].min.map(|min| (row.rhs - b * min) / a),
+            self.bounds[removed].max.map(|max| (row.rhs - b * max) / a),
+        ];
+        let (min, max) = if b / a > 0.0 {
+            (ends[1], ends[0])
+        } else {
+            (ends[0], ends[1])
+        };
+        self.tighten(kept, min, max)?;
+
+        for other in self.rows.iter_mut().flatten() {
+            if let Some(value) = other.entries.remove(&removed) {
+                let entry = other.entries.entry(kept).or_insert(0.0);
+                *entry -= value * a / b;
+                if entry.abs() < DROP {
+                    other.entries.remove(&kept);
+                }
+                other.rhs -= value * row.rhs / b;
+            }
+        }
+        let cost = self.objective[removed];
+        self.objective[kept] -= cost * a / b;
+        self.constant += cost * row.rhs / b;
+        self.objective[removed] = 0.0;
+        self.active[removed] = false;
+
+        self.reductions.push(Reduction::Doubleton {
+            row: index,
+            rhs: row.rhs,
+            kept_coefficient: a,
+            removed_coefficient: b,
+            kept: kept_state,
+            removed: removed_state,
+        });
+        self.report.doubleton_equalities += 1;
+        Ok(())
+    }
+
+    // returns whether the column was removed
+    fn remove_fixed(&mut self, col: usize) -> bool {
+        let bound = self.bounds[col];
+        let (Some(min), Some(max)) = (bound.min, bound.max) else {
+            return false;
+        };
+        if !self.active[col] || min != max {
+            return false;
+        }
+        for row in self.rows.iter_mut().flatten() {
+            if let Some(value) = row.entries.remove(&col) {
+                row.rhs -= value * min;
+            }
+        }
+        self.constant += self.objective[col] * min;
+        self.active[col] = false;
+        self.reductions
+            .push(Reduction::FixedColumn { col, value: min });
+        self.report.fixed_variables += 1;
+        true
+    }
+}
+
+/// Simplifies a problem before it is solved: singleton rows become bounds,
+/// fixed variables are substituted, empty and redundant rows are dropped
+/// and doubleton equalities eliminate one of their variables. Rows that can
+/// never hold make the problem [`AlpsError::Infeasible`].
+///
+/// The reductions repeat until none applies, [`Presolved::postsolve`] maps
+/// a solution back.
+pub fn presolve(
+    problem: &MatrixProblem,
+    options: &PresolveOptions,
+) -> Result<Presolved, AlpsError> {
+    problem.check()?;
+    let mut workspace = Workspace {
+        rows: problem
+            .matrix
+            .row_entries()
+            .into_iter()
+            .zip(&problem.senses)
+            .zip(&problem.rhs)
+            .map(|((entries, sense), rhs)| {
+                let mut row = Row {
+                    entries: BTreeMap::new(),
+                    sense: *sense,
+                    rhs: *rhs,
+                };
+                for (col, value) in entries {
+                    *row.entries.entry(col).or_insert(0.0) += value;
+                }
+                row.entries.retain(|_, value| *value != 0.0);
+                Some(row)
+            })
+            .collect(),
+        active: vec![true; problem.variables.len()],
+        bounds: problem.bounds.clone(),
+        objective: problem.objective.clone(),
+        constant: problem.objective_constant,
+        reductions: vec![],
+        report: PresolveReport {
+            rows_before: problem.senses.len(),
+            columns_before: problem.variables.len(),
+            ..PresolveReport::default()
+        },
+    };
+    for col in 0..problem.variables.len() {
+        workspace.tighten(col, None, None)?;
+    }
+
+    loop {
+        let mut changed = false;
+        for row in 0..workspace.rows.len() {
+            changed |= workspace.reduce_row(row, options)?;
+        }
+        if options.fixed_variables {
+            for col in 0..problem.variables.len() {
+                changed |= workspace.remove_fixed(col);
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let columns: Vec<usize> = (0..problem.variables.len())
+        .filter(|col| workspace.active[*col])
+        .collect();
+    let mut position = vec![usize::MAX; problem.variables.len()];
+    for (reduced, col) in columns.iter().enumerate() {
+        position[*col] = reduced;
+    }
+    let rows: Vec<usize> = (0..workspace.rows.len())
+        .filter(|row| workspace.rows[*row].is_some())
+        .collect();
+    let mut entries = vec![];
+    for (reduced, row) in rows.iter().enumerate() {
+        for (col, value) in &workspace.rows[*row].as_ref().unwrap().entries {
+            entries.push((reduced, position[*col], *value));
+        }
+    }
+    let reduced = MatrixProblem {
+        variables: columns
+            .iter()
+            .map(|col| problem.variables[*col].clone())
+            .collect(),
+        goal: problem.goal.clone(),
+        objective: columns
+            .iter()
+            .map(|col| workspace.objective[*col])
+            .collect(),
+        objective_constant: workspace.constant,
+        matrix: SparseMatrix::Triplet {
+            rows: rows.len(),
+            cols: columns.len(),
+            entries,
+        },
+        senses: rows
+            .iter()
+            .map(|row| workspace.rows[*row].as_ref().unwrap().sense)
+            .collect(),
+        rhs: rows
+            .iter()
+            .map(|row| workspace.rows[*row].as_ref().unwrap().rhs)
+            .collect(),
+        bounds: columns.iter().map(|col| workspace.bounds[*col]).collect(),
+        constraint_names: rows
+            .iter()
+            .map(|row| problem.constraint_names[*row].clone())
+            .collect(),
+    };
+
+    let report = PresolveReport {
+        rows_after: rows.len(),
+        columns_after: columns.len(),
+        ..workspace.report
+    };
+    Ok(Presolved {
+        problem: reduced,
+        report,
+        rows,
+        columns,
+        original_rows: problem.senses.len(),
+        original_columns: problem.variables.len(),
+        reductions: workspace.reductions,
+    })
+}
@@ -0,0 +1,98 @@
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// A stage of a solve, in the order they run. alps solves linear programs
+/// only, so there is no branch and bound phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Reading the expressions and building the solver's model
+    Parse,
+    Presolve,
+    Scaling,
+    /// Looking for a feasible solution, phase one of the simplex
+    Feasibility,
+    /// Optimising the linear program, phase two of the simplex or all of
+    /// minilp
+    Lp,
+    /// Mapping the solution back onto the model and evaluating it
+    Extract,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Phase::Parse => "parse",
+            Phase::Presolve => "presolve",
+            Phase::Scaling => "scaling",
+            Phase::Feasibility => "feasibility",
+            Phase::Lp => "lp",
+            Phase::Extract => "extract",
+        })
+    }
+}
+
+/// What an [`Observer`] hears about a running solve.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SolveEvent {
+    /// A stage started
+    Phase { phase: Phase },
+    /// A simplex pivot, with the sum of the infeasibilities during
+    /// feasibility and the objective after that
+    Iteration {
+        phase: Phase,
+        iterations: usize,
+        objective: f64,
+    },
+    /// A feasible solution better than the last one
+    Incumbent { objective: f64 },
+    /// A proven bound on the objective. Without branch and bound the only
+    /// one is the optimum itself
+    Bound { bound: f64 },
+}
+
+impl fmt::Display for SolveEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveEvent::Phase { phase } => write!(f, "{}", phase),
+            SolveEvent::Iteration {
+                phase,
+                iterations,
+                objective,
+            } => write!(f, "{} iteration {}: {}", phase, iterations, objective),
+            SolveEvent::Incumbent { objective } => write!(f, "incumbent {}", objective),
+            SolveEvent::Bound { bound } => write!(f, "bound {}", bound),
+        }
+    }
+}
+
+/// Called with every [`SolveEvent`] of a solve, on the solving thread.
+/// Clones share the callback.
+#[derive(Clone)]
+pub struct Observer(Arc<dyn Fn(&SolveEvent) + Send + Sync>);
+
+impl Observer {
+    pub fn new(callback: impl Fn(&SolveEvent) + Send + Sync + 'static) -> Observer {
+        Observer(Arc::new(callback))
+    }
+
+    pub fn notify(&self, event: &SolveEvent) {
+        (self.0)(event)
+    }
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Observer")
+    }
+}
+
+// two observers are the same when they share the callback
+impl PartialEq for Observer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
@@ -0,0 +1,429 @@
+use crate::{AlpsError, MatrixProblem, Sense, SolutionResponse, UnoptimizedProblem};
+use std::fmt;
+
+/// What one term of the objective adds to the optimum, `coefficient × value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermContribution {
+    pub variable: String,
+    pub coefficient: f64,
+    pub value: f64,
+    pub contribution: f64,
+}
+
+/// A variable at the optimum with its bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableReport {
+    pub name: String,
+    pub value: f64,
+    pub reduced_cost: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// A constraint at the optimum, as `activity <sense> rhs` with every
+/// variable moved to the left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintReport {
+    pub name: String,
+    pub expression: String,
+    pub sense: Sense,
+    pub activity: f64,
+    pub rhs: f64,
+    /// How far the constraint is from binding, never negative when it holds
+    pub slack: f64,
+    pub binding: bool,
+    pub dual: Option<f64>,
+}
+
+/// A solution laid out for people: the objective split into its terms,
+/// variables in declaration order and the slack of every constraint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolutionReport {
+    pub goal: String,
+    pub expression: String,
+    pub objective: f64,
+    pub terms: Vec<TermContribution>,
+    /// The constant of the objective
+    pub constant: f64,
+    pub variables: Vec<VariableReport>,
+    pub constraints: Vec<ConstraintReport>,
+}
+
+impl SolutionReport {
+    pub fn new(
+        problem: &UnoptimizedProblem,
+        solution: &SolutionResponse,
+    ) -> Result<SolutionReport, AlpsError> {
+        let matrix = MatrixProblem::try_from(problem)?;
+        let values: Vec<f64> = matrix
+            .variables
+            .iter()
+            .map(|name| {
+                solution
+                    .variable_solutions
+                    .get(name)
+                    .copied()
+                    .unwrap_or(0.0)
+            })
+            .collect();
+
+        let terms = matrix
+            .variables
+            .iter()
+            .zip(&matrix.objective)
+            .zip(&values)
+            .filter(|((_, coefficient), _)| **coefficient != 0.0)
+            .map(|((variable, coefficient), value)| TermContribution {
+                variable: variable.clone(),
+                coefficient: *coefficient,
+                value: *value,
+                contribution: coefficient * value,
+            })
+            .collect();
+
+        let constraints = matrix
+            .matrix
+            .row_entries()
+            .iter()
+            .zip(&problem.constraints)
+            .enumerate()
+            .map(|(row, (entries, constraint))| {
+                let activity: f64 = entries
+                    .iter()
+                    .map(|(col, value)| value * values[*col])
+                    .sum();
+                let rhs = matrix.rhs[row];
+                let sense = matrix.senses[row];
+                let slack = match sense {
+                    Sense::Ge => activity - rhs,
+                    Sense::Le | Sense::Eq => rhs - activity,
+                };
+                ConstraintReport {
+                    name: constraint.name.clone(),
+                    expression: constraint.expression.clone(),
+                    sense,
+                    activity,
+                    rhs,
+                    slack,
+                    binding: slack.abs() <= 1e-6 * (1.0 + rhs.abs()),
+                    dual: solution.const_values.get(row).and_then(|c| c.dual),
+                }
+            })
+            .collect();
+
+        Ok(SolutionReport {
+            goal: problem.objective.goal.clone(),
+            expression: problem.objective.expression.clone(),
+            objective: solution.objective,
+            terms,
+            constant: matrix.objective_constant,
+            variables: matrix
+                .variables
+                .into_iter()
+                .zip(values)
+                .zip(&matrix.bounds)
+                .map(|((name, value), bound)| VariableReport {
+                    reduced_cost: solution
+                        .reduced_costs
+                        .as_ref()
+                        .and_then(|costs| costs.get(&name).copied()),
+                    name,
+                    value,
+                    min: bound.min,
+                    max: bound.max,
+                })
+                .collect(),
+            constraints,
+        })
+    }
+}
+
+impl fmt::Display for SolutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.goal, self.expression)?;
+        writeln!(f, "objective = {}", number(self.objective))?;
+
+        let terms = self.term_rows();
+        writeln!(f)?;
+        write!(f, "{}", table(&["term", "value", "contribution"], &terms))?;
+
+        let variables = self.variable_rows();
+        writeln!(f)?;
+        write!(f, "{}", table(&self.variable_header(), &variables))?;
+
+        if self.constraints.is_empty() {
+            return Ok(());
+        }
+        let mut header = vec!["constraint", "", "activity", "rhs", "slack", "binding"];
+        let constraints: Vec<Vec<String>> = self
+            .constraints
+            .iter()
+            .map(|constraint| {
+                let mut row = vec![
+                    constraint.name.clone(),
+                    constraint.sense.as_str().to_string(),
+                    number(constraint.activity),
+                    number(constraint.rhs),
+                    number(constraint.slack),
+                    if constraint.binding { "*" } else { "" }.to_string(),
+                ];
+                if self.has_duals() {
+                    row.insert(5, optional(constraint.dual, number));
+                }
+                row
+            })
+            .collect();
+        if self.has_duals() {
+            header.insert(5, "dual");
+        }
+        writeln!(f)?;
+        write!(f, "{}", table(&header, &constraints))
+    }
+}
+
+impl SolutionReport {
+    fn has_reduced_costs(&self) -> bool {
+        self.variables.iter().any(|v| v.reduced_cost.is_some())
+    }
+
+    fn has_duals(&self) -> bool {
+        self.constraints.iter().any(|c| c.dual.is_some())
+    }
+
+    fn has_bounds(&self) -> bool {
+        self.variables
+            .iter()
+            .any(|v| v.min.is_some() || v.max.is_some())
+    }
+
+    fn term_rows(&self) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = self
+            .terms
+            .iter()
+            .map(|term| {
+                vec![
+                    format!("{} × {}", number(term.coefficient), term.variable),
+                    number(term.value),
+                    number(term.contribution),
+                ]
+            })
+            .collect();
+        if self.constant != 0.0 {
+            rows.push(vec![
+                "constant".to_string(),
+                String::new(),
+                number(self.constant),
+            ]);
+        }
+        rows
+    }
+
+    fn variable_header(&self) -> Vec<&'static str> {
+        let mut header = vec!["variable", "value"];
+        if self.has_reduced_costs() {
+            header.push("reduced cost");
+        }
+        if self.has_bounds() {
+            header.extend(["min", "max"]);
+        }
+        header
+    }
+
+    fn variable_rows(&self) -> Vec<Vec<String>> {
+        self.variables
+            .iter()
+            .map(|variable| {
+                let mut row = vec![variable.name.clone(), number(variable.value)];
+                if self.has_reduced_costs() {
+                    row.push(optional(variable.reduced_cost, number));
+                }
+                if self.has_bounds() {
+                    row.push(optional(variable.min, number));
+                    row.push(optional(variable.max, number));
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// The variables as CSV, `name,value,reduced_cost,min,max`. Values
+    /// that are unknown or unbounded are left empty.
+    pub fn variables_csv(&self) -> String {
+        let mut text = String::from("name,value,reduced_cost,min,max\n");
+        for variable in &self.variables {
+            let row = [
+                csv_field(&variable.name),
+                variable.value.to_string(),
+                optional(variable.reduced_cost, |value| value.to_string()),
+                optional(variable.min, |value| value.to_string()),
+                optional(variable.max, |value| value.to_string()),
+            ];
+            text += &(row.join(",") + "\n");
+        }
+        text
+    }
+
+    /// The constraints as CSV, `name,expression,lhs,rhs,slack,dual`, where
+    /// `lhs` is the activity with every variable moved to the left.
+    pub fn constraints_csv(&self) -> String {
+        let mut text = String::from("name,expression,lhs,rhs,slack,dual\n");
+        for constraint in &self.constraints {
+            let row = [
+                csv_field(&constraint.name),
+                csv_field(&constraint.expression),
+                constraint.activity.to_string(),
+                constraint.rhs.to_string(),
+                constraint.slack.to_string(),
+                optional(constraint.dual, |value| value.to_string()),
+            ];
+            text += &(row.join(",") + "\n");
+        }
+        text
+    }
+
+    /// Both CSV sections, variables first, separated by an empty line.
+    pub fn to_csv(&self) -> String {
+        format!("{}\n{}", self.variables_csv(), self.constraints_csv())
+    }
+
+    /// The report as Markdown tables, for pasting into documents.
+    pub fn to_markdown(&self) -> String {
+        let mut text = format!(
+            "**{}** `{}` = {}\n",
+            self.goal,
+            self.expression,
+            number(self.objective)
+        );
+
+        let terms = self.term_rows();
+        text += "\n";
+        text += &markdown_table(&["term", "value", "contribution"], &terms);
+
+        text += "\n";
+        text += &markdown_table(&self.variable_header(), &self.variable_rows());
+
+        if !self.constraints.is_empty() {
+            let mut header = vec!["constraint", "expression", "activity", "rhs", "slack"];
+            if self.has_duals() {
+                header.push("dual");
+            }
+            header.push("binding");
+            let rows: Vec<Vec<String>> = self
+                .constraints
+                .iter()
+                .map(|constraint| {
+                    let mut row = vec![
+                        constraint.name.clone(),
+                        format!("`{}`", constraint.expression),
+                        number(constraint.activity),
+                        number(constraint.rhs),
+                        number(constraint.slack),
+                    ];
+                    if self.has_duals() {
+                        row.push(optional(constraint.dual, number));
+                    }
+                    row.push(if constraint.binding { "yes" } else { "" }.to_string());
+                    row
+                })
+                .collect();
+            text += "\n";
+            text += &markdown_table(&header, &rows);
+        }
+        text
+    }
+}
+
+/// A plain text report of a solution, see [`SolutionReport`].
+pub fn explain(
+    problem: &UnoptimizedProblem,
+    solution: &SolutionResponse,
+) -> Result<String, AlpsError> {
+    Ok(SolutionReport::new(problem, solution)?.to_string())
+}
+
+// six decimals at most, without trailing zeros or negative zero
+pub(crate) fn number(value: f64) -> String {
+    let text = format!("{:.6}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+fn optional(value: Option<f64>, format: impl Fn(f64) -> String) -> String {
+    value.map(format).unwrap_or_default()
+}
+
+// quotes fields holding a comma, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// a GitHub flavoured table, columns of numbers right aligned
+fn markdown_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let numeric = numeric_columns(header.len(), rows);
+    let escape = |cell: &str| cell.replace('|', "\\|");
+    let mut text = format!(
+        "| {} |\n|{}|\n",
+        header.join(" | "),
+        numeric
+            .iter()
+            .map(|numeric| if *numeric { " ---: " } else { " --- " })
+            .collect::<Vec<_>>()
+            .join("|")
+    );
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| escape(cell)).collect();
+        text += &format!("| {} |\n", cells.join(" | "));
+    }
+    text
+}
+
+fn numeric_columns(columns: usize, rows: &[Vec<String>]) -> Vec<bool> {
+    (0..columns)
+        .map(|col| {
+            let mut cells = rows
+                .iter()
+                .map(|row| &row[col])
+                .filter(|cell| !cell.is_empty());
+            cells.clone().next().is_some() && cells.all(|cell| cell.parse::<f64>().is_ok())
+        })
+        .collect()
+}
+
+// columns separated by two spaces, columns of numbers (and blanks) right
+// aligned
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|title| title.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let numeric = numeric_columns(header.len(), rows);
+    let header: Vec<String> = header.iter().map(|title| title.to_string()).collect();
+    let mut text = String::new();
+    for row in std::iter::once(&header).chain(rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .zip(&numeric)
+            .map(|((cell, width), numeric)| {
+                let padding = " ".repeat(width - cell.chars().count());
+                if *numeric {
+                    format!("{}{}", padding, cell)
+                } else {
+                    format!("{}{}", cell, padding)
+                }
+            })
+            .collect();
+        text += cells.join("  ").trim_end();
+        text += "\n";
+    }
+    text
+}
@@ -0,0 +1,245 @@
+use crate::matrix::{Bound, MatrixProblem, SparseMatrix};
+use crate::AlpsError;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+// rounds of alternating row and column geometric means
+const PASSES: usize = 4;
+
+/// How [`scale`] picks the factors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalingMethod {
+    /// Divide every row, then every column, by its largest coefficient
+    Equilibrate,
+    /// Divide rows and columns by the geometric mean of their smallest and
+    /// largest coefficient, a few passes in turn
+    #[default]
+    GeometricMean,
+}
+
+impl ScalingMethod {
+    pub const ALL: [ScalingMethod; 2] = [ScalingMethod::Equilibrate, ScalingMethod::GeometricMean];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScalingMethod::Equilibrate => "equilibrate",
+            ScalingMethod::GeometricMean => "geometric_mean",
+        }
+    }
+}
+
+impl fmt::Display for ScalingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for ScalingMethod {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ScalingMethod::ALL
+            .into_iter()
+            .find(|method| method.as_str() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown scaling `{}`, expected equilibrate or geometric_mean",
+                    name
+                )
+            })
+    }
+}
+
+/// The factors [`scale`] applied, for debugging.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScalingReport {
+    pub method: ScalingMethod,
+    /// Largest over smallest absolute coefficient before scaling
+    pub ratio_before: Option<f64>,
+    /// Largest over smallest absolute coefficient after scaling
+    pub ratio_after: Option<f64>,
+    /// What every constraint was multiplied by
+    pub rows: IndexMap<String, f64>,
+    /// What every variable was divided by
+    pub columns: IndexMap<String, f64>,
+}
+
+/// A scaled problem with the factors to map its solution back.
+#[derive(Debug, Clone)]
+pub struct Scaled {
+    pub problem: MatrixProblem,
+    pub report: ScalingReport,
+    rows: Vec<f64>,
+    columns: Vec<f64>,
+}
+
+impl Scaled {
+    /// Maps values, and duals when the solver gave them, of the scaled
+    /// problem back onto the unscaled one.
+    pub fn unscale(&self, values: &[f64], duals: Option<&[f64]>) -> (Vec<f64>, Option<Vec<f64>>) {
+        let values = values
+            .iter()
+            .zip(&self.columns)
+            .map(|(value, factor)| value * factor)
+            .collect();
+        let duals = duals.map(|duals| {
+            duals
+                .iter()
+                .zip(&self.rows)
+                .map(|(dual, factor)| dual * factor)
+                .collect()
+        });
+        (values, duals)
+    }
+}
+
+// largest over smallest absolute nonzero coefficient
+fn ratio(rows: &[Vec<(usize, f64)>]) -> Option<f64> {
+    let values = rows.iter().flatten().map(|(_, value)| value.abs());
+    let values = values.filter(|value| *value != 0.0);
+    let (min, max) = values.fold((f64::INFINITY, 0.0f64), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+    (max > 0.0).then(|| max / min)
+}
+
+// the smallest and largest absolute scaled value of some entries
+fn extremes(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values
+        .map(f64::abs)
+        .filter(|value| *value != 0.0)
+        .fold(None, |range, value| match range {
+            None => Some((value, value)),
+            Some((min, max)) => Some((value.min(min), value.max(max))),
+        })
+}
+
+// the nearest power of two, so scaling doesn't round any digit away
+fn power_of_two(factor: f64) -> f64 {
+    if factor.is_finite() && factor > 0.0 {
+        factor.log2().round().exp2()
+    } else {
+        1.0
+    }
+}
+
+/// Multiplies every row and divides every column of a problem by a power
+/// of two so its coefficients are closer to one. Bounds, right hand sides
+/// and the objective are scaled along, [`Scaled::unscale`] maps a solution
+/// back.
+pub fn scale(problem: &MatrixProblem, method: ScalingMethod) -> Result<Scaled, AlpsError> {
+    problem.check()?;
+    let entries = problem.matrix.row_entries();
+    let mut rows = vec![1.0; entries.len()];
+    let mut columns = vec![1.0; problem.variables.len()];
+    let mut by_column = vec![vec![]; columns.len()];
+    for (row, row_entries) in entries.iter().enumerate() {
+        for (col, value) in row_entries {
+            by_column[*col].push((row, *value));
+        }
+    }
+
+    let passes = match method {
+        ScalingMethod::Equilibrate => 1,
+        ScalingMethod::GeometricMean => PASSES,
+    };
+    let factor = |(min, max): (f64, f64)| match method {
+        ScalingMethod::Equilibrate => 1.0 / max,
+        ScalingMethod::GeometricMean => 1.0 / (min * max).sqrt(),
+    };
+    for _ in 0..passes {
+        for (row, row_entries) in entries.iter().enumerate() {
+            let values = row_entries.iter().map(|(col, value)| value * columns[*col]);
+            if let Some(range) = extremes(values) {
+                rows[row] = factor(range);
+            }
+        }
+        for (col, column_entries) in by_column.iter().enumerate() {
+            let values = column_entries.iter().map(|(row, value)| value * rows[*row]);
+            if let Some(range) = extremes(values) {
+                columns[col] = factor(range);
+            }
+        }
+    }
+    let rows: Vec<f64> = rows.into_iter().map(power_of_two).collect();
+    let columns: Vec<f64> = columns.into_iter().map(power_of_two).collect();
+
+    let scaled_entries: Vec<Vec<(usize, f64)>> = entries
+        .iter()
+        .enumerate()
+        .map(|(row, row_entries)| {
+            row_entries
+                .iter()
+                .map(|(col, value)| (*col, value * rows[row] * columns[*col]))
+                .collect()
+        })
+        .collect();
+    let scaled = MatrixProblem {
+        variables: problem.variables.clone(),
+        goal: problem.goal.clone(),
+        objective: problem
+            .objective
+            .iter()
+            .zip(&columns)
+            .map(|(cost, factor)| cost * factor)
+            .collect(),
+        objective_constant: problem.objective_constant,
+        matrix: SparseMatrix::Triplet {
+            rows: entries.len(),
+            cols: columns.len(),
+            entries: scaled_entries
+                .iter()
+                .enumerate()
+                .flat_map(|(row, row_entries)| {
+                    row_entries
+                        .iter()
+                        .map(move |(col, value)| (row, *col, *value))
+                })
+                .collect(),
+        },
+        senses: problem.senses.clone(),
+        rhs: problem
+            .rhs
+            .iter()
+            .zip(&rows)
+            .map(|(rhs, factor)| rhs * factor)
+            .collect(),
+        bounds: problem
+            .bounds
+            .iter()
+            .zip(&columns)
+            .map(|(bound, factor)| Bound {
+                min: bound.min.map(|min| min / factor),
+                max: bound.max.map(|max| max / factor),
+            })
+            .collect(),
+        constraint_names: problem.constraint_names.clone(),
+    };
+
+    let report = ScalingReport {
+        method,
+        ratio_before: ratio(&entries),
+        ratio_after: ratio(&scaled_entries),
+        rows: problem
+            .constraint_names
+            .iter()
+            .cloned()
+            .zip(rows.iter().copied())
+            .collect(),
+        columns: problem
+            .variables
+            .iter()
+            .cloned()
+            .zip(columns.iter().copied())
+            .collect(),
+    };
+    Ok(Scaled {
+        problem: scaled,
+        report,
+        rows,
+        columns,
+    })
+}
@@ -0,0 +1,235 @@
+use crate::jobs::{JobError, JobQueue, JobsConfig};
+use crate::{solve_catching_panics, validate, AlpsError, SolveOptions, Solver, UnoptimizedProblem};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response};
+
+/// Limits applied by [`Server`] to every request.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Largest accepted request body, larger bodies get a `413`
+    pub max_body_bytes: usize,
+    /// The algorithm `/solve` uses
+    pub solver: Solver,
+    /// Longest a single solve may take. A solve that reaches it with a
+    /// feasible point answers with the best one so far and status
+    /// `time_limit`, one that doesn't gets a `504`
+    pub time_limit: Option<Duration>,
+    /// Number of requests handled at the same time
+    pub threads: usize,
+    /// The background queue behind `/jobs`
+    pub jobs: JobsConfig,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            max_body_bytes: 4 * 1024 * 1024,
+            solver: Solver::default(),
+            time_limit: Some(Duration::from_secs(60)),
+            threads: 4,
+            jobs: JobsConfig::default(),
+        }
+    }
+}
+
+/// A JSON response, the status code and its body.
+#[derive(Debug, Clone, PartialEq)]
+struct Reply {
+    status: u16,
+    body: Value,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Reply {
+        Reply { status: 200, body }
+    }
+
+    // errors always have the same shape, `{"error": {"kind", "message"}}`
+    fn error(status: u16, kind: &str, message: impl Into<String>) -> Reply {
+        let message: String = message.into();
+        Reply {
+            status,
+            body: json!({ "error": { "kind": kind, "message": message } }),
+        }
+    }
+}
+
+impl From<JobError> for Reply {
+    fn from(error: JobError) -> Self {
+        match error {
+            JobError::QueueFull => Reply::error(503, "queue_full", "the job queue is full"),
+            JobError::NotFound => Reply::error(404, "not_found", "no job with this id"),
+            JobError::Finished(status) => Reply::error(
+                409,
+                "job_finished",
+                format!("the job already finished as {:?}", status),
+            ),
+            JobError::Persistence(message) => Reply::error(500, "persistence", message),
+        }
+    }
+}
+
+impl From<AlpsError> for Reply {
+    fn from(error: AlpsError) -> Self {
+        let status = match error {
+            AlpsError::InvalidInput(_) | AlpsError::Parse(_) | AlpsError::InvalidModel(_) => 400,
+            AlpsError::Infeasible | AlpsError::Unbounded => 422,
+            AlpsError::Solver(_) => 500,
+            AlpsError::Stopped(_) => 504,
+        };
+        Reply {
+            status,
+            body: json!({ "error": error }),
+        }
+    }
+}
+
+/// Serves `solve` and `validate` over HTTP.
+///
+/// * `POST /solve`, a problem in and a [`SolutionResponse`](crate::SolutionResponse) out
+/// * `POST /validate`, a problem in and `{"valid", "issues"}` out
+/// * `GET /health`
+/// * `POST /jobs`, a problem in and `{"id", "status"}` out, solved in the background
+/// * `GET /jobs/{id}`, the status, incumbent objective and gap of a job
+/// * `DELETE /jobs/{id}`, cancels a job
+///
+/// Failures are answered with `{"error": {"kind": "...", "message": "..."}}`,
+/// errors from parsing or solving are the serialized [`AlpsError`].
+pub struct Server {
+    http: tiny_http::Server,
+    config: ServerConfig,
+    jobs: JobQueue,
+}
+
+impl Server {
+    /// Binds to `address`, use port `0` to pick a free port.
+    pub fn bind(address: &str, config: ServerConfig) -> std::io::Result<Server> {
+        let http = tiny_http::Server::http(address)
+            .map_err(|error| std::io::Error::other(error.to_string()))?;
+        let jobs = JobQueue::new(config.jobs.clone())?;
+        Ok(Server { http, config, jobs })
+    }
+
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Handles requests on `config.threads` threads, blocking until the
+    /// listener is closed.
+    pub fn run(&self) {
+        std::thread::scope(|scope| {
+            for _ in 0..self.config.threads.max(1) {
+                scope.spawn(|| {
+                    while let Ok(request) = self.http.recv() {
+                        self.handle(request);
+                    }
+                });
+            }
+        });
+    }
+
+    fn handle(&self, mut request: Request) {
+        let reply = match read_body(&mut request, self.config.max_body_bytes) {
+            Ok(body) => {
+                let path = request.url().split('?').next().unwrap_or("").to_string();
+                self.route(request.method(), &path, &body)
+            }
+            Err(reply) => reply,
+        };
+        let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(reply.body.to_string())
+            .with_status_code(reply.status)
+            .with_header(header);
+        // the client may already be gone, there is nobody left to tell
+        let _ = request.respond(response);
+    }
+
+    fn route(&self, method: &Method, path: &str, body: &[u8]) -> Reply {
+        match (method, path) {
+            (Method::Get, "/health") => {
+                Reply::ok(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+            }
+            (Method::Post, "/solve") => match parse_problem(body) {
+                Ok(problem) => {
+                    let options = SolveOptions {
+                        solver: self.config.solver,
+                        time_limit: self.config.time_limit.map(|limit| limit.as_secs_f64()),
+                        ..SolveOptions::default()
+                    };
+                    match solve_catching_panics(problem, &options) {
+                        Ok(solution) => Reply::ok(serde_json::to_value(solution).unwrap()),
+                        Err(error) => error.into(),
+                    }
+                }
+                Err(error) => error.into(),
+            },
+            (Method::Post, "/validate") => match serde_json::from_slice::<Value>(body) {
+                Ok(document) => {
+                    let issues = validate(&document);
+                    Reply::ok(json!({ "valid": issues.is_empty(), "issues": issues }))
+                }
+                Err(error) => AlpsError::from(error).into(),
+            },
+            (Method::Post, "/jobs") => match parse_problem(body) {
+                Ok(problem) => match self.jobs.submit(problem) {
+                    Ok(id) => Reply {
+                        status: 202,
+                        body: json!({ "id": id, "status": "queued" }),
+                    },
+                    Err(error) => error.into(),
+                },
+                Err(error) => error.into(),
+            },
+            (Method::Get, _) if path.starts_with("/jobs/") => {
+                match self.jobs.get(&path["/jobs/".len()..]) {
+                    Some(job) => Reply::ok(serde_json::to_value(job).unwrap()),
+                    None => JobError::NotFound.into(),
+                }
+            }
+            (Method::Delete, _) if path.starts_with("/jobs/") => {
+                match self.jobs.cancel(&path["/jobs/".len()..]) {
+                    Ok(job) => Reply::ok(serde_json::to_value(job).unwrap()),
+                    Err(error) => error.into(),
+                }
+            }
+            (_, "/health" | "/solve" | "/validate" | "/jobs") => Reply::error(
+                405,
+                "method_not_allowed",
+                format!("{} is not allowed", method),
+            ),
+            _ => Reply::error(404, "not_found", format!("no route for {}", path)),
+        }
+    }
+}
+
+// reads the whole body, refusing anything above `limit` bytes
+fn read_body(request: &mut Request, limit: usize) -> Result<Vec<u8>, Reply> {
+    let too_large = || {
+        Reply::error(
+            413,
+            "payload_too_large",
+            format!("request bodies are limited to {} bytes", limit),
+        )
+    };
+    if request.body_length().is_some_and(|length| length > limit) {
+        return Err(too_large());
+    }
+    let mut body = vec![];
+    request
+        .as_reader()
+        .take(limit as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|error| Reply::error(400, "invalid_input", error.to_string()))?;
+    if body.len() > limit {
+        return Err(too_large());
+    }
+    Ok(body)
+}
+
+fn parse_problem(body: &[u8]) -> Result<UnoptimizedProblem, AlpsError> {
+    Ok(serde_json::from_slice(body)?)
+}
@@ -0,0 +1,924 @@
+use crate::limits::{SolveStatus, Stop};
+use crate::matrix::{MatrixProblem, Sense};
+use crate::progress::{Phase, SolveEvent};
+use crate::warm::{basis, Basis, Start, VariableStatus, WarmStart, WarmStartReport};
+use crate::{objective_direction, AlpsError};
+use good_lp::solvers::ObjectiveDirection;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+
+// values closer to zero than this are treated as zero
+const EPSILON: f64 = 1e-9;
+
+// degenerate pivots in a row before switching to Bland's rule
+const DEGENERATE_LIMIT: usize = 50;
+
+// times the final basis is rebuilt from the original rows
+const REFACTORS: usize = 3;
+
+/// The optimum found by [`solve_simplex`], indexed like the columns and rows
+/// of the [`MatrixProblem`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimplexSolution {
+    pub values: Vec<f64>,
+    /// Objective value, including the constant
+    pub objective: f64,
+    /// Change of the objective per unit increase of each row's rhs
+    pub duals: Vec<f64>,
+    /// Change of the objective per unit increase of each column, with the
+    /// other nonbasic columns held fixed
+    pub reduced_costs: Vec<f64>,
+    /// Whether each column is basic at the optimum, the others sit at a
+    /// bound
+    pub basic: Vec<bool>,
+    /// Number of pivots over both phases
+    pub iterations: usize,
+    /// The final basis, to warm start a later solve
+    pub basis: Basis,
+    /// What became of the warm start, when there was one
+    pub warm_start: Option<WarmStartReport>,
+    /// Optimal, or why the solve stopped at a feasible basis before that
+    pub status: SolveStatus,
+}
+
+/// Numbers the simplex runs on: `f64` with tolerances, or exact rationals
+/// where every tolerance is zero.
+pub(crate) trait Scalar: Clone + PartialOrd + Signed + ToPrimitive {
+    // whether pivots round, so the final basis is worth rebuilding
+    const ROUNDS: bool;
+    // values this close to zero are treated as zero
+    fn epsilon() -> Self;
+    // entries smaller than this are dropped after a pivot
+    fn negligible() -> Self;
+    // phase one residual, relative to the rhs, that still counts as feasible
+    fn feasibility() -> Self;
+    // how close, relative to it, a starting value must be to a bound or rhs
+    // to sit on it, loose enough for values that went through `f64`
+    fn closeness() -> Self;
+}
+
+impl Scalar for f64 {
+    const ROUNDS: bool = true;
+
+    fn epsilon() -> Self {
+        EPSILON
+    }
+
+    fn negligible() -> Self {
+        EPSILON * 1e-3
+    }
+
+    fn feasibility() -> Self {
+        1e-7
+    }
+
+    fn closeness() -> Self {
+        1e-7
+    }
+}
+
+impl Scalar for BigRational {
+    const ROUNDS: bool = false;
+
+    fn epsilon() -> Self {
+        BigRational::zero()
+    }
+
+    fn negligible() -> Self {
+        BigRational::zero()
+    }
+
+    fn feasibility() -> Self {
+        BigRational::zero()
+    }
+
+    fn closeness() -> Self {
+        BigRational::new(1.into(), 10_000_000.into())
+    }
+}
+
+/// A problem handed to [`optimise`], in the numbers the simplex runs on.
+pub(crate) struct LinearProgram<T> {
+    pub rows: Vec<Vec<(usize, T)>>,
+    pub senses: Vec<Sense>,
+    pub rhs: Vec<T>,
+    pub objective: Vec<T>,
+    pub constant: T,
+    /// Lower and upper bound of every column
+    pub bounds: Vec<(Option<T>, Option<T>)>,
+    pub minimise: bool,
+}
+
+/// How [`optimise`] ended, an unbounded problem is an error.
+pub(crate) enum Outcome<T> {
+    Optimal(Optimum<T>),
+    /// Multipliers of the rows that prove no point satisfies them all: `>=`
+    /// rows get a nonnegative one, `<=` rows a nonpositive one, and their
+    /// combination can't hold within the bounds
+    Infeasible(Vec<T>),
+}
+
+/// What [`optimise`] found, laid out like [`SimplexSolution`].
+pub(crate) struct Optimum<T> {
+    pub values: Vec<T>,
+    pub objective: T,
+    pub duals: Vec<T>,
+    pub reduced_costs: Vec<T>,
+    pub basic: Vec<bool>,
+    pub iterations: usize,
+    pub statuses: Vec<VariableStatus>,
+    /// Whether each row's slack is basic
+    pub slack: Vec<bool>,
+    /// Why the warm start wasn't used
+    pub rejected: Option<String>,
+    pub status: SolveStatus,
+}
+
+// how a column of the problem maps onto nonnegative tableau columns
+#[derive(Clone)]
+enum Column<T> {
+    // x = min + z
+    Shifted(usize, T),
+    // x = max - z
+    Mirrored(usize, T),
+    // x = z+ - z-
+    Split(usize, usize),
+}
+
+impl<T: Scalar> Column<T> {
+    fn is_basic(&self, basic: &[bool]) -> bool {
+        match *self {
+            Column::Shifted(col, _) | Column::Mirrored(col, _) => basic[col],
+            Column::Split(positive, negative) => basic[positive] || basic[negative],
+        }
+    }
+
+    fn value(&self, z: &[T]) -> T {
+        match self {
+            Column::Shifted(col, min) => min.clone() + z[*col].clone(),
+            Column::Mirrored(col, max) => max.clone() - z[*col].clone(),
+            Column::Split(positive, negative) => z[*positive].clone() - z[*negative].clone(),
+        }
+    }
+}
+
+// a row as `entries <sense> rhs` over the nonnegative columns
+type Row<T> = (Vec<(usize, T)>, Sense, T);
+
+struct Tableau<T> {
+    // m rows of `columns + 1` entries, the last one is the rhs
+    rows: Vec<Vec<T>>,
+    basis: Vec<usize>,
+    columns: usize,
+    iterations: usize,
+}
+
+impl<T: Scalar> Tableau<T> {
+    fn rhs(&self, row: usize) -> &T {
+        &self.rows[row][self.columns]
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        let pivot = self.rows[row][col].clone();
+        for value in &mut self.rows[row] {
+            *value = value.clone() / pivot.clone();
+        }
+        let pivot_row = self.rows[row].clone();
+        let negligible = T::negligible();
+        for (other, values) in self.rows.iter_mut().enumerate() {
+            let factor = values[col].clone();
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot_value) in values.iter_mut().zip(&pivot_row) {
+                if pivot_value.is_zero() {
+                    continue;
+                }
+                *value = value.clone() - factor.clone() * pivot_value.clone();
+                if value.abs() < negligible {
+                    *value = T::zero();
+                }
+            }
+        }
+        self.basis[row] = col;
+        self.iterations += 1;
+    }
+
+    // minimises `costs · z` from the current feasible basis, only letting
+    // the columns marked in `allowed` enter, until optimal or stopped,
+    // calling `pivoted` with the objective after every pivot
+    fn optimise(
+        &mut self,
+        costs: &[T],
+        allowed: &[bool],
+        stop: &Stop,
+        mut pivoted: impl FnMut(usize, T),
+    ) -> Result<SolveStatus, AlpsError> {
+        let epsilon = T::epsilon();
+        let mut degenerate = 0;
+        loop {
+            let reduced = self.reduced_costs(costs);
+            let candidates =
+                (0..self.columns).filter(|col| allowed[*col] && reduced[*col] < -epsilon.clone());
+            // Dantzig's rule, falling back to Bland's rule against cycling
+            let entering = if degenerate < DEGENERATE_LIMIT {
+                candidates.min_by(|a, b| {
+                    reduced[*a]
+                        .partial_cmp(&reduced[*b])
+                        .unwrap_or(Ordering::Equal)
+                })
+            } else {
+                candidates.min()
+            };
+            let Some(col) = entering else {
+                return Ok(SolveStatus::Optimal);
+            };
+            if let Some(status) = stop.check(self.iterations) {
+                return Ok(status);
+            }
+
+            let mut leaving: Option<(usize, T)> = None;
+            for row in 0..self.rows.len() {
+                let value = &self.rows[row][col];
+                if *value <= epsilon {
+                    continue;
+                }
+                let ratio = self.rhs(row).clone() / value.clone();
+                let better = match &leaving {
+                    None => true,
+                    Some((best, best_ratio)) => {
+                        ratio < best_ratio.clone() - epsilon.clone()
+                            || (ratio <= best_ratio.clone() + epsilon.clone()
+                                && self.basis[row] < self.basis[*best])
+                    }
+                };
+                if better {
+                    leaving = Some((row, ratio));
+                }
+            }
+            let Some((row, ratio)) = leaving else {
+                return Err(AlpsError::Unbounded);
+            };
+            degenerate = if ratio.abs() <= epsilon {
+                degenerate + 1
+            } else {
+                0
+            };
+            self.pivot(row, col);
+            pivoted(self.iterations, self.objective(costs));
+        }
+    }
+
+    fn reduced_costs(&self, costs: &[T]) -> Vec<T> {
+        let mut reduced = costs.to_vec();
+        for (row, values) in self.rows.iter().enumerate() {
+            let cost = &costs[self.basis[row]];
+            if !cost.is_zero() {
+                for (col, value) in values[..self.columns].iter().enumerate() {
+                    if !value.is_zero() {
+                        reduced[col] = reduced[col].clone() - cost.clone() * value.clone();
+                    }
+                }
+            }
+        }
+        reduced
+    }
+
+    fn objective(&self, costs: &[T]) -> T {
+        (0..self.rows.len()).fold(T::zero(), |sum, row| {
+            sum + costs[self.basis[row]].clone() * self.rhs(row).clone()
+        })
+    }
+
+    // pivots `desired` into the basis, in place of columns that aren't
+    fn install(&mut self, desired: &[usize]) -> Result<(), String> {
+        let mut wanted = vec![false; self.columns];
+        for col in desired {
+            wanted[*col] = true;
+        }
+        for col in desired {
+            if self.basis.contains(col) {
+                continue;
+            }
+            let row = (0..self.rows.len())
+                .filter(|row| !wanted[self.basis[*row]])
+                .filter(|row| self.rows[*row][*col].abs() > T::epsilon())
+                .max_by(|a, b| {
+                    let (a, b) = (self.rows[*a][*col].abs(), self.rows[*b][*col].abs());
+                    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+                });
+            let Some(row) = row else {
+                return Err("the basis is singular for this model".to_string());
+            };
+            self.pivot(row, *col);
+        }
+        Ok(())
+    }
+
+    // the same basis pivoted in afresh from the `original` rows, without the
+    // rounding the pivots that led here picked up, `None` when it is
+    // singular or infeasible that way
+    fn refactor(&self, original: &Tableau<T>) -> Option<Tableau<T>> {
+        let mut fresh = Tableau {
+            rows: original.rows.clone(),
+            basis: original.basis.clone(),
+            columns: self.columns,
+            iterations: 0,
+        };
+        fresh.install(&self.basis).ok()?;
+        fresh.iterations = self.iterations;
+        let tolerance = T::feasibility();
+        for row in 0..fresh.rows.len() {
+            let rhs = fresh.rhs(row).clone();
+            if rhs < -(tolerance.clone() * (T::one() + rhs.abs())) {
+                return None;
+            }
+            if rhs.is_negative() {
+                fresh.rows[row][fresh.columns] = T::zero();
+            }
+        }
+        Some(fresh)
+    }
+
+    // how far the basic values are from satisfying the `original` rows
+    fn residual(&self, original: &Tableau<T>) -> T {
+        let mut z = vec![T::zero(); self.columns];
+        for (row, col) in self.basis.iter().enumerate() {
+            z[*col] = self.rhs(row).clone();
+        }
+        original.rows.iter().fold(T::zero(), |worst, row| {
+            let activity = (0..self.columns)
+                .filter(|col| !z[*col].is_zero())
+                .fold(T::zero(), |sum, col| {
+                    sum + row[col].clone() * z[col].clone()
+                });
+            let residual = (activity - row[self.columns].clone()).abs();
+            if residual > worst {
+                residual
+            } else {
+                worst
+            }
+        })
+    }
+
+    // pivots the artificials left in the basis out where possible, the ones
+    // that stay belong to redundant rows and remain zero
+    fn drive_out(&mut self, is_artificial: &[bool]) {
+        let epsilon = T::epsilon();
+        for row in 0..self.rows.len() {
+            if is_artificial[self.basis[row]] {
+                let replacement = (0..self.columns)
+                    .find(|col| !is_artificial[*col] && self.rows[row][*col].abs() > epsilon);
+                if let Some(col) = replacement {
+                    self.pivot(row, col);
+                }
+            }
+        }
+    }
+
+    // checks an installed basis is feasible, turning free columns basic at
+    // a negative value onto their negative part
+    fn settle(&mut self, columns: &[Column<T>], is_artificial: &[bool]) -> Result<(), String> {
+        for column in columns {
+            if let Column::Split(positive, negative) = column {
+                let row = self.basis.iter().position(|col| col == positive);
+                if let Some(row) = row.filter(|row| self.rhs(*row).is_negative()) {
+                    self.pivot(row, *negative);
+                }
+            }
+        }
+        let tolerance = T::feasibility();
+        for row in 0..self.rows.len() {
+            let rhs = self.rhs(row).clone();
+            let scale = T::one() + rhs.abs();
+            let artificial = is_artificial[self.basis[row]];
+            if rhs < -(tolerance.clone() * scale.clone())
+                || (artificial && rhs > tolerance.clone() * scale)
+            {
+                return Err("the starting point is not feasible for this model".to_string());
+            }
+            if rhs.is_negative() {
+                self.rows[row][self.columns] = T::zero();
+            }
+        }
+        Ok(())
+    }
+
+    // c_B B^-1 for the first `count` rows, B^-1 sits under the columns that
+    // started as the identity
+    fn multipliers(&self, costs: &[T], identity: &[usize], count: usize) -> Vec<T> {
+        (0..count)
+            .map(|row| {
+                (0..self.rows.len()).fold(T::zero(), |sum, basic| {
+                    sum + costs[self.basis[basic]].clone() * self.rows[basic][identity[row]].clone()
+                })
+            })
+            .collect()
+    }
+}
+
+/// Solves a problem in matrix form with a dense two-phase primal simplex.
+///
+/// Unlike the default solver it reports duals, reduced costs and the number
+/// of iterations. Every column is shifted onto a nonnegative one, finite
+/// upper bounds become extra rows.
+pub fn solve_simplex(problem: &MatrixProblem) -> Result<SimplexSolution, AlpsError> {
+    solve_simplex_from(problem, None)
+}
+
+/// Like [`solve_simplex`], starting from the basis a warm start gives when
+/// it is feasible for the problem and from scratch otherwise.
+pub fn solve_simplex_from(
+    problem: &MatrixProblem,
+    warm_start: Option<&WarmStart>,
+) -> Result<SimplexSolution, AlpsError> {
+    simplex_within(problem, warm_start, &Stop::default())
+}
+
+// the simplex under the limits of `stop`
+pub(crate) fn simplex_within(
+    problem: &MatrixProblem,
+    warm_start: Option<&WarmStart>,
+    stop: &Stop,
+) -> Result<SimplexSolution, AlpsError> {
+    problem.check()?;
+    let start =
+        warm_start.map(|warm| warm.start(&problem.variables, &problem.constraint_names, Some));
+    let Outcome::Optimal(optimum) = optimise(&program(problem), start.as_ref(), stop)? else {
+        return Err(AlpsError::Infeasible);
+    };
+    let accepted = warm_start.is_some() && optimum.rejected.is_none();
+    let baseline = warm_start.map_or(optimum.iterations, |warm| {
+        warm.baseline(optimum.iterations, accepted)
+    });
+    Ok(SimplexSolution {
+        values: optimum.values,
+        objective: optimum.objective,
+        duals: optimum.duals.into_iter().map(clean).collect(),
+        reduced_costs: optimum.reduced_costs.into_iter().map(clean).collect(),
+        basic: optimum.basic,
+        iterations: optimum.iterations,
+        basis: basis(
+            &problem.variables,
+            &problem.constraint_names,
+            &optimum.statuses,
+            &optimum.slack,
+            baseline,
+        ),
+        warm_start: warm_start.map(|warm| warm.report(optimum.iterations, optimum.rejected)),
+        status: optimum.status,
+    })
+}
+
+// a checked problem in the form `optimise` takes
+pub(crate) fn program(problem: &MatrixProblem) -> LinearProgram<f64> {
+    LinearProgram {
+        rows: problem.matrix.row_entries(),
+        senses: problem.senses.clone(),
+        rhs: problem.rhs.clone(),
+        objective: problem.objective.clone(),
+        constant: problem.objective_constant,
+        bounds: problem
+            .bounds
+            .iter()
+            .map(|bound| {
+                (
+                    bound.min.filter(|min| min.is_finite()),
+                    bound.max.filter(|max| max.is_finite()),
+                )
+            })
+            .collect(),
+        minimise: objective_direction(&problem.goal) == ObjectiveDirection::Minimisation,
+    }
+}
+
+/// The two-phase simplex behind [`solve_simplex`], for any [`Scalar`].
+pub(crate) fn optimise<T: Scalar>(
+    problem: &LinearProgram<T>,
+    start: Option<&Start<T>>,
+    stop: &Stop,
+) -> Result<Outcome<T>, AlpsError> {
+    // lay out the nonnegative columns
+    let mut columns = vec![];
+    let mut width = 0;
+    let mut bound_rows = vec![];
+    // the row holding each column's upper bound, when it has one
+    let mut bound_row = vec![None; problem.bounds.len()];
+    for (col, bound) in problem.bounds.iter().enumerate() {
+        let column = match bound.clone() {
+            (Some(min), max) => {
+                if let Some(max) = max {
+                    bound_row[col] = Some(problem.rhs.len() + bound_rows.len());
+                    bound_rows.push((width, max - min.clone()));
+                }
+                Column::Shifted(width, min)
+            }
+            (None, Some(max)) => Column::Mirrored(width, max),
+            (None, None) => {
+                width += 1;
+                Column::Split(width - 1, width)
+            }
+        };
+        width += 1;
+        columns.push(column);
+    }
+
+    // every row over the nonnegative columns
+    let mut rows: Vec<Row<T>> = vec![];
+    for (row, entries) in problem.rows.iter().enumerate() {
+        let mut shifted = vec![];
+        let mut rhs = problem.rhs[row].clone();
+        for (col, value) in entries {
+            match &columns[*col] {
+                Column::Shifted(z, min) => {
+                    shifted.push((*z, value.clone()));
+                    rhs = rhs - value.clone() * min.clone();
+                }
+                Column::Mirrored(z, max) => {
+                    shifted.push((*z, -value.clone()));
+                    rhs = rhs - value.clone() * max.clone();
+                }
+                Column::Split(positive, negative) => {
+                    shifted.push((*positive, value.clone()));
+                    shifted.push((*negative, -value.clone()));
+                }
+            }
+        }
+        rows.push((shifted, problem.senses[row], rhs));
+    }
+    for (z, range) in bound_rows {
+        rows.push((vec![(z, T::one())], Sense::Le, range));
+    }
+
+    // flip rows with a negative rhs, then give each row a column of the
+    // identity: a slack for `<=`, an artificial for `>=` and `==`
+    let m = rows.len();
+    let mut flips = vec![T::one(); m];
+    let mut slacks = vec![];
+    let mut slack_of = vec![None; m];
+    let mut identity = vec![0; m];
+    let mut artificial = vec![];
+    let mut next = width;
+    for (row, (_, sense, rhs)) in rows.iter_mut().enumerate() {
+        if rhs.is_negative() {
+            flips[row] = -T::one();
+            *sense = match sense {
+                Sense::Le => Sense::Ge,
+                Sense::Ge => Sense::Le,
+                Sense::Eq => Sense::Eq,
+            };
+        }
+        match sense {
+            Sense::Le => {
+                slacks.push((row, next, T::one()));
+                slack_of[row] = Some(next);
+                identity[row] = next;
+                next += 1;
+            }
+            Sense::Ge => {
+                slacks.push((row, next, -T::one()));
+                slack_of[row] = Some(next);
+                identity[row] = next + 1;
+                artificial.push(next + 1);
+                next += 2;
+            }
+            Sense::Eq => {
+                identity[row] = next;
+                artificial.push(next);
+                next += 1;
+            }
+        }
+    }
+    let total = next;
+
+    let mut tableau = Tableau {
+        rows: vec![vec![T::zero(); total + 1]; m],
+        basis: identity.clone(),
+        columns: total,
+        iterations: 0,
+    };
+    for (row, (entries, _, rhs)) in rows.iter().enumerate() {
+        for (z, value) in entries {
+            let cell = &mut tableau.rows[row][*z];
+            *cell = cell.clone() + flips[row].clone() * value.clone();
+        }
+        tableau.rows[row][identity[row]] = T::one();
+        tableau.rows[row][total] = flips[row].clone() * rhs.clone();
+    }
+    for (row, col, value) in slacks {
+        tableau.rows[row][col] = value;
+    }
+
+    // kept to rebuild the final basis from
+    let original = T::ROUNDS.then(|| Tableau {
+        rows: tableau.rows.clone(),
+        basis: tableau.basis.clone(),
+        columns: total,
+        iterations: 0,
+    });
+
+    let mut is_artificial = vec![false; total];
+    for col in &artificial {
+        is_artificial[*col] = true;
+    }
+
+    // a warm start replaces phase one when its basis is feasible
+    let mut rejected = None;
+    let mut warm = false;
+    if let Some(start) = start {
+        let statuses = match start {
+            Start::Values(values) => crash(problem, values),
+            Start::Statuses { variables, slack } => (variables.clone(), slack.clone()),
+        };
+        let desired = desired_columns(&columns, &bound_row, &slack_of, &statuses);
+        let mut started = Tableau {
+            rows: tableau.rows.clone(),
+            basis: tableau.basis.clone(),
+            columns: total,
+            iterations: 0,
+        };
+        let installed = if desired.len() > m {
+            Err(format!(
+                "the basis has {} columns for {} rows",
+                desired.len(),
+                m
+            ))
+        } else {
+            started.install(&desired)
+        };
+        match installed.and_then(|()| started.settle(&columns, &is_artificial)) {
+            Ok(()) => {
+                started.drive_out(&is_artificial);
+                started.iterations = 0;
+                tableau = started;
+                warm = true;
+            }
+            Err(reason) => rejected = Some(reason),
+        }
+    }
+
+    // phase one, minimise the sum of the artificials
+    if !warm && !artificial.is_empty() {
+        stop.phase(Phase::Feasibility);
+        let costs: Vec<T> = is_artificial
+            .iter()
+            .map(|a| if *a { T::one() } else { T::zero() })
+            .collect();
+        let status = tableau.optimise(&costs, &vec![true; total], stop, |iterations, sum| {
+            stop.emit(SolveEvent::Iteration {
+                phase: Phase::Feasibility,
+                iterations,
+                objective: float(&sum),
+            })
+        })?;
+        if !status.is_optimal() {
+            return Err(AlpsError::Stopped(format!(
+                "{} before a feasible solution was found",
+                status
+            )));
+        }
+        let scale = tableau.rows.iter().fold(T::one(), |scale, row| {
+            let rhs = row[total].abs();
+            if rhs > scale {
+                rhs
+            } else {
+                scale
+            }
+        });
+        if tableau.objective(&costs) > T::feasibility() * scale {
+            let farkas = tableau.multipliers(&costs, &identity, problem.rhs.len());
+            let farkas = farkas.into_iter().zip(&flips);
+            return Ok(Outcome::Infeasible(
+                farkas.map(|(y, flip)| y * flip.clone()).collect(),
+            ));
+        }
+        tableau.drive_out(&is_artificial);
+    }
+
+    // phase two on the real objective, always minimising
+    stop.phase(Phase::Lp);
+    let sign = if problem.minimise {
+        T::one()
+    } else {
+        -T::one()
+    };
+    let mut costs = vec![T::zero(); total];
+    // the objective of the columns at zero, what shifting them moved
+    let mut offset = problem.constant.clone();
+    for (col, value) in problem.objective.iter().enumerate() {
+        let cost = sign.clone() * value.clone();
+        match &columns[col] {
+            Column::Shifted(z, min) => {
+                costs[*z] = costs[*z].clone() + cost;
+                offset = offset + value.clone() * min.clone();
+            }
+            Column::Mirrored(z, max) => {
+                costs[*z] = costs[*z].clone() - cost;
+                offset = offset + value.clone() * max.clone();
+            }
+            Column::Split(positive, negative) => {
+                costs[*positive] = costs[*positive].clone() + cost.clone();
+                costs[*negative] = costs[*negative].clone() - cost;
+            }
+        }
+    }
+    let real = |value: T| float(&(offset.clone() + sign.clone() * value));
+    let mut incumbent = real(tableau.objective(&costs));
+    stop.emit(SolveEvent::Incumbent {
+        objective: incumbent,
+    });
+    let allowed: Vec<bool> = is_artificial.iter().map(|a| !a).collect();
+    let mut pivoted = |iterations, value| {
+        let objective = real(value);
+        stop.emit(SolveEvent::Iteration {
+            phase: Phase::Lp,
+            iterations,
+            objective,
+        });
+        if objective != incumbent {
+            incumbent = objective;
+            stop.emit(SolveEvent::Incumbent { objective });
+        }
+    };
+    let mut status = tableau.optimise(&costs, &allowed, stop, &mut pivoted)?;
+    // rebuild the final basis so the values and multipliers don't carry the
+    // rounding of every pivot, and carry on if that shows it isn't optimal
+    if let Some(original) = &original {
+        for _ in 0..REFACTORS {
+            let Some(fresh) = tableau.refactor(original) else {
+                break;
+            };
+            if fresh.residual(original) >= tableau.residual(original) {
+                break;
+            }
+            tableau = fresh;
+            let pivots = tableau.iterations;
+            if status.is_optimal() {
+                status = tableau.optimise(&costs, &allowed, stop, &mut pivoted)?;
+            }
+            if tableau.iterations == pivots {
+                break;
+            }
+        }
+    }
+
+    let mut z = vec![T::zero(); total];
+    for (row, col) in tableau.basis.iter().enumerate() {
+        z[*col] = tableau.rhs(row).clone();
+    }
+    let values: Vec<T> = columns.iter().map(|column| column.value(&z)).collect();
+    let mut in_basis = vec![false; total];
+    for col in &tableau.basis {
+        in_basis[*col] = true;
+    }
+    let basic = columns
+        .iter()
+        .map(|column| column.is_basic(&in_basis))
+        .collect();
+    let statuses = columns
+        .iter()
+        .zip(&bound_row)
+        .map(|(column, bound)| match column {
+            Column::Shifted(z, _) if in_basis[*z] => match bound.and_then(|row| slack_of[row]) {
+                Some(slack) if !in_basis[slack] => VariableStatus::Upper,
+                _ => VariableStatus::Basic,
+            },
+            Column::Mirrored(z, _) if !in_basis[*z] => VariableStatus::Upper,
+            column if column.is_basic(&in_basis) => VariableStatus::Basic,
+            _ => VariableStatus::Lower,
+        })
+        .collect();
+    let slack = slack_of[..problem.rhs.len()]
+        .iter()
+        .map(|slack| slack.is_some_and(|col| in_basis[col]))
+        .collect();
+    let objective = problem
+        .objective
+        .iter()
+        .zip(&values)
+        .fold(problem.constant.clone(), |sum, (cost, value)| {
+            sum + cost.clone() * value.clone()
+        });
+
+    let duals: Vec<T> = tableau
+        .multipliers(&costs, &identity, problem.rhs.len())
+        .into_iter()
+        .zip(&flips)
+        .map(|(dual, flip)| sign.clone() * flip.clone() * dual)
+        .collect();
+    let mut reduced_costs = problem.objective.clone();
+    for (row, entries) in problem.rows.iter().enumerate() {
+        for (col, value) in entries {
+            reduced_costs[*col] = reduced_costs[*col].clone() - value.clone() * duals[row].clone();
+        }
+    }
+
+    if status.is_optimal() {
+        stop.emit(SolveEvent::Bound {
+            bound: float(&objective),
+        });
+    }
+
+    Ok(Outcome::Optimal(Optimum {
+        values,
+        objective,
+        duals,
+        reduced_costs,
+        basic,
+        iterations: tableau.iterations,
+        statuses,
+        slack,
+        rejected,
+        status,
+    }))
+}
+
+// where each column and row slack of a starting point sits: a value
+// strictly inside its bounds is basic, and so is the slack of a constraint
+// it leaves room in
+fn crash<T: Scalar>(
+    problem: &LinearProgram<T>,
+    values: &[Option<T>],
+) -> (Vec<VariableStatus>, Vec<bool>) {
+    let near = |a: &T, b: &T| {
+        let tolerance = T::closeness() * (T::one() + b.abs());
+        (a.clone() - b.clone()).abs() <= tolerance
+    };
+    let statuses = values
+        .iter()
+        .zip(&problem.bounds)
+        .map(|(value, (min, max))| {
+            let Some(value) = value else {
+                return VariableStatus::Lower;
+            };
+            if max.as_ref().is_some_and(|max| near(value, max)) {
+                VariableStatus::Upper
+            } else if near(value, min.as_ref().unwrap_or(&T::zero())) {
+                VariableStatus::Lower
+            } else {
+                VariableStatus::Basic
+            }
+        })
+        .collect();
+    let slack = problem
+        .rows
+        .iter()
+        .zip(&problem.senses)
+        .zip(&problem.rhs)
+        .map(|((entries, sense), rhs)| {
+            let activity = entries.iter().fold(T::zero(), |sum, (col, value)| {
+                let x = values[*col].clone().unwrap_or_else(T::zero);
+                sum + value.clone() * x
+            });
+            *sense != Sense::Eq && !near(&activity, rhs)
+        })
+        .collect();
+    (statuses, slack)
+}
+
+// the tableau columns a basis of statuses is made of
+fn desired_columns<T>(
+    columns: &[Column<T>],
+    bound_row: &[Option<usize>],
+    slack_of: &[Option<usize>],
+    (statuses, slack): &(Vec<VariableStatus>, Vec<bool>),
+) -> Vec<usize> {
+    let mut desired = vec![];
+    for ((column, bound), status) in columns.iter().zip(bound_row).zip(statuses) {
+        let bound_slack = bound.and_then(|row| slack_of[row]);
+        match (column, status) {
+            (Column::Shifted(z, _), VariableStatus::Basic) => {
+                desired.push(*z);
+                desired.extend(bound_slack);
+            }
+            (Column::Shifted(z, _), VariableStatus::Upper) if bound_slack.is_some() => {
+                desired.push(*z)
+            }
+            (Column::Shifted(..), _) => desired.extend(bound_slack),
+            (Column::Mirrored(z, _), VariableStatus::Basic) => desired.push(*z),
+            (Column::Split(positive, _), VariableStatus::Basic) => desired.push(*positive),
+            _ => {}
+        }
+    }
+    for (slack, basic) in slack_of.iter().zip(slack) {
+        if *basic {
+            desired.extend(slack);
+        }
+    }
+    desired
+}
+
+// a value as an f64 for the events, NaN when out of range
+fn float<T: Scalar>(value: &T) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+// drops rounding noise and negative zeros from reported multipliers
+fn clean(value: f64) -> f64 {
+    if value.abs() < EPSILON {
+        0.0
+    } else {
+        value
+    }
+}
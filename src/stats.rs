@@ -0,0 +1,252 @@
+use crate::report::number;
+use crate::{AlpsError, MatrixProblem, Sense, UnoptimizedProblem};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+// rows, or a whole matrix, whose largest coefficient is this many times
+// their smallest are reported as badly scaled
+const SCALING_WARNING: f64 = 1e6;
+
+/// The smallest and largest of some absolute values.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ValueRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ValueRange {
+    // the range of the nonzero absolute values, if there are any
+    fn of(values: impl IntoIterator<Item = f64>) -> Option<ValueRange> {
+        values
+            .into_iter()
+            .map(f64::abs)
+            .filter(|value| *value != 0.0)
+            .fold(None, |range, value| {
+                Some(match range {
+                    None => ValueRange {
+                        min: value,
+                        max: value,
+                    },
+                    Some(range) => ValueRange {
+                        min: range.min.min(value),
+                        max: range.max.max(value),
+                    },
+                })
+            })
+    }
+
+    /// How many times larger the largest value is than the smallest.
+    pub fn ratio(&self) -> f64 {
+        self.max / self.min
+    }
+}
+
+impl fmt::Display for ValueRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} to {}", short(self.min), short(self.max))
+    }
+}
+
+/// How the nonzeros are spread over the rows or the columns.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Density {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+impl Density {
+    fn of(counts: &[usize]) -> Density {
+        Density {
+            min: counts.iter().copied().min().unwrap_or(0),
+            max: counts.iter().copied().max().unwrap_or(0),
+            mean: counts.iter().sum::<usize>() as f64 / counts.len().max(1) as f64,
+        }
+    }
+}
+
+impl fmt::Display for Density {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} to {}, mean {:.1}", self.min, self.max, self.mean)
+    }
+}
+
+/// A summary of the size and shape of a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelStats {
+    pub variables: usize,
+    /// Variables without any bound
+    pub free: usize,
+    /// Variables with only a lower bound
+    pub lower_bounded: usize,
+    /// Variables with only an upper bound
+    pub upper_bounded: usize,
+    /// Variables with both bounds, apart from the fixed ones
+    pub boxed: usize,
+    /// Variables whose bounds are equal
+    pub fixed: usize,
+    pub constraints: usize,
+    pub less_equal: usize,
+    pub greater_equal: usize,
+    pub equal: usize,
+    /// Constraints without any variable
+    pub empty_rows: usize,
+    /// Constraints on a single variable, which could be bounds instead
+    pub singleton_rows: usize,
+    /// Nonzero coefficients in the constraint matrix
+    pub nonzeros: usize,
+    /// Share of the matrix that is nonzero
+    pub density: f64,
+    /// Nonzeros per constraint
+    pub row_density: Density,
+    /// Nonzeros per variable
+    pub column_density: Density,
+    /// Absolute values of the nonzero coefficients of the matrix
+    pub coefficients: Option<ValueRange>,
+    /// Absolute values of the nonzero right hand sides
+    pub rhs: Option<ValueRange>,
+    /// Absolute values of the nonzero objective coefficients
+    pub objective: Option<ValueRange>,
+    /// Badly scaled rows, free variables and variables used nowhere
+    pub warnings: Vec<String>,
+}
+
+impl ModelStats {
+    pub fn of(problem: &UnoptimizedProblem) -> Result<ModelStats, AlpsError> {
+        Ok(ModelStats::of_matrix(&MatrixProblem::try_from(problem)?))
+    }
+
+    pub fn of_matrix(matrix: &MatrixProblem) -> ModelStats {
+        let rows = matrix.matrix.row_entries();
+        let count = |sense: Sense| matrix.senses.iter().filter(|s| **s == sense).count();
+        let bounds = |has_min: bool, has_max: bool| {
+            matrix
+                .bounds
+                .iter()
+                .filter(|bound| bound.min.is_some() == has_min && bound.max.is_some() == has_max)
+                .filter(|bound| !(has_min && has_max && bound.min == bound.max))
+                .count()
+        };
+        let row_counts: Vec<usize> = rows.iter().map(Vec::len).collect();
+        let mut column_counts = vec![0; matrix.variables.len()];
+        for (col, _) in rows.iter().flatten() {
+            column_counts[*col] += 1;
+        }
+        let nonzeros: usize = row_counts.iter().sum();
+        let cells = matrix.senses.len() * matrix.variables.len();
+
+        let coefficients = ValueRange::of(rows.iter().flatten().map(|(_, value)| *value));
+        let mut warnings = vec![];
+        if let Some(range) = coefficients.filter(|range| range.ratio() >= SCALING_WARNING) {
+            warnings.push(format!(
+                "the coefficients range from {} ({:.0} orders of magnitude), consider rescaling",
+                range,
+                range.ratio().log10()
+            ));
+        }
+        for (row, entries) in rows.iter().enumerate() {
+            if let Some(range) = ValueRange::of(entries.iter().map(|(_, value)| *value)) {
+                if range.ratio() >= SCALING_WARNING {
+                    warnings.push(format!(
+                        "constraint `{}` is badly scaled, its coefficients range from {} ({:.0} orders of magnitude)",
+                        matrix.constraint_names[row],
+                        range,
+                        range.ratio().log10()
+                    ));
+                }
+            }
+        }
+        for (col, name) in matrix.variables.iter().enumerate() {
+            let bound = &matrix.bounds[col];
+            if bound.min.is_none() && bound.max.is_none() {
+                warnings.push(format!("variable `{}` is free, it has no bounds", name));
+            }
+            if column_counts[col] == 0 {
+                warnings.push(format!(
+                    "variable `{}` is in no constraint, only its bounds limit it",
+                    name
+                ));
+            }
+        }
+
+        ModelStats {
+            variables: matrix.variables.len(),
+            free: bounds(false, false),
+            lower_bounded: bounds(true, false),
+            upper_bounded: bounds(false, true),
+            boxed: bounds(true, true),
+            fixed: matrix
+                .bounds
+                .iter()
+                .filter(|bound| bound.min.is_some() && bound.min == bound.max)
+                .count(),
+            constraints: matrix.senses.len(),
+            less_equal: count(Sense::Le),
+            greater_equal: count(Sense::Ge),
+            equal: count(Sense::Eq),
+            empty_rows: row_counts.iter().filter(|count| **count == 0).count(),
+            singleton_rows: row_counts.iter().filter(|count| **count == 1).count(),
+            nonzeros,
+            density: nonzeros as f64 / cells.max(1) as f64,
+            row_density: Density::of(&row_counts),
+            column_density: Density::of(&column_counts),
+            coefficients,
+            rhs: ValueRange::of(matrix.rhs.iter().copied()),
+            objective: ValueRange::of(matrix.objective.iter().copied()),
+            warnings,
+        }
+    }
+}
+
+// plain numbers where they read well, scientific notation otherwise
+pub(crate) fn short(value: f64) -> String {
+    if value == 0.0 || (1e-3..1e7).contains(&value.abs()) {
+        number(value)
+    } else {
+        format!("{:e}", value)
+    }
+}
+
+impl fmt::Display for ModelStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = |range: Option<ValueRange>| match range {
+            Some(range) => range.to_string(),
+            None => "all zero".to_string(),
+        };
+        writeln!(
+            f,
+            "variables    {:>6}  ({} free, {} lower bounded, {} upper bounded, {} boxed, {} fixed)",
+            self.variables,
+            self.free,
+            self.lower_bounded,
+            self.upper_bounded,
+            self.boxed,
+            self.fixed
+        )?;
+        writeln!(
+            f,
+            "constraints  {:>6}  ({} <=, {} >=, {} ==, {} empty, {} singleton)",
+            self.constraints,
+            self.less_equal,
+            self.greater_equal,
+            self.equal,
+            self.empty_rows,
+            self.singleton_rows
+        )?;
+        writeln!(
+            f,
+            "nonzeros     {:>6}  ({:.1}% dense)",
+            self.nonzeros,
+            100.0 * self.density
+        )?;
+        writeln!(f, "per row       {}", self.row_density)?;
+        writeln!(f, "per column    {}", self.column_density)?;
+        writeln!(f, "coefficients  {}", range(self.coefficients))?;
+        writeln!(f, "rhs           {}", range(self.rhs))?;
+        writeln!(f, "objective     {}", range(self.objective))?;
+        for warning in &self.warnings {
+            writeln!(f, "warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
@@ -1,12 +1,71 @@
 // test create_expression
-#[cfg(test)]
-use crate::lib::{
+use crate::{
     //
+    alps,
+    canonicalize,
+    certify,
     create_constraints,
     create_expression,
     create_variables,
+    evaluate,
+    explain,
+    parse_alps,
+    parse_assignment,
     parse_objective_expression,
+    parse_rational,
+    plot_svg,
+    presolve,
+    problem_schema,
+    prove,
+    scale,
+    solution_schema,
+    solve,
+    solve_batch,
+    solve_exact,
+    solve_matrix,
+    solve_simplex,
+    solve_with,
+    solve_with_options,
+    to_alps,
+    to_dot,
+    tokens,
+    validate_str,
+    verify,
+    AlpsError,
+    BatchItem,
+    Bound,
+    CancelToken,
+    Certificate,
+    CertifyOptions,
+    GraphOptions,
+    JobError,
+    JobQueue,
+    JobStatus,
+    JobView,
+    JobsConfig,
+    MatrixProblem,
+    Metrics,
+    ModelFormat,
+    ModelStats,
+    Observer,
+    PeakAllocator,
+    Phase,
+    PresolveOptions,
+    Problem,
+    ScalingMethod,
+    Sense,
+    Server,
+    ServerConfig,
+    SolutionReport,
+    SolveEvent,
+    SolveOptions,
+    SolveStatus,
+    Solver,
+    SparseMatrix,
     UnoptimizedProblem,
+    VariableStatus,
+    VerifyOptions,
+    WarmStart,
 };
 
 #[test]
@@ -37,8 +96,8 @@ fn test_create_expression_simple() {
     let (_problem_variables, _variable_names, variable_hashmap) =
         create_variables(problem.variables);
 
-    let parsed_expression = parse_objective_expression(&problem.objective.expression);
-    let expression = create_expression(&parsed_expression, &variable_hashmap);
+    let parsed_expression = parse_objective_expression(&problem.objective.expression).unwrap();
+    let expression = create_expression(&parsed_expression, &variable_hashmap).unwrap();
     let string_expression = format!("{:?}", expression);
     let acceptable_expressions = ["v1 + -1 v0 + 10".to_string(), "-1 v1 + v0 + 10".to_string()];
 
@@ -77,11 +136,11 @@ fn test_create_constraint_simple() {
     let (_problem_variables, _variable_names, variable_hashmap) =
         create_variables(problem.variables);
 
-    let parsed_expression = parse_objective_expression(&problem.objective.expression);
-    let expression = create_expression(&parsed_expression, &variable_hashmap);
+    let parsed_expression = parse_objective_expression(&problem.objective.expression).unwrap();
+    let expression = create_expression(&parsed_expression, &variable_hashmap).unwrap();
     let string_expression = format!("{:?}", expression);
 
-    let constraints = create_constraints(&problem.constraints, &variable_hashmap);
+    let constraints = create_constraints(&problem.constraints, &variable_hashmap).unwrap();
     assert_eq!(constraints.len(), 2);
 
     let mut expected_constraints =
@@ -100,7 +159,7 @@ fn test_create_constraint_simple() {
 
     let mut actual_constraints = constraints
         .iter()
-        .map(|x| format!("{:?}", x))
+        .map(|(x, _lhs, _rhs)| format!("{:?}", x))
         .collect::<Vec<String>>();
 
     actual_constraints.sort();
@@ -157,8 +216,8 @@ fn test_create_expression() {
     let (_problem_variables, _variable_names, variable_hashmap) =
         create_variables(problem.variables);
 
-    let parsed_expression = parse_objective_expression(&problem.objective.expression);
-    let expression = create_expression(&parsed_expression, &variable_hashmap);
+    let parsed_expression = parse_objective_expression(&problem.objective.expression).unwrap();
+    let expression = create_expression(&parsed_expression, &variable_hashmap).unwrap();
     let string_expression = format!("{:?}", expression);
 
     // a+2-b+3
@@ -176,3 +235,1560 @@ fn test_create_expression() {
 
     assert!(matched);
 }
+
+// `"goal": "min"` minimises where it used to maximise, a literal after an
+// operator keeps its minus sign and bounds can be fractional
+#[test]
+fn test_goal_and_literals() {
+    let json_problem = r#"
+        {
+            "variables": {
+              "x": {"name": "x", "min": 0.5, "max": 4},
+              "y": {"name": "y", "min": 0, "max": 4}
+            },
+            "objective": {
+              "goal": "min",
+              "expression": "x + 2 * y"
+            },
+            "constraints": [
+              {"name": "a", "expression": "-1 * x + y >= -1"}
+            ]
+        }
+    "#;
+    let problem: UnoptimizedProblem = serde_json::from_str(json_problem).unwrap();
+    let solution = solve(problem.clone()).unwrap();
+    assert!((solution.objective - 0.5).abs() < 1e-6);
+
+    // the same problem maximised, what every goal did before
+    let mut maximised = problem;
+    maximised.objective.goal = "max".to_string();
+    let solution = solve(maximised).unwrap();
+    assert!((solution.objective - 12.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_matrix_round_trip() {
+    let problem: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let expected = solve(problem.clone()).unwrap().objective;
+    assert!((expected - 94.75).abs() < 1e-6);
+
+    let matrix = MatrixProblem::try_from(&problem).unwrap();
+    assert_eq!(matrix.variables, vec!["bagels", "doughnuts"]);
+    assert_eq!(matrix.objective, vec![3.0, 1.25]);
+    assert_eq!(matrix.senses[0], Sense::Le);
+    assert_eq!(matrix.senses[3], Sense::Ge);
+    assert_eq!(matrix.rhs, vec![400.0, 200.0, 200.0, 12.0, 14.0]);
+
+    let solution = solve_matrix(&matrix).unwrap();
+    assert!((solution.objective - expected).abs() < 1e-6);
+    assert_eq!(solution.const_values[0].name, "flour");
+    assert_eq!(
+        solution.const_values[0].expression,
+        "12 * bagels + 6.5 * doughnuts <= 400"
+    );
+
+    // and back through the expression parser
+    let round_trip = UnoptimizedProblem::try_from(&matrix).unwrap();
+    let solution = solve(round_trip.clone()).unwrap();
+    assert!((solution.objective - expected).abs() < 1e-6);
+    assert_eq!(MatrixProblem::try_from(&round_trip).unwrap(), matrix);
+}
+
+#[test]
+fn test_matrix_formats() {
+    let csr = SparseMatrix::Csr {
+        rows: 2,
+        cols: 3,
+        row_ptr: vec![0, 2, 3],
+        col_idx: vec![2, 0, 1],
+        values: vec![-1.5, 2.0, 4.0],
+    };
+    let triplet = SparseMatrix::Triplet {
+        rows: 2,
+        cols: 3,
+        entries: vec![(0, 0, 1.0), (1, 1, 4.0), (0, 2, -1.5), (0, 0, 1.0)],
+    };
+    assert_eq!(csr.row_entries(), triplet.row_entries());
+    assert_eq!(triplet.to_csr(), csr.to_csr());
+    assert_eq!(csr.to_triplet().to_csr(), csr.to_csr());
+
+    let matrix = MatrixProblem {
+        variables: vec!["x".to_string(), "y".to_string(), "z".to_string()],
+        goal: "min".to_string(),
+        objective: vec![1.0, 1.0, -1.0],
+        objective_constant: 0.0,
+        matrix: csr,
+        senses: vec![Sense::Ge, Sense::Le],
+        rhs: vec![-3.0, 8.0],
+        bounds: vec![
+            Bound {
+                min: Some(0.0),
+                max: Some(3.0),
+            },
+            Bound {
+                min: Some(0.0),
+                max: None,
+            },
+            Bound {
+                min: Some(0.0),
+                max: None,
+            },
+        ],
+        constraint_names: vec!["a".to_string(), "b".to_string()],
+    };
+    let problem = UnoptimizedProblem::try_from(&matrix).unwrap();
+    assert_eq!(problem.constraints[0].expression, "2 * x - 1.5 * z >= -3");
+    // a missing constraint name is an error, not a panic
+    let mut unnamed = matrix.clone();
+    unnamed.constraint_names.pop();
+    assert!(matches!(
+        UnoptimizedProblem::try_from(&unnamed),
+        Err(AlpsError::InvalidModel(_))
+    ));
+    assert_eq!(problem.objective.expression, "x + y - z");
+
+    // x = 3, y = 0 and z = 6 is the best point, row `a` is at its limit
+    let solution = solve_matrix(&matrix).unwrap();
+    assert!((solution.objective + 3.0).abs() < 1e-6);
+    assert!((solution.const_values[0].lhs + 3.0).abs() < 1e-6);
+    assert!((solve(problem).unwrap().objective + 3.0).abs() < 1e-6);
+
+    let broken = MatrixProblem {
+        rhs: vec![1.0],
+        ..matrix
+    };
+    assert!(solve_matrix(&broken).is_err());
+}
+
+#[test]
+fn test_alps_text_format() {
+    let source = "
+        # the bakery, with the minimums written as bounds
+        max: 3 * bagels + 1.25 * doughnuts;
+        flour: 12 * bagels + 6.5 * doughnuts <= 400;
+        milk: bagels + 0.5 * doughnuts
+            <= 200;
+        2 * bagels + 0.25 * doughnuts <= 200;
+        bagels >= 12; 14 <= doughnuts;
+        var unused;
+    ";
+    let problem = parse_alps(source).unwrap();
+    assert_eq!(problem.objective.goal, "max");
+    assert_eq!(problem.variables["bagels"].min, Some(12.0));
+    assert_eq!(problem.variables["doughnuts"].min, Some(14.0));
+    assert!(problem.variables.contains_key("unused"));
+    assert_eq!(problem.constraints.len(), 3);
+    assert_eq!(problem.constraints[1].name, "milk");
+    assert_eq!(problem.constraints[2].name, "c3");
+
+    // minilp reports a free variable that appears nowhere as unbounded
+    let mut expected = problem.clone();
+    expected.variables.shift_remove("unused");
+    assert!((solve(expected).unwrap().objective - 94.75).abs() < 1e-6);
+
+    // names containing colons survive a round trip
+    let compute: UnoptimizedProblem = include_str!("../problems/compute-modeling.json").into();
+    let text = to_alps(&compute);
+    let parsed = parse_alps(&text).unwrap();
+    assert_eq!(to_alps(&parsed), text);
+    assert_eq!(parsed.constraints, compute.constraints);
+    // and the variables keep their order
+    assert!(parsed.variables.keys().eq(compute.variables.keys()));
+    let reversed = parse_alps("var y, x;\nmax: x + y;\nc: x + y <= 1;").unwrap();
+    let round_trip = parse_alps(&to_alps(&reversed)).unwrap();
+    assert!(round_trip.variables.keys().eq(["y", "x"]));
+
+    let error = parse_alps("max: x;\nx y;").unwrap_err();
+    assert_eq!(
+        error,
+        AlpsError::Parse("line 2: `x y` is neither an objective nor a constraint".to_string())
+    );
+}
+
+#[test]
+fn test_builder() {
+    let mut problem = Problem::new();
+    let bagels = problem.var("bagels").min(12.0).build();
+    let doughnuts = problem.var("doughnuts").min(14.0).build();
+    problem
+        .maximise(3.0 * bagels + 1.25 * doughnuts)
+        .constraint("flour", 12.0 * bagels + 6.5 * doughnuts, Sense::Le, 400.0)
+        .constraint("milk", bagels + 0.5 * doughnuts, Sense::Le, 200.0)
+        .constraint("sugar", 2.0 * bagels, Sense::Le, 200.0 - 0.25 * doughnuts);
+    assert_eq!(problem.var("bagels").min(12.0).build(), bagels);
+    assert_eq!(problem.name(doughnuts), "doughnuts");
+
+    let solution = problem.solve().unwrap();
+    assert!((solution.objective - 94.75).abs() < 1e-6);
+
+    // the same problem through the expression parser
+    let expressions = problem.to_problem();
+    assert_eq!(
+        expressions.constraints[2].expression,
+        "2 * bagels + 0.25 * doughnuts <= 200"
+    );
+    let parsed: UnoptimizedProblem = serde_json::from_str(&problem.to_json()).unwrap();
+    assert_eq!(parsed, expressions);
+    assert!((solve(parsed).unwrap().objective - 94.75).abs() < 1e-6);
+
+    let mut problem = Problem::new();
+    let x = problem.var("x").min(0.0).max(3.0).build();
+    let y = problem.var("y").min(0.0).build();
+    problem
+        .minimise(-x - 2.0 * y + 1.0)
+        .constraint("sum", x + y, Sense::Le, 4.0);
+    let solution = problem.solve().unwrap();
+    assert!((solution.objective - -7.0).abs() < 1e-6);
+    assert_eq!(problem.to_matrix().objective_constant, 1.0);
+}
+
+#[test]
+fn test_alps_macro() {
+    let problem = alps! {
+        max: (3*bagels) + (1.25*doughnuts);
+        flour: 12*bagels + 6.5*doughnuts <= 400;
+        "milk:litres": bagels + doughnuts/2 <= 200;
+        2*bagels + 0.25*doughnuts <= 200;
+        bagels >= 12;
+        14 <= doughnuts;
+        doughnuts <= 1_000;
+    };
+    let solution = problem.solve().unwrap();
+    assert!((solution.objective - 94.75).abs() < 1e-6);
+
+    let expressions = problem.to_problem();
+    let names: Vec<&str> = expressions
+        .constraints
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, ["flour", "milk:litres", "c3"]);
+    assert_eq!(
+        expressions.constraints[1].expression,
+        "bagels + 0.5 * doughnuts <= 200"
+    );
+    assert_eq!(expressions.variables["doughnuts"].min, Some(14.0));
+    assert_eq!(expressions.variables["doughnuts"].max, Some(1000.0));
+
+    let problem = alps! {
+        minimize: -x - 2*(y - 1);
+        sum: x + y <= 4;
+        x >= 0; x <= 3;
+        y >= 0;
+    };
+    assert!((problem.solve().unwrap().objective - -6.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_simplex_solver() {
+    for text in [
+        include_str!("../problems/airline.json"),
+        include_str!("../problems/bakery.json"),
+        include_str!("../problems/compute-modeling.json"),
+        include_str!("../problems/farm.json"),
+        include_str!("../problems/publishing.json"),
+        include_str!("../problems/sports.json"),
+        include_str!("../problems/tech.json"),
+    ] {
+        let problem: UnoptimizedProblem = text.into();
+        let close = |a: f64, b: f64| (a - b).abs() <= 1e-9 * (1.0 + b.abs());
+        let expected = solve_with(problem.clone(), Solver::Exact).unwrap();
+        let solution = solve_with(problem.clone(), Solver::Simplex).unwrap();
+        assert!(close(solution.objective, expected.objective));
+        for (name, value) in &expected.variable_solutions {
+            assert!(close(solution.variable_solutions[name], *value));
+        }
+        let minilp = solve(problem).unwrap().objective;
+        assert!((minilp - expected.objective).abs() < 1e-6 * (1.0 + expected.objective.abs()));
+    }
+
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let matrix = MatrixProblem::try_from(&bakery).unwrap();
+    let solution = solve_simplex(&matrix).unwrap();
+    assert!((solution.objective - 94.75).abs() < 1e-9);
+    assert_eq!(solution.duals, [0.25, 0.0, 0.0, 0.0, -0.375]);
+    assert!(solution.iterations > 0);
+
+    let infeasible = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"min": 0}}, "objective": {"goal": "max", "expression": "x"},
+            "constraints": [{"name": "c", "expression": "x <= -1"}]}"#,
+    );
+    assert_eq!(
+        solve_with(infeasible, Solver::Simplex).unwrap_err(),
+        AlpsError::Infeasible
+    );
+    assert_eq!("simplex".parse(), Ok(Solver::Simplex));
+}
+
+#[test]
+fn test_presolve() {
+    let options = SolveOptions {
+        solver: Solver::Simplex,
+        presolve: Some(PresolveOptions::default()),
+        ..SolveOptions::default()
+    };
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let solution = solve_with_options(bakery, &options).unwrap();
+    assert!((solution.objective - 94.75).abs() < 1e-9);
+    let duals: Vec<_> = solution
+        .const_values
+        .iter()
+        .map(|c| c.dual.unwrap())
+        .collect();
+    assert_eq!(duals, [0.25, 0.0, 0.0, 0.0, -0.375]);
+    let report = solution.presolve.unwrap();
+    assert_eq!((report.singleton_rows, report.rows_after), (2, 3));
+
+    // a doubleton equality, a fixed variable and singleton bounds
+    let problem = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"min": 0}, "y": {"min": 0}, "z": {"min": 0}, "w": {"min": 0}},
+            "objective": {"goal": "max", "expression": "3 * x + 2 * y + z - w"},
+            "constraints": [{"name": "link", "expression": "x - y == 2"},
+                            {"name": "fix", "expression": "z == 3"},
+                            {"name": "cap", "expression": "x + y + z <= 13"},
+                            {"name": "spare", "expression": "w >= 1"},
+                            {"name": "loose", "expression": "x + w <= 100"}]}"#,
+    );
+    let expected = solve_with(problem.clone(), Solver::Simplex).unwrap();
+    let solution = solve_with_options(problem, &options).unwrap();
+    assert_eq!(solution.objective, expected.objective);
+    assert_eq!(solution.variable_solutions, expected.variable_solutions);
+    assert_eq!(solution.const_values, expected.const_values);
+    let report = solution.presolve.unwrap();
+    assert_eq!(report.doubleton_equalities, 1);
+    assert_eq!(report.fixed_variables, 1);
+    assert_eq!((report.rows_after, report.columns_after), (1, 2));
+
+    // presolve alone finds the crossing bounds
+    let infeasible = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"min": 0, "max": 5}}, "objective": {"goal": "max", "expression": "x"},
+            "constraints": [{"name": "c", "expression": "2 * x >= 12"}]}"#,
+    );
+    let matrix = MatrixProblem::try_from(&infeasible).unwrap();
+    assert_eq!(
+        presolve(&matrix, &PresolveOptions::default()).unwrap_err(),
+        AlpsError::Infeasible
+    );
+    let disabled = PresolveOptions {
+        singleton_rows: false,
+        redundant_rows: false,
+        ..PresolveOptions::default()
+    };
+    assert_eq!(presolve(&matrix, &disabled).unwrap().report.rows_after, 1);
+}
+
+#[test]
+fn test_scaling() {
+    let compute: UnoptimizedProblem = include_str!("../problems/compute-modeling.json").into();
+    for (method, solver) in ScalingMethod::ALL
+        .into_iter()
+        .flat_map(|method| [Solver::Minilp, Solver::Simplex].map(|solver| (method, solver)))
+    {
+        let options = SolveOptions {
+            solver,
+            scaling: Some(method),
+            verify: Some(VerifyOptions::default()),
+            ..SolveOptions::default()
+        };
+        let solution = solve_with_options(compute.clone(), &options).unwrap();
+        assert!((solution.objective - 125000.008).abs() < 1e-6);
+        // every value within its bounds and every row satisfied
+        assert!(solution.verification.unwrap().feasible);
+        assert!(solution
+            .variable_solutions
+            .values()
+            .all(|value| *value >= 0.0));
+        let report = solution.scaling.unwrap();
+        assert!(report.ratio_after.unwrap() < report.ratio_before.unwrap());
+        for factor in report.rows.values().chain(report.columns.values()) {
+            assert_eq!(factor.log2().fract(), 0.0);
+        }
+    }
+
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let matrix = MatrixProblem::try_from(&bakery).unwrap();
+    let scaled = scale(&matrix, ScalingMethod::GeometricMean).unwrap();
+    let solution = solve_simplex(&scaled.problem).unwrap();
+    let (values, duals) = scaled.unscale(&solution.values, Some(&solution.duals));
+    let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9);
+    assert!(close(&values, &[25.75, 14.0]));
+    assert!(close(&duals.unwrap(), &[0.25, 0.0, 0.0, 0.0, -0.375]));
+    assert_eq!("equilibrate".parse(), Ok(ScalingMethod::Equilibrate));
+}
+
+#[test]
+fn test_verify() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let options = SolveOptions {
+        verify: Some(VerifyOptions::default()),
+        ..SolveOptions::default()
+    };
+    let solution = solve_with_options(bakery.clone(), &options).unwrap();
+    let report = solution.verification.unwrap();
+    assert!(report.feasible);
+    assert_eq!(report.objective, 94.75);
+
+    let plan = parse_assignment("bagels = 12.25\ndoughnuts = 40\nmuffins = 3\n").unwrap();
+    let options = VerifyOptions {
+        integers: vec!["bagels".to_string()],
+        ..VerifyOptions::default()
+    };
+    let report = verify(&bakery, &plan, &options).unwrap();
+    assert!(!report.feasible);
+    let violated: Vec<_> = report
+        .constraints
+        .iter()
+        .filter(|check| !check.satisfied)
+        .map(|check| (check.name.as_str(), check.violation))
+        .collect();
+    assert_eq!(violated, [("flour", 7.0)]);
+    assert_eq!(report.max_violation, 7.0);
+    assert_eq!(report.integrality[0].limit, 12.0);
+    assert_eq!(report.unknown, ["muffins"]);
+
+    // within the tolerances, with a bound and scientific notation
+    let problem = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"max": 1}}, "objective": {"goal": "min", "expression": "x"},
+            "constraints": [{"name": "c", "expression": "2e-1 * x >= 0.2"}]}"#,
+    );
+    let values = parse_assignment(r#"{"x": 1.0000001}"#).unwrap();
+    let report = verify(&problem, &values, &VerifyOptions::default()).unwrap();
+    assert!(report.feasible);
+    assert!(report.bounds.is_empty());
+    let strict = VerifyOptions {
+        absolute: 0.0,
+        relative: 0.0,
+        ..VerifyOptions::default()
+    };
+    let report = verify(&problem, &values, &strict).unwrap();
+    assert_eq!(report.bounds[0].limit, 1.0);
+    let csv = "name,value,reduced_cost,min,max\nx,0.5,,,1\n\nname,expression\n";
+    assert_eq!(parse_assignment(csv).unwrap()["x"], 0.5);
+}
+
+#[test]
+fn test_evaluate() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let plan = parse_assignment("bagels = 12\ndoughnuts = 40\n").unwrap();
+    let evaluation = evaluate(&bakery, &plan, &VerifyOptions::default()).unwrap();
+    assert!(!evaluation.feasible);
+    assert_eq!(evaluation.objective, 86.0);
+    assert_eq!(evaluation.violated, ["flour"]);
+    assert_eq!(evaluation.const_values[0].lhs, 404.0);
+    assert!(evaluation.missing.is_empty());
+
+    let partial = parse_assignment("bagels = 12\n").unwrap();
+    let evaluation = evaluate(&bakery, &partial, &VerifyOptions::default()).unwrap();
+    assert_eq!(evaluation.missing, ["doughnuts"]);
+    assert_eq!(evaluation.violated, ["doughnut_min"]);
+    assert!(evaluation
+        .to_string()
+        .contains("constraint `doughnut_min` is violated: 0 >= 14"));
+
+    let optimum = parse_assignment("bagels = 25.75\ndoughnuts = 14\n").unwrap();
+    let evaluation = evaluate(&bakery, &optimum, &VerifyOptions::default()).unwrap();
+    assert!(evaluation.feasible);
+    assert_eq!(evaluation.objective, 94.75);
+
+    let bounded = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"min": 0, "max": 4}}, "objective": {"goal": "max", "expression": "x"},
+            "constraints": []}"#,
+    );
+    let values = parse_assignment(r#"{"x": 5}"#).unwrap();
+    assert_eq!(
+        evaluate(&bounded, &values, &VerifyOptions::default())
+            .unwrap()
+            .out_of_bounds,
+        ["x"]
+    );
+
+    // the tolerances are those of `verify`, so both agree on every plan
+    let close = parse_assignment(r#"{"x": 4.0000001}"#).unwrap();
+    let evaluation = evaluate(&bounded, &close, &VerifyOptions::default()).unwrap();
+    assert!(evaluation.feasible);
+    let strict = VerifyOptions {
+        absolute: 0.0,
+        relative: 0.0,
+        integers: vec!["x".to_string()],
+    };
+    for plan in [&plan, &partial, &optimum, &close] {
+        let problem = if plan.contains_key("x") {
+            &bounded
+        } else {
+            &bakery
+        };
+        let evaluation = evaluate(problem, plan, &strict).unwrap();
+        assert_eq!(
+            evaluation.feasible,
+            verify(problem, plan, &strict).unwrap().feasible
+        );
+    }
+    let evaluation = evaluate(&bounded, &close, &strict).unwrap();
+    assert_eq!(evaluation.out_of_bounds, ["x"]);
+    assert_eq!(evaluation.not_whole, ["x"]);
+}
+
+#[test]
+fn test_exact_solver() {
+    let exact = |text: &str| parse_rational(text).unwrap().to_string();
+    assert_eq!(exact("6.5"), "13/2");
+    assert_eq!(exact(".25"), "1/4");
+    assert_eq!(exact("1e-3"), "1/1000");
+    assert_eq!(exact("-2.5E2"), "-250");
+    assert_eq!(parse_rational("1.2.3"), None);
+
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let solution = solve_with(bakery.clone(), Solver::Exact).unwrap();
+    assert_eq!(solution.objective, 94.75);
+    let fractions = solution.exact.unwrap();
+    assert_eq!(fractions.objective, "379/4");
+    assert_eq!(fractions.variable_solutions["bagels"], "103/4");
+    let duals: Vec<_> = fractions.duals.values().map(String::as_str).collect();
+    assert_eq!(duals, ["1/4", "0", "0", "0", "-3/8"]);
+
+    let sports: UnoptimizedProblem = include_str!("../problems/sports.json").into();
+    let solution = solve_exact(&sports).unwrap();
+    assert_eq!(solution.exact.unwrap().objective, "615000/19");
+
+    let options = SolveOptions {
+        solver: Solver::Exact,
+        presolve: Some(PresolveOptions::default()),
+        ..SolveOptions::default()
+    };
+    assert!(matches!(
+        solve_with_options(bakery, &options),
+        Err(AlpsError::InvalidInput(_))
+    ));
+    let nonlinear = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {}, "y": {}}, "objective": {"goal": "max", "expression": "x"},
+            "constraints": [{"name": "c", "expression": "x * y <= 1"}]}"#,
+    );
+    assert!(matches!(solve_exact(&nonlinear), Err(AlpsError::Parse(_))));
+}
+
+#[test]
+fn test_certificates() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let options = SolveOptions {
+        certificate: true,
+        ..SolveOptions::default()
+    };
+    // minilp reports no duals, the simplex supplies them
+    let solution = solve_with_options(bakery.clone(), &options).unwrap();
+    let certificate = solution.certificate.unwrap();
+    let Certificate::Optimal {
+        duals, duality_gap, ..
+    } = &certificate
+    else {
+        panic!("expected an optimality certificate");
+    };
+    assert_eq!(duals["flour"], 0.25);
+    assert_eq!(*duality_gap, 0.0);
+    let check = certify(&bakery, &certificate, &CertifyOptions::default()).unwrap();
+    assert!(check.valid, "{}", check);
+    assert_eq!(check.objective, Some(94.75));
+
+    // a dual on the wrong side of zero is caught
+    let mut forged = certificate.clone();
+    if let Certificate::Optimal { duals, .. } = &mut forged {
+        duals["flour"] = 0.1;
+    }
+    let check = certify(&bakery, &forged, &CertifyOptions::default()).unwrap();
+    assert!(!check.valid);
+    assert!(check.duality_gap.unwrap() > 1.0);
+    // a dual of a constraint the problem doesn't have is only noted
+    let mut extra = certificate.clone();
+    if let Certificate::Optimal { duals, .. } = &mut extra {
+        duals.insert("nowhere".to_string(), 1.0);
+    }
+    let check = certify(&bakery, &extra, &CertifyOptions::default()).unwrap();
+    assert!(check.valid, "{}", check);
+    assert_eq!(check.issues.len(), 1);
+
+    // finding the duals is held to the limits of the solve, and a solution
+    // stopped short of the optimum has nothing to certify
+    let limited = SolveOptions {
+        iteration_limit: Some(1),
+        ..options.clone()
+    };
+    assert!(matches!(
+        solve_with_options(bakery.clone(), &limited),
+        Err(AlpsError::Stopped(_))
+    ));
+    let mut stopped = solve_with(bakery.clone(), Solver::Simplex).unwrap();
+    stopped.status = SolveStatus::TimeLimit;
+    assert!(matches!(
+        crate::certificate(&bakery, &stopped),
+        Err(AlpsError::Stopped(_))
+    ));
+
+    // x + y <= 3 and x + 2y >= 10 can't both hold with y <= 4 and x - y == 1
+    let infeasible = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"min": 0}, "y": {"min": 0, "max": 4}},
+            "objective": {"goal": "max", "expression": "x + y"},
+            "constraints": [{"name": "cap", "expression": "x + y <= 3"},
+                            {"name": "need", "expression": "x + 2 * y >= 10"},
+                            {"name": "bal", "expression": "x - y == 1"}]}"#,
+    );
+    assert!(matches!(
+        solve_with_options(infeasible.clone(), &options),
+        Err(AlpsError::Infeasible)
+    ));
+    let proof = prove(&infeasible).unwrap();
+    let Certificate::Infeasible { farkas, margin, .. } = &proof else {
+        panic!("expected an infeasibility certificate");
+    };
+    assert!(farkas["cap"] < 0.0 && farkas["need"] > 0.0);
+    assert_eq!(*margin, 4.0);
+    assert!(
+        certify(&infeasible, &proof, &CertifyOptions::default())
+            .unwrap()
+            .valid
+    );
+
+    let mut forged = proof;
+    if let Certificate::Infeasible { farkas, .. } = &mut forged {
+        farkas["need"] = 0.0;
+    }
+    let check = certify(&infeasible, &forged, &CertifyOptions::default()).unwrap();
+    assert!(!check.valid);
+    assert!(check.margin.unwrap() < 0.0);
+
+    // values that passed through f64 pivots or scaling still certify, with
+    // the residuals held to the tolerances of `verify`
+    let compute: UnoptimizedProblem = include_str!("../problems/compute-modeling.json").into();
+    let scalings = [None].into_iter().chain(ScalingMethod::ALL.map(Some));
+    let runs = scalings
+        .flat_map(|scaling| [Solver::Minilp, Solver::Simplex].map(|solver| (scaling, solver)))
+        .chain([(None, Solver::Exact)]);
+    for (scaling, solver) in runs {
+        let options = SolveOptions {
+            solver,
+            scaling,
+            certificate: true,
+            ..SolveOptions::default()
+        };
+        let solution = solve_with_options(compute.clone(), &options).unwrap();
+        let check = certify(
+            &compute,
+            solution.certificate.as_ref().unwrap(),
+            &CertifyOptions::default(),
+        )
+        .unwrap();
+        assert!(check.valid, "{:?} {:?}: {}", scaling, solver, check);
+        assert!((check.objective.unwrap() - 125000.008).abs() < 1e-6);
+    }
+
+    // duals that leave a gap give way to those of the exact simplex
+    let mut solution = solve_with(compute.clone(), Solver::Simplex).unwrap();
+    for constraint in &mut solution.const_values {
+        constraint.dual = Some(0.0);
+    }
+    let repaired = crate::certificate(&compute, &solution).unwrap();
+    let check = certify(&compute, &repaired, &CertifyOptions::default()).unwrap();
+    assert!(check.valid, "{}", check);
+    let Certificate::Optimal { duals, .. } = &repaired else {
+        panic!("expected an optimality certificate");
+    };
+    assert!(duals.values().any(|dual| *dual != 0.0));
+}
+
+#[test]
+fn test_warm_start() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let cold = solve_with(bakery.clone(), Solver::Simplex).unwrap();
+    let basis = cold.basis.clone().unwrap();
+    assert_eq!(basis.variables["bagels"], VariableStatus::Basic);
+    assert_eq!(basis.slack, ["milk", "sugar", "bagel_min"]);
+    assert!(basis.iterations > 0);
+
+    let from = |warm_start: WarmStart, solver: Solver| {
+        let options = SolveOptions {
+            solver,
+            warm_start: Some(warm_start),
+            ..SolveOptions::default()
+        };
+        solve_with_options(bakery.clone(), &options).unwrap()
+    };
+    for solver in [Solver::Simplex, Solver::Exact] {
+        let warm = from(WarmStart::Basis(basis.clone()), solver);
+        assert!((warm.objective - 94.75).abs() < 1e-9);
+        let report = warm.warm_start.unwrap();
+        assert!(report.accepted);
+        assert_eq!(report.iterations, 0);
+        assert_eq!(report.saved, Some(basis.iterations as i64));
+        // the baseline carries over to the next warm start
+        assert_eq!(warm.basis.unwrap().iterations, basis.iterations);
+
+        let values = WarmStart::Values(cold.variable_solutions.clone());
+        assert!(from(values, solver).warm_start.unwrap().accepted);
+    }
+
+    // nothing basic leaves the minimums on `bagel_min` and `doughnut_min`
+    // unmet, so the solve starts from scratch
+    let mut infeasible = basis.clone();
+    infeasible
+        .variables
+        .values_mut()
+        .for_each(|s| *s = VariableStatus::Lower);
+    infeasible.slack.clear();
+    let solution = from(WarmStart::Basis(infeasible), Solver::Simplex);
+    assert_eq!(solution.objective, 94.75);
+    let report = solution.warm_start.unwrap();
+    assert!(!report.accepted && report.reason.is_some());
+    assert_eq!(report.saved, None);
+
+    let minilp = from(WarmStart::Basis(basis.clone()), Solver::Minilp);
+    assert!(!minilp.warm_start.unwrap().accepted);
+
+    // a free variable basic at a negative value
+    let free = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {}, "y": {"min": 0}},
+            "objective": {"goal": "min", "expression": "x + y"},
+            "constraints": [{"name": "floor", "expression": "x - y >= -3"},
+                            {"name": "cap", "expression": "y <= 1"}]}"#,
+    );
+    let values = WarmStart::Values(std::collections::HashMap::from([("x".to_string(), -3.0)]));
+    let options = SolveOptions {
+        solver: Solver::Simplex,
+        warm_start: Some(values),
+        ..SolveOptions::default()
+    };
+    let solution = solve_with_options(free, &options).unwrap();
+    assert_eq!(solution.objective, -3.0);
+    assert!(solution.warm_start.unwrap().accepted);
+
+    let text = serde_json::to_string(&cold).unwrap();
+    assert_eq!(WarmStart::parse(&text).unwrap(), WarmStart::Basis(basis));
+    assert!(matches!(
+        WarmStart::parse("bagels,25\ndoughnuts,14").unwrap(),
+        WarmStart::Values(_)
+    ));
+}
+
+#[test]
+fn test_limits() {
+    let farm: UnoptimizedProblem = include_str!("../problems/farm.json").into();
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let limited = |problem: &UnoptimizedProblem, options: SolveOptions| {
+        solve_with_options(problem.clone(), &options)
+    };
+
+    // phase one ends after a pivot, from then on every basis is feasible
+    let options = SolveOptions {
+        solver: Solver::Simplex,
+        iteration_limit: Some(2),
+        ..SolveOptions::default()
+    };
+    let solution = limited(&farm, options.clone()).unwrap();
+    assert_eq!(solution.status, SolveStatus::IterationLimit);
+    assert!(solution.objective < 34000.0);
+    let report = verify(
+        &farm,
+        &solution.variable_solutions,
+        &VerifyOptions::default(),
+    )
+    .unwrap();
+    assert!(report.feasible);
+    assert!(serde_json::to_string(&solution)
+        .unwrap()
+        .contains(r#""status":"iteration_limit""#));
+    // bakery's minimums need two pivots of phase one
+    let options = SolveOptions {
+        iteration_limit: Some(1),
+        ..options
+    };
+    assert!(matches!(
+        limited(&bakery, options),
+        Err(AlpsError::Stopped(_))
+    ));
+
+    // the origin is feasible, so even a solve stopped at once has a solution
+    let origin = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"min": 0}, "y": {"min": 0}},
+            "objective": {"goal": "max", "expression": "3 * x + 2 * y"},
+            "constraints": [{"name": "a", "expression": "x + y <= 4"},
+                            {"name": "b", "expression": "x + 3 * y <= 6"}]}"#,
+    );
+    let options = SolveOptions {
+        solver: Solver::Exact,
+        time_limit: Some(0.0),
+        ..SolveOptions::default()
+    };
+    assert_eq!(
+        limited(&origin, options).unwrap().status,
+        SolveStatus::TimeLimit
+    );
+
+    let cancel = CancelToken::new();
+    let remote = cancel.clone();
+    std::thread::spawn(move || remote.cancel()).join().unwrap();
+    assert!(cancel.is_cancelled());
+    let options = SolveOptions {
+        solver: Solver::Simplex,
+        cancel: Some(cancel.clone()),
+        ..SolveOptions::default()
+    };
+    let solution = limited(&origin, options).unwrap();
+    assert_eq!(solution.status, SolveStatus::Interrupted);
+    assert_eq!(solution.objective, 0.0);
+    let options = SolveOptions {
+        cancel: Some(cancel),
+        ..SolveOptions::default()
+    };
+    assert!(matches!(
+        limited(&farm, options),
+        Err(AlpsError::Stopped(_))
+    ));
+
+    // minilp can't look at the limits, it is given up on in their place
+    let slow = |options: &SolveOptions| {
+        let stop = crate::limits::Stop::new(options).unwrap();
+        stop.watch(|| std::thread::sleep(std::time::Duration::from_secs(10)))
+    };
+    let started = std::time::Instant::now();
+    let options = SolveOptions {
+        time_limit: Some(0.05),
+        ..SolveOptions::default()
+    };
+    assert!(matches!(slow(&options), Err(AlpsError::Stopped(_))));
+    let cancel = CancelToken::new();
+    let remote = cancel.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        remote.cancel();
+    });
+    let options = SolveOptions {
+        cancel: Some(cancel),
+        ..SolveOptions::default()
+    };
+    assert!(matches!(slow(&options), Err(AlpsError::Stopped(_))));
+    assert!(started.elapsed().as_secs() < 5);
+    let options = SolveOptions {
+        time_limit: Some(60.0),
+        ..SolveOptions::default()
+    };
+    assert_eq!(limited(&bakery, options).unwrap().objective, 94.75);
+
+    // a time limit has to be a number of seconds
+    for time_limit in [-1.0, f64::NAN] {
+        let options = SolveOptions {
+            time_limit: Some(time_limit),
+            ..SolveOptions::default()
+        };
+        assert!(matches!(
+            limited(&bakery, options),
+            Err(AlpsError::InvalidInput(_))
+        ));
+    }
+
+    let solution = solve_with(farm, Solver::Simplex).unwrap();
+    assert_eq!(solution.status, SolveStatus::Optimal);
+    assert!(!serde_json::to_string(&solution).unwrap().contains("status"));
+}
+
+#[test]
+fn test_progress() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let heard = events.clone();
+    let observer = Observer::new(move |event| heard.lock().unwrap().push(event.clone()));
+    let phases = |events: &[SolveEvent]| -> Vec<Phase> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                SolveEvent::Phase { phase } => Some(*phase),
+                _ => None,
+            })
+            .collect()
+    };
+
+    for solver in Solver::ALL {
+        let options = SolveOptions {
+            solver,
+            observer: Some(observer.clone()),
+            ..SolveOptions::default()
+        };
+        let solution = solve_with_options(bakery.clone(), &options).unwrap();
+        let events = std::mem::take(&mut *events.lock().unwrap());
+        let incumbents: Vec<f64> = events
+            .iter()
+            .filter_map(|event| match event {
+                SolveEvent::Incumbent { objective } => Some(*objective),
+                _ => None,
+            })
+            .collect();
+        // a maximisation, every incumbent is better than the last
+        assert!(incumbents.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!((incumbents.last().unwrap() - solution.objective).abs() < 1e-9);
+        assert_eq!(
+            events.last(),
+            Some(&SolveEvent::Phase {
+                phase: Phase::Extract
+            })
+        );
+        if solver == Solver::Minilp {
+            assert_eq!(phases(&events), [Phase::Parse, Phase::Lp, Phase::Extract]);
+        } else {
+            assert_eq!(
+                phases(&events),
+                [Phase::Parse, Phase::Feasibility, Phase::Lp, Phase::Extract]
+            );
+            let pivots = events
+                .iter()
+                .filter(|event| matches!(event, SolveEvent::Iteration { .. }))
+                .count();
+            assert_eq!(pivots, solution.basis.unwrap().iterations);
+        }
+        assert!(events.contains(&SolveEvent::Bound {
+            bound: solution.objective
+        }));
+    }
+
+    let options = SolveOptions {
+        solver: Solver::Simplex,
+        presolve: Some(PresolveOptions::default()),
+        observer: Some(observer),
+        ..SolveOptions::default()
+    };
+    solve_with_options(bakery, &options).unwrap();
+    let events = events.lock().unwrap();
+    assert_eq!(phases(&events)[..2], [Phase::Parse, Phase::Presolve]);
+    assert_eq!(
+        serde_json::to_string(&events[0]).unwrap(),
+        r#"{"event":"phase","phase":"parse"}"#
+    );
+}
+
+#[test]
+fn test_metrics() {
+    let origin = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"min": 0}, "y": {"min": 0}},
+            "objective": {"goal": "max", "expression": "3 * x + 2 * y"},
+            "constraints": [{"name": "a", "expression": "x + y <= 4"},
+                            {"name": "b", "expression": "x + 3*y <= 6"}]}"#,
+    );
+    assert_eq!(tokens(&origin), 7 + 5 + 7);
+
+    let farm: UnoptimizedProblem = include_str!("../problems/farm.json").into();
+    let solution = solve_with_options(farm.clone(), &SolveOptions::default()).unwrap();
+    assert_eq!(solution.metrics, None);
+    assert!(!serde_json::to_string(&solution)
+        .unwrap()
+        .contains("metrics"));
+
+    let nonzeros = ModelStats::of(&farm).unwrap().nonzeros;
+    for solver in Solver::ALL {
+        let options = SolveOptions {
+            solver,
+            metrics: true,
+            ..SolveOptions::default()
+        };
+        let solution = solve_with_options(farm.clone(), &options).unwrap();
+        let metrics: Metrics = solution.metrics.clone().unwrap();
+        assert_eq!(metrics.tokens, tokens(&farm));
+        assert_eq!(metrics.nonzeros, nonzeros);
+        assert!(metrics.build > 0.0 && metrics.solve > 0.0 && metrics.postprocess > 0.0);
+        assert_eq!(metrics.presolve, 0.0);
+        // only alps reading the text knows how long that took, and the
+        // tests run without the counting allocator
+        assert_eq!(metrics.parse, None);
+        assert_eq!(metrics.peak_allocation, None);
+        match solver {
+            Solver::Minilp => assert_eq!(metrics.iterations, None),
+            _ => assert_eq!(
+                metrics.iterations,
+                Some(solution.basis.as_ref().unwrap().iterations)
+            ),
+        }
+        let json = serde_json::to_value(&solution).unwrap();
+        assert_eq!(json["metrics"]["tokens"], tokens(&farm));
+    }
+
+    let options = SolveOptions {
+        solver: Solver::Simplex,
+        presolve: Some(PresolveOptions::default()),
+        metrics: true,
+        ..SolveOptions::default()
+    };
+    let metrics = solve_with_options(farm, &options).unwrap().metrics.unwrap();
+    assert!(metrics.presolve > 0.0);
+
+    // each solve keeps its own peak, called by hand since the tests don't
+    // install the allocator
+    use std::alloc::{GlobalAlloc, Layout};
+    let allocate = |size: usize| {
+        let layout = Layout::from_size_align(size, 8).unwrap();
+        unsafe { PeakAllocator.dealloc(PeakAllocator.alloc(layout), layout) };
+    };
+    PeakAllocator::track();
+    let mut first = crate::metrics::Recorder::start();
+    allocate(1 << 20);
+    let mut second = crate::metrics::Recorder::start();
+    allocate(1 << 10);
+    assert_eq!(second.finish(false).peak_allocation, Some(1 << 10));
+    assert_eq!(first.finish(false).peak_allocation, Some(1 << 20));
+    PeakAllocator::untrack();
+    let mut after = crate::metrics::Recorder::start();
+    allocate(1 << 10);
+    assert_eq!(after.finish(false).peak_allocation, None);
+}
+
+#[test]
+fn test_model_formats() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    for format in ModelFormat::ALL {
+        let text = format.write(&bakery).unwrap();
+        assert_eq!(ModelFormat::detect(&text), format);
+        let parsed = format.parse(&text).unwrap();
+        assert_eq!(format.write(&parsed).unwrap(), text);
+    }
+    // variables keep their declaration order
+    let reversed = parse_alps("var y, x;\nmax: x + y;\nc: x + y <= 1;").unwrap();
+    let json = ModelFormat::Json.write(&reversed).unwrap();
+    let round_trip = ModelFormat::Json.parse(&json).unwrap();
+    assert!(round_trip.variables.keys().eq(["y", "x"]));
+    assert_eq!(
+        ModelFormat::from_path("model.alps"),
+        Some(ModelFormat::Alps)
+    );
+    assert_eq!(ModelFormat::from_path("model.json"), None);
+
+    let messy = UnoptimizedProblem::from(
+        r#"{"variables": {"x": {"name": "x", "min": 0}}, "objective": {"goal": " MAX", "expression": "2*x"},
+            "constraints": [{"name": " c ", "expression": "x<=(4)"}]}"#,
+    );
+    let canonical = canonicalize(&messy);
+    assert_eq!(canonical.objective.goal, "max");
+    assert_eq!(canonical.objective.expression, "2 * x");
+    assert_eq!(canonical.constraints[0].name, "c");
+    assert_eq!(canonical.constraints[0].expression, "x <= ( 4 )");
+    assert_eq!(canonicalize(&canonical), canonical);
+
+    let stats = ModelStats::of(&bakery).unwrap();
+    assert_eq!(stats.variables, 2);
+    assert_eq!(stats.free, 2);
+    assert_eq!(stats.constraints, 5);
+}
+
+#[test]
+fn test_solution_report() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let solution = solve(bakery.clone()).unwrap();
+    let report = SolutionReport::new(&bakery, &solution).unwrap();
+
+    let names: Vec<&str> = report
+        .variables
+        .iter()
+        .map(|variable| variable.name.as_str())
+        .collect();
+    assert_eq!(names, ["bagels", "doughnuts"]);
+    let contributions: f64 = report.terms.iter().map(|term| term.contribution).sum();
+    assert!((contributions - report.objective).abs() < 1e-9);
+    assert_eq!(report.terms[0].coefficient, 3.0);
+
+    let binding: Vec<&str> = report
+        .constraints
+        .iter()
+        .filter(|constraint| constraint.binding)
+        .map(|constraint| constraint.name.as_str())
+        .collect();
+    assert_eq!(binding, ["flour", "doughnut_min"]);
+    let milk = &report.constraints[1];
+    assert_eq!(milk.sense, Sense::Le);
+    assert!((milk.slack - (milk.rhs - milk.activity)).abs() < 1e-9);
+    assert!(milk.slack > 0.0);
+
+    let text = explain(&bakery, &solution).unwrap();
+    assert!(text.contains("objective = 94.75\n"));
+    assert!(text.contains("3 × bagels        25.75         77.25\n"));
+    assert!(text.contains("flour         <=       400  400       0  *\n"));
+
+    // the constant of the objective is part of the optimum
+    let shifted = parse_alps("min: 2 * x + 3; c: x >= 1;").unwrap();
+    let solution = solve(shifted.clone()).unwrap();
+    assert!((solution.objective - 5.0).abs() < 1e-9);
+    assert_eq!(
+        SolutionReport::new(&shifted, &solution).unwrap().constant,
+        3.0
+    );
+}
+
+#[test]
+fn test_solution_export() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let solution = solve_with(bakery.clone(), Solver::Simplex).unwrap();
+    assert_eq!(solution.const_values[0].dual, Some(0.25));
+    assert_eq!(solution.reduced_costs.as_ref().unwrap()["bagels"], 0.0);
+    let report = SolutionReport::new(&bakery, &solution).unwrap();
+
+    let csv = report.to_csv();
+    let (variables, constraints) = csv.split_once("\n\n").unwrap();
+    assert_eq!(
+        variables.lines().collect::<Vec<_>>(),
+        [
+            "name,value,reduced_cost,min,max",
+            "bagels,25.75,0,,",
+            "doughnuts,14,0,,"
+        ]
+    );
+    assert_eq!(
+        constraints.lines().nth(1),
+        Some("flour,12 * bagels + 6.5 * doughnuts <= 400,400,400,0,0.25")
+    );
+
+    let markdown = report.to_markdown();
+    assert!(markdown.starts_with("**max** `( 3 * bagels ) + ( 1.25 * doughnuts )` = 94.75\n"));
+    assert!(
+        markdown.contains("| doughnut_min | `doughnuts >= 14` | 14 | 14 | 0 | -0.375 | yes |\n")
+    );
+
+    // minilp gives no duals, names with commas and pipes are escaped
+    let mut problem = parse_alps("max: x; c: x <= 2;").unwrap();
+    problem.constraints[0].name = "a, \"b\"|c".to_string();
+    let solution = solve(problem.clone()).unwrap();
+    assert_eq!(solution.const_values[0].dual, None);
+    assert!(!serde_json::to_string(&solution).unwrap().contains("dual"));
+    let report = SolutionReport::new(&problem, &solution).unwrap();
+    assert!(report
+        .constraints_csv()
+        .contains("\n\"a, \"\"b\"\"|c\",x <= 2,2,2,0,\n"));
+    assert!(report.to_markdown().contains("| a, \"b\"\\|c | `x <= 2` |"));
+}
+
+#[test]
+fn test_plot() {
+    for text in [
+        include_str!("../problems/airline.json"),
+        include_str!("../problems/bakery.json"),
+        include_str!("../problems/farm.json"),
+        include_str!("../problems/publishing.json"),
+        include_str!("../problems/sports.json"),
+        include_str!("../problems/tech.json"),
+    ] {
+        let problem: UnoptimizedProblem = text.into();
+        let svg = plot_svg(&problem).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polygon class=\"feasible\"").count(), 1);
+        assert_eq!(svg.matches("<circle class=\"optimum\"").count(), 1);
+        assert_eq!(svg.matches("class=\"objective\"").count(), 1);
+        for constraint in &problem.constraints {
+            assert!(svg.contains(&format!(">{}", constraint.name)));
+        }
+    }
+
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let svg = plot_svg(&bakery).unwrap();
+    assert!(svg.contains("max (25.75, 14) = 94.75</text>"));
+    assert!(svg.contains(">milk (outside the view)</text>"));
+
+    // infeasible problems are drawn without a region or an optimum
+    let infeasible = parse_alps("max: x + y; c: x + y >= 4; d: x + y <= 2;").unwrap();
+    let svg = plot_svg(&infeasible).unwrap();
+    assert!(!svg.contains("<polygon") && !svg.contains("<circle"));
+    assert_eq!(svg.matches("class=\"constraint\"").count(), 2);
+
+    let compute: UnoptimizedProblem = include_str!("../problems/compute-modeling.json").into();
+    assert!(matches!(
+        plot_svg(&compute),
+        Err(AlpsError::InvalidModel(_))
+    ));
+}
+
+#[test]
+fn test_graph() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let matrix = MatrixProblem::try_from(&bakery).unwrap();
+    let dot = to_dot(&matrix, &GraphOptions::default());
+    assert!(dot.starts_with("graph alps {\n"));
+    assert!(dot.contains("    \"c0\" [shape=box, label=\"flour\\n<= 400\"];\n"));
+    assert!(dot.contains("    \"c0\" -- \"v1\" [label=\"6.5\"];\n"));
+    assert_eq!(dot.matches(" -- ").count(), 8);
+
+    // a filter keeps the matching nodes and their neighbours
+    let options = GraphOptions {
+        filter: Some(regex::Regex::new("^doughnut").unwrap()),
+        solution: Some(solve_simplex(&matrix).unwrap()),
+    };
+    let dot = to_dot(&matrix, &options);
+    assert_eq!(dot.matches(" -- ").count(), 4);
+    assert!(!dot.contains("bagel_min"));
+    assert!(dot.contains("label=\"doughnut_min\\n>= 14\", style=filled, fillcolor=\"#f4a6a6\""));
+    assert!(dot.contains("label=\"milk\\n<= 200\", style=filled, fillcolor=\"#d9ead3\""));
+    assert!(dot.contains("label=\"doughnuts\", style=filled, fillcolor=\"#9fc5e8\""));
+}
+
+#[test]
+fn test_model_stats() {
+    let bakery: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+    let stats = ModelStats::of(&bakery).unwrap();
+    assert_eq!(
+        (stats.less_equal, stats.greater_equal, stats.equal),
+        (3, 2, 0)
+    );
+    assert_eq!(stats.singleton_rows, 2);
+    assert_eq!(stats.nonzeros, 8);
+    assert_eq!((stats.row_density.min, stats.row_density.max), (1, 2));
+    assert_eq!(stats.column_density.mean, 4.0);
+    let coefficients = stats.coefficients.unwrap();
+    assert_eq!((coefficients.min, coefficients.max), (0.25, 12.0));
+    assert_eq!(stats.rhs.unwrap().max, 400.0);
+    assert_eq!(
+        stats.warnings,
+        [
+            "variable `bagels` is free, it has no bounds",
+            "variable `doughnuts` is free, it has no bounds"
+        ]
+    );
+
+    // the big-M rows of compute-modeling are flagged
+    let compute: UnoptimizedProblem = include_str!("../problems/compute-modeling.json").into();
+    let stats = ModelStats::of(&compute).unwrap();
+    assert_eq!(stats.boxed, 13);
+    assert!(stats.warnings[0].starts_with("the coefficients range from 0.008 to 1000000"));
+    assert!(stats
+        .warnings
+        .iter()
+        .any(|warning| warning.starts_with("constraint `non_overlap_A_B_GPU:0` is badly scaled")));
+    assert!(stats.to_string().contains("\nwarning: "));
+
+    let fixed = parse_alps("max: x; x >= 1; x <= 1; var y;").unwrap();
+    let stats = ModelStats::of(&fixed).unwrap();
+    assert_eq!((stats.fixed, stats.boxed, stats.free), (1, 0, 1));
+    assert!(stats.coefficients.is_none());
+    assert!(stats
+        .warnings
+        .contains(&"variable `y` is in no constraint, only its bounds limit it".to_string()));
+}
+
+#[test]
+fn test_validate() {
+    for problem in [
+        include_str!("../problems/bakery.json"),
+        include_str!("../problems/compute-modeling.json"),
+    ] {
+        assert_eq!(validate_str(problem), vec![]);
+    }
+
+    let issues = validate_str(
+        r#"
+        {
+            "variables": {
+              "a": {"name": "b", "min": 5, "max": 1},
+              "c/d": {"kind": "integer"}
+            },
+            "objective": {"goal": "max", "expression": "a + q"},
+            "constraints": [
+              {"name": "flour", "expression": "a + c/d <= 4"},
+              {"name": "flour", "expression": "a >= 1", "weight": 2}
+            ]
+          }
+        "#,
+    );
+    let found = issues
+        .iter()
+        .map(|issue| issue.pointer.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        found,
+        vec![
+            "/variables/a/name",
+            "/variables/a",
+            "/variables/c~1d/kind",
+            "/objective/expression",
+            "/constraints/1/weight",
+            "/constraints/1/name",
+        ]
+    );
+    assert_eq!(issues[3].message, "undeclared variable `q`");
+
+    let schema = problem_schema();
+    assert_eq!(schema["additionalProperties"], false);
+    assert!(schema["definitions"]["Variable"]["properties"]["min"].is_object());
+    assert!(solution_schema()["properties"]["const_values"].is_object());
+
+    // the parser refuses the unknown fields the schema rules out
+    let typo = r#"{"variables": {"x": {"mni": 0}}, "objective": {"goal": "max", "expression": "x"},
+                   "constraints": []}"#;
+    assert!(serde_json::from_str::<UnoptimizedProblem>(typo).is_err());
+}
+
+#[test]
+fn test_solve_batch() {
+    let bakery: serde_json::Value =
+        serde_json::from_str(include_str!("../problems/bakery.json")).unwrap();
+    let mut with_id = bakery.clone();
+    with_id["id"] = "bakery-1".into();
+    let mut undeclared = bakery.clone();
+    undeclared["objective"]["expression"] = "3 * bagels + 2 * croissants".into();
+    let input = format!("{}\n\n{}\n{{ not json\n{}\n", with_id, undeclared, bakery);
+
+    let mut output = vec![];
+    let failed = solve_batch(input.as_bytes(), &mut output, 3).unwrap();
+    assert_eq!(failed, 2);
+
+    let items = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect::<Vec<BatchItem>>();
+    let lines = items.iter().map(|item| item.line).collect::<Vec<usize>>();
+    assert_eq!(lines, vec![1, 3, 4, 5]);
+
+    assert_eq!(items[0].id, Some("bakery-1".into()));
+    assert!((items[0].solution.as_ref().unwrap().objective - 94.75).abs() < 1e-6);
+    assert_eq!(
+        items[1].error,
+        Some(AlpsError::Parse(
+            "undeclared variable `croissants`".to_string()
+        ))
+    );
+    assert!(matches!(items[2].error, Some(AlpsError::InvalidInput(_))));
+    assert_eq!(items[3].id, None);
+    assert!(items[3].solution.is_some());
+}
+
+// sends a single request and returns the status code and the JSON body
+fn http_request(
+    address: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> (u16, serde_json::Value) {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let (_headers, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn test_server() {
+    let config = ServerConfig {
+        max_body_bytes: 2048,
+        ..ServerConfig::default()
+    };
+    let server = Server::bind("127.0.0.1:0", config).unwrap();
+    let address = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run());
+
+    let (status, body) = http_request(address, "GET", "/health", "");
+    assert_eq!((status, body["status"].as_str()), (200, Some("ok")));
+
+    let bakery = include_str!("../problems/bakery.json");
+    let (status, body) = http_request(address, "POST", "/solve", bakery);
+    assert_eq!(status, 200);
+    assert!((body["objective"].as_f64().unwrap() - 94.75).abs() < 1e-6);
+
+    let infeasible = bakery.replace("bagels >= 12", "bagels >= 1200");
+    let (status, body) = http_request(address, "POST", "/solve", &infeasible);
+    assert_eq!(
+        (status, body["error"]["kind"].as_str()),
+        (422, Some("infeasible"))
+    );
+
+    let (status, body) = http_request(address, "POST", "/solve", "{}");
+    assert_eq!(
+        (status, body["error"]["kind"].as_str()),
+        (400, Some("invalid_input"))
+    );
+
+    let (status, body) = http_request(address, "POST", "/validate", r#"{"variables": {}}"#);
+    assert_eq!((status, body["valid"].as_bool()), (200, Some(false)));
+    assert_eq!(body["issues"].as_array().unwrap().len(), 2);
+
+    let (status, body) = http_request(address, "POST", "/solve", &" ".repeat(4096));
+    assert_eq!(
+        (status, body["error"]["kind"].as_str()),
+        (413, Some("payload_too_large"))
+    );
+
+    let (status, body) = http_request(address, "POST", "/jobs", bakery);
+    assert_eq!((status, body["status"].as_str()), (202, Some("queued")));
+    let job = format!("/jobs/{}", body["id"].as_str().unwrap());
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let body = loop {
+        let (status, body) = http_request(address, "GET", &job, "");
+        assert_eq!(status, 200);
+        if body["status"] != "queued" && body["status"] != "running" {
+            break body;
+        }
+        assert!(std::time::Instant::now() < deadline);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    };
+    assert_eq!(body["status"], "succeeded");
+    assert!((body["incumbent"].as_f64().unwrap() - 94.75).abs() < 1e-6);
+    let (status, body) = http_request(address, "DELETE", &job, "");
+    assert_eq!(
+        (status, body["error"]["kind"].as_str()),
+        (409, Some("job_finished"))
+    );
+    let (status, _) = http_request(address, "GET", "/jobs/unknown", "");
+    assert_eq!(status, 404);
+
+    let (status, _) = http_request(address, "GET", "/solve", "");
+    assert_eq!(status, 405);
+    let (status, _) = http_request(address, "GET", "/nothing", "");
+    assert_eq!(status, 404);
+
+    // the time limit stops the solve itself
+    let config = ServerConfig {
+        time_limit: Some(std::time::Duration::ZERO),
+        ..ServerConfig::default()
+    };
+    let server = Server::bind("127.0.0.1:0", config).unwrap();
+    let address = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run());
+    let (status, body) = http_request(address, "POST", "/solve", bakery);
+    assert_eq!(
+        (status, body["error"]["kind"].as_str()),
+        (504, Some("stopped"))
+    );
+}
+
+#[test]
+fn test_job_queue_persistence() {
+    let directory = std::env::temp_dir().join(format!("alps-jobs-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let problem: UnoptimizedProblem = include_str!("../problems/bakery.json").into();
+
+    // without workers the jobs stay queued
+    let queue = JobQueue::new(JobsConfig {
+        workers: 0,
+        max_queued: 2,
+        directory: Some(directory.clone()),
+        ..JobsConfig::default()
+    })
+    .unwrap();
+    let first = queue.submit(problem.clone()).unwrap();
+    let second = queue.submit(problem.clone()).unwrap();
+    assert_eq!(queue.submit(problem.clone()), Err(JobError::QueueFull));
+    assert_eq!(queue.cancel(&second).unwrap().status, JobStatus::Cancelled);
+    assert_eq!(
+        queue.cancel(&second),
+        Err(JobError::Finished(JobStatus::Cancelled))
+    );
+    assert_eq!(queue.cancel("unknown"), Err(JobError::NotFound));
+    assert_eq!(queue.get(&first).unwrap().status, JobStatus::Queued);
+    drop(queue);
+
+    // a restart picks the queued job up again
+    let queue = JobQueue::new(JobsConfig {
+        workers: 1,
+        max_queued: 2,
+        directory: Some(directory.clone()),
+        ..JobsConfig::default()
+    })
+    .unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while !queue.get(&first).unwrap().status.is_finished() {
+        assert!(std::time::Instant::now() < deadline);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let job = queue.get(&first).unwrap();
+    assert_eq!(job.status, JobStatus::Succeeded);
+    assert_eq!(job.gap, Some(0.0));
+    assert!((job.incumbent.unwrap() - 94.75).abs() < 1e-6);
+    assert_eq!(queue.get(&second).unwrap().status, JobStatus::Cancelled);
+    assert_ne!(queue.submit(problem).unwrap(), first);
+    drop(queue);
+
+    // only the newest finished jobs are kept, in memory and on disk
+    let queue = JobQueue::new(JobsConfig {
+        workers: 0,
+        max_finished: 1,
+        directory: Some(directory.clone()),
+        ..JobsConfig::default()
+    })
+    .unwrap();
+    assert_eq!(queue.get(&first), None);
+    assert!(!directory.join(format!("{}.json", first)).exists());
+    assert_eq!(queue.get(&second).unwrap().status, JobStatus::Cancelled);
+
+    drop(queue);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_job_cancel() {
+    // a Klee-Minty cube, Dantzig's rule visits all 2^20 vertices
+    let n = 20;
+    let terms = |i: u32| {
+        (1..i)
+            .map(|j| format!("{} * x{} + ", 2u64.pow(i - j + 1), j))
+            .collect::<String>()
+    };
+    let constraints: Vec<String> = (1..=n)
+        .map(|i| {
+            format!(
+                r#"{{"name": "c{i}", "expression": "{}x{i} <= {}"}}"#,
+                terms(i),
+                5u64.pow(i)
+            )
+        })
+        .collect();
+    let variables: Vec<String> = (1..=n)
+        .map(|i| format!(r#""x{}": {{"min": 0}}"#, i))
+        .collect();
+    let objective: Vec<String> = (1..=n)
+        .map(|j| format!("{} * x{}", 2u64.pow(n - j), j))
+        .collect();
+    let cube = UnoptimizedProblem::from(
+        format!(
+            r#"{{"variables": {{{}}}, "objective": {{"goal": "max", "expression": "{}"}},
+                "constraints": [{}]}}"#,
+            variables.join(", "),
+            objective.join(" + "),
+            constraints.join(", ")
+        )
+        .as_str(),
+    );
+
+    // the simplex reports incumbents while it pivots
+    let queue = JobQueue::new(JobsConfig {
+        workers: 1,
+        solver: Solver::Simplex,
+        ..JobsConfig::default()
+    })
+    .unwrap();
+    let slow = queue.submit(cube).unwrap();
+    let wait = |id: &str, done: &dyn Fn(&JobView) -> bool| {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            let job = queue.get(id).unwrap();
+            if done(&job) {
+                break job;
+            }
+            assert!(std::time::Instant::now() < deadline);
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    };
+    // the incumbent moves while the job runs, the gap waits for a bound
+    let running = wait(&slow, &|job| {
+        job.incumbent.is_some_and(|incumbent| incumbent > 0.0)
+    });
+    assert_eq!(running.status, JobStatus::Running);
+    assert_eq!(running.gap, None);
+
+    // cancelling frees the only worker for the next job
+    assert_eq!(queue.cancel(&slow).unwrap().status, JobStatus::Cancelled);
+    let bakery = queue
+        .submit(include_str!("../problems/bakery.json").into())
+        .unwrap();
+    let job = wait(&bakery, &|job| job.status.is_finished());
+    assert_eq!(job.status, JobStatus::Succeeded);
+    assert_eq!(job.gap, Some(0.0));
+    assert_eq!(queue.get(&slow).unwrap().status, JobStatus::Cancelled);
+}
@@ -0,0 +1,230 @@
+use crate::{add_spaces, find_inequality, tokenize, InfixToken};
+use crate::{AlpsError, Constraint, Objective, UnoptimizedProblem, Variable};
+
+// the goals accepted as the label of the objective statement
+const GOALS: [&str; 6] = ["max", "maximize", "maximise", "min", "minimize", "minimise"];
+
+/// Reads a problem written in the `.alps` text format.
+///
+/// A model is a list of statements ending in `;`, `#` starts a comment:
+///
+/// ```text
+/// # the bakery problem
+/// max: 3 * bagels + 1.25 * doughnuts;
+/// flour: 12 * bagels + 6.5 * doughnuts <= 400;
+/// milk: bagels + 0.5 * doughnuts <= 200;
+/// bagels >= 12;
+/// var doughnuts;
+/// ```
+///
+/// * `max: ...` or `min: ...` sets the objective
+/// * `name: lhs <= rhs` adds a named constraint, the label ends at the first
+///   colon followed by whitespace so names like `GPU:0` still work
+/// * an unnamed `variable >= number` (or `<=`, `==`) sets a bound, any other
+///   unnamed constraint is called `c` followed by its position
+/// * `var a, b;` declares variables that appear in no expression
+///
+/// Variables are declared by using them, the expressions themselves are only
+/// checked when the problem is solved.
+pub fn parse_alps(source: &str) -> Result<UnoptimizedProblem, AlpsError> {
+    let mut problem = UnoptimizedProblem::default();
+    for (line, statement) in statements(source) {
+        let error = |message: String| AlpsError::Parse(format!("line {}: {}", line, message));
+
+        if let Some(names) = statement.strip_prefix("var ") {
+            for name in names.split(',').map(str::trim) {
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(error(format!("`{}` is not a variable name", name)));
+                }
+                declare(&mut problem, name);
+            }
+            continue;
+        }
+
+        let (label, expression) = match statement.find(": ").or_else(|| statement.find(":\t")) {
+            Some(index) => (
+                Some(statement[..index].trim()),
+                statement[index + 1..].trim(),
+            ),
+            None => (None, statement.as_str()),
+        };
+        if expression.is_empty() {
+            return Err(error("the statement has no expression".to_string()));
+        }
+
+        let Some(inequality) = find_inequality(expression) else {
+            match label {
+                Some(goal) if GOALS.contains(&goal.to_lowercase().as_str()) => {
+                    if !problem.objective.expression.is_empty() {
+                        return Err(error("the objective is set twice".to_string()));
+                    }
+                    for name in variable_names(expression) {
+                        declare(&mut problem, &name);
+                    }
+                    problem.objective = Objective {
+                        goal: goal.to_string(),
+                        expression: expression.to_string(),
+                    };
+                    continue;
+                }
+                _ => {
+                    return Err(error(format!(
+                        "`{}` is neither an objective nor a constraint",
+                        statement
+                    )))
+                }
+            }
+        };
+
+        let mut sides = expression.split(inequality).map(str::trim);
+        let (lhs, rhs) = (sides.next().unwrap(), sides.next().unwrap_or(""));
+        if label.is_none() {
+            if let Some((name, inequality, value)) = as_bound(lhs, inequality, rhs) {
+                let variable = declare(&mut problem, name);
+                match inequality {
+                    ">=" => variable.min = Some(value),
+                    "<=" => variable.max = Some(value),
+                    "==" => {
+                        variable.min = Some(value);
+                        variable.max = Some(value);
+                    }
+                    unsupported => {
+                        return Err(error(format!(
+                            "the bound on `{}` uses the unsupported inequality `{}`",
+                            name, unsupported
+                        )))
+                    }
+                }
+                continue;
+            }
+        }
+
+        for name in variable_names(lhs).into_iter().chain(variable_names(rhs)) {
+            declare(&mut problem, &name);
+        }
+        let name = match label {
+            Some(label) => label.to_string(),
+            None => format!("c{}", problem.constraints.len() + 1),
+        };
+        problem.constraints.push(Constraint {
+            name,
+            expression: expression.to_string(),
+        });
+    }
+    Ok(problem)
+}
+
+/// Writes a problem in the `.alps` text format read by [`parse_alps`].
+///
+/// Every variable is declared in a `var` statement ahead of the objective, in
+/// the order of the problem so that reading the text back keeps it, then
+/// each bound gets a statement.
+pub fn to_alps(problem: &UnoptimizedProblem) -> String {
+    let mut output = String::new();
+    if !problem.variables.is_empty() {
+        let names: Vec<&str> = problem.variables.keys().map(String::as_str).collect();
+        output += &format!("var {};\n", names.join(", "));
+    }
+    if !problem.objective.expression.is_empty() {
+        output += &format!(
+            "{}: {};\n",
+            problem.objective.goal, problem.objective.expression
+        );
+    }
+
+    for name in problem.variables.keys() {
+        let variable = &problem.variables[name];
+        match (variable.min, variable.max) {
+            (Some(min), Some(max)) if min == max => output += &format!("{} == {};\n", name, min),
+            (min, max) => {
+                if let Some(min) = min {
+                    output += &format!("{} >= {};\n", name, min);
+                }
+                if let Some(max) = max {
+                    output += &format!("{} <= {};\n", name, max);
+                }
+            }
+        }
+    }
+
+    for constraint in &problem.constraints {
+        output += &format!("{}: {};\n", constraint.name, constraint.expression);
+    }
+    output
+}
+
+// splits the source into trimmed statements, each with the line it starts on
+fn statements(source: &str) -> Vec<(usize, String)> {
+    let mut statements = vec![];
+    let mut current = String::new();
+    let mut start = 1;
+    for (index, line) in source.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        for (position, part) in line.split(';').enumerate() {
+            if position > 0 {
+                if !current.trim().is_empty() {
+                    statements.push((start, current.trim().to_string()));
+                }
+                current.clear();
+            }
+            if current.trim().is_empty() && !part.trim().is_empty() {
+                start = index + 1;
+            }
+            current.push_str(part);
+        }
+        current.push(' ');
+    }
+    if !current.trim().is_empty() {
+        statements.push((start, current.trim().to_string()));
+    }
+    statements
+}
+
+// the variables used by an expression, in order of appearance
+fn variable_names(expression: &str) -> Vec<String> {
+    tokenize(&add_spaces(expression))
+        .into_iter()
+        .filter_map(|token| match token {
+            InfixToken::Variable(name) if name.parse::<f64>().is_err() => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+// reads `x >= 3` or `3 <= x` as a bound on `x`, normalised to `x >= 3`
+fn as_bound<'a>(
+    lhs: &'a str,
+    inequality: &str,
+    rhs: &'a str,
+) -> Option<(&'a str, &'static str, f64)> {
+    let is_name = |side: &str| variable_names(side) == [side];
+    if is_name(lhs) {
+        return Some((lhs, flip(inequality, false), rhs.parse().ok()?));
+    }
+    if is_name(rhs) {
+        return Some((rhs, flip(inequality, true), lhs.parse().ok()?));
+    }
+    None
+}
+
+fn flip(inequality: &str, flipped: bool) -> &'static str {
+    match (inequality, flipped) {
+        ("<=", false) | (">=", true) => "<=",
+        (">=", false) | ("<=", true) => ">=",
+        ("==", _) => "==",
+        ("<", false) | (">", true) => "<",
+        _ => ">",
+    }
+}
+
+// adds a variable without bounds unless it is already declared
+fn declare<'a>(problem: &'a mut UnoptimizedProblem, name: &str) -> &'a mut Variable {
+    problem
+        .variables
+        .entry(name.to_string())
+        .or_insert_with(|| Variable {
+            name: name.to_string(),
+            min: None,
+            max: None,
+        })
+}
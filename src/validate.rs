@@ -0,0 +1,311 @@
+use crate::{add_spaces, find_inequality, infix_to_postfix, tokenize, InfixToken};
+use crate::{SolutionResponse, UnoptimizedProblem};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The JSON Schema of the problem input format.
+pub fn problem_schema() -> Value {
+    serde_json::to_value(schemars::schema_for!(UnoptimizedProblem)).unwrap()
+}
+
+/// The JSON Schema of the solution output format.
+pub fn solution_schema() -> Value {
+    serde_json::to_value(schemars::schema_for!(SolutionResponse)).unwrap()
+}
+
+/// A single problem found by [`validate`], located by a JSON pointer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}: {}", pointer, self.message)
+    }
+}
+
+// collects issues while walking the document
+#[derive(Default)]
+struct Issues(Vec<ValidationIssue>);
+
+impl Issues {
+    fn push(&mut self, pointer: &str, message: impl Into<String>) {
+        self.0.push(ValidationIssue {
+            pointer: pointer.to_string(),
+            message: message.into(),
+        });
+    }
+}
+
+// escapes a key for use inside a JSON pointer (RFC 6901)
+fn pointer_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+// checks that `value` is an object with only the `allowed` keys and all of
+// the `required` ones
+fn check_object<'a>(
+    value: &'a Value,
+    pointer: &str,
+    allowed: &[&str],
+    required: &[&str],
+    issues: &mut Issues,
+) -> Option<&'a Map<String, Value>> {
+    let Some(object) = value.as_object() else {
+        issues.push(
+            pointer,
+            format!("expected an object, found {}", type_name(value)),
+        );
+        return None;
+    };
+    for key in object.keys() {
+        if !allowed.contains(&key.as_str()) {
+            let pointer = format!("{}/{}", pointer, pointer_segment(key));
+            issues.push(&pointer, format!("unknown field `{}`", key));
+        }
+    }
+    for key in required {
+        if !object.contains_key(*key) {
+            issues.push(pointer, format!("missing field `{}`", key));
+        }
+    }
+    Some(object)
+}
+
+fn check_string<'a>(
+    object: &'a Map<String, Value>,
+    key: &str,
+    pointer: &str,
+    issues: &mut Issues,
+) -> Option<&'a str> {
+    let value = object.get(key)?;
+    let pointer = format!("{}/{}", pointer, key);
+    match value.as_str() {
+        Some(s) => Some(s),
+        None => {
+            issues.push(
+                &pointer,
+                format!("expected a string, found {}", type_name(value)),
+            );
+            None
+        }
+    }
+}
+
+fn check_bound(
+    object: &Map<String, Value>,
+    key: &str,
+    pointer: &str,
+    issues: &mut Issues,
+) -> Option<f64> {
+    let value = object.get(key)?;
+    if value.is_null() {
+        return None;
+    }
+    let pointer = format!("{}/{}", pointer, key);
+    match value.as_f64() {
+        Some(bound) => Some(bound),
+        None => {
+            issues.push(
+                &pointer,
+                format!("expected a number, found {}", type_name(value)),
+            );
+            None
+        }
+    }
+}
+
+// checks a single side of an expression, reporting undeclared variables
+fn check_expression(
+    expression: &str,
+    pointer: &str,
+    declared: &HashSet<String>,
+    issues: &mut Issues,
+) {
+    let tokens = tokenize(&add_spaces(expression));
+    if infix_to_postfix(&tokens).is_none() {
+        issues.push(
+            pointer,
+            format!("`{}` is not a valid expression", expression.trim()),
+        );
+        return;
+    }
+    for token in tokens {
+        if let InfixToken::Variable(name) = token {
+            if name.parse::<f64>().is_err() && !declared.contains(&name) {
+                issues.push(pointer, format!("undeclared variable `{}`", name));
+            }
+        }
+    }
+}
+
+/// Checks a problem document against the input format, reporting every
+/// issue found instead of stopping at the first one.
+///
+/// Structural checks cover unknown and missing fields, value types, variable
+/// names that disagree with their key, `min` above `max` and duplicate
+/// constraint names. Semantic checks parse every expression and report
+/// undeclared variables.
+pub fn validate(document: &Value) -> Vec<ValidationIssue> {
+    let mut issues = Issues::default();
+    let Some(root) = check_object(
+        document,
+        "",
+        &["variables", "objective", "constraints"],
+        &["variables", "objective", "constraints"],
+        &mut issues,
+    ) else {
+        return issues.0;
+    };
+
+    let mut declared = HashSet::new();
+    if let Some(variables) = root.get("variables") {
+        match variables.as_object() {
+            Some(variables) => {
+                for (key, variable) in variables {
+                    declared.insert(key.clone());
+                    let pointer = format!("/variables/{}", pointer_segment(key));
+                    let Some(variable) = check_object(
+                        variable,
+                        &pointer,
+                        &["name", "min", "max"],
+                        &[],
+                        &mut issues,
+                    ) else {
+                        continue;
+                    };
+                    if let Some(name) = check_string(variable, "name", &pointer, &mut issues) {
+                        if name != key {
+                            issues.push(
+                                &format!("{}/name", pointer),
+                                format!("name `{}` does not match its key `{}`", name, key),
+                            );
+                        }
+                    }
+                    let min = check_bound(variable, "min", &pointer, &mut issues);
+                    let max = check_bound(variable, "max", &pointer, &mut issues);
+                    if let (Some(min), Some(max)) = (min, max) {
+                        if min > max {
+                            issues
+                                .push(&pointer, format!("min {} is greater than max {}", min, max));
+                        }
+                    }
+                }
+            }
+            None => issues.push(
+                "/variables",
+                format!("expected an object, found {}", type_name(variables)),
+            ),
+        }
+    }
+
+    if let Some(objective) = root.get("objective") {
+        if let Some(objective) = check_object(
+            objective,
+            "/objective",
+            &["goal", "expression"],
+            &["goal", "expression"],
+            &mut issues,
+        ) {
+            if let Some(goal) = check_string(objective, "goal", "/objective", &mut issues) {
+                let known = ["max", "maximize", "maximise", "min", "minimize", "minimise"];
+                if !known.contains(&goal.trim().to_lowercase().as_str()) {
+                    issues.push(
+                        "/objective/goal",
+                        format!("unknown goal `{}`, expected `max` or `min`", goal),
+                    );
+                }
+            }
+            if let Some(expression) =
+                check_string(objective, "expression", "/objective", &mut issues)
+            {
+                check_expression(expression, "/objective/expression", &declared, &mut issues);
+            }
+        }
+    }
+
+    if let Some(constraints) = root.get("constraints") {
+        match constraints.as_array() {
+            Some(constraints) => {
+                let mut seen: HashMap<&str, usize> = HashMap::new();
+                for (index, constraint) in constraints.iter().enumerate() {
+                    let pointer = format!("/constraints/{}", index);
+                    let Some(constraint) = check_object(
+                        constraint,
+                        &pointer,
+                        &["name", "expression"],
+                        &["name", "expression"],
+                        &mut issues,
+                    ) else {
+                        continue;
+                    };
+                    if let Some(name) = check_string(constraint, "name", &pointer, &mut issues) {
+                        if let Some(first) = seen.insert(name, index) {
+                            issues.push(
+                                &format!("{}/name", pointer),
+                                format!(
+                                    "duplicate constraint name `{}`, first used by /constraints/{}",
+                                    name, first
+                                ),
+                            );
+                        }
+                    }
+                    let Some(expression) =
+                        check_string(constraint, "expression", &pointer, &mut issues)
+                    else {
+                        continue;
+                    };
+                    let pointer = format!("{}/expression", pointer);
+                    match find_inequality(expression) {
+                        Some(inequality @ ("<=" | ">=" | "==")) => {
+                            for side in expression.splitn(2, inequality) {
+                                check_expression(side, &pointer, &declared, &mut issues);
+                            }
+                        }
+                        Some(inequality) => issues
+                            .push(&pointer, format!("unsupported inequality `{}`", inequality)),
+                        None => issues.push(&pointer, "constraint does not contain an inequality"),
+                    }
+                }
+            }
+            None => issues.push(
+                "/constraints",
+                format!("expected an array, found {}", type_name(constraints)),
+            ),
+        }
+    }
+
+    issues.0
+}
+
+/// Parses and validates a problem document, a parse failure is reported as
+/// a single issue at the root.
+pub fn validate_str(json: &str) -> Vec<ValidationIssue> {
+    match serde_json::from_str::<Value>(json) {
+        Ok(document) => validate(&document),
+        Err(error) => vec![ValidationIssue {
+            pointer: String::new(),
+            message: format!("invalid JSON: {}", error),
+        }],
+    }
+}
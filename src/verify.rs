@@ -0,0 +1,398 @@
+use crate::stats::short;
+use crate::{AlpsError, Sense, UnoptimizedProblem};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// How much [`verify`] lets an assignment miss by.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct VerifyOptions {
+    /// Violations up to this much are accepted
+    pub absolute: f64,
+    /// On top of `absolute`, this share of the larger side of a constraint
+    /// or of the bound is accepted
+    pub relative: f64,
+    /// Variables that must take whole values
+    pub integers: Vec<String>,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        VerifyOptions {
+            absolute: 1e-6,
+            relative: 1e-9,
+            integers: vec![],
+        }
+    }
+}
+
+impl VerifyOptions {
+    pub(crate) fn tolerance(&self, size: f64) -> f64 {
+        self.absolute + self.relative * size.abs()
+    }
+}
+
+/// Both sides of a constraint under an assignment and how far apart they
+/// are in the wrong direction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConstraintCheck {
+    pub name: String,
+    pub expression: String,
+    pub lhs: f64,
+    pub rhs: f64,
+    /// Zero when the constraint holds exactly
+    pub violation: f64,
+    /// Whether the violation is within the tolerances
+    pub satisfied: bool,
+}
+
+/// A variable outside its bounds or away from a whole value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VariableViolation {
+    pub name: String,
+    pub value: f64,
+    /// The bound or whole number it should be at
+    pub limit: f64,
+    pub violation: f64,
+}
+
+/// What [`verify`] found.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VerificationReport {
+    /// Whether every constraint, bound and integrality holds
+    pub feasible: bool,
+    /// The largest violation of any constraint, bound or integrality
+    pub max_violation: f64,
+    /// The objective under the assignment
+    pub objective: f64,
+    pub constraints: Vec<ConstraintCheck>,
+    pub bounds: Vec<VariableViolation>,
+    pub integrality: Vec<VariableViolation>,
+    /// Declared variables without a value, taken as zero
+    pub missing: Vec<String>,
+    /// Values for variables the problem doesn't declare, ignored
+    pub unknown: Vec<String>,
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}, max violation {}, objective {}",
+            if self.feasible {
+                "feasible"
+            } else {
+                "infeasible"
+            },
+            short(self.max_violation),
+            short(self.objective)
+        )?;
+        for check in self.constraints.iter().filter(|check| !check.satisfied) {
+            writeln!(
+                f,
+                "constraint `{}` is violated by {}: {} against {}",
+                check.name,
+                short(check.violation),
+                short(check.lhs),
+                short(check.rhs)
+            )?;
+        }
+        for violation in &self.bounds {
+            writeln!(
+                f,
+                "variable `{}` is {}, outside its bound {}",
+                violation.name,
+                short(violation.value),
+                short(violation.limit)
+            )?;
+        }
+        for violation in &self.integrality {
+            writeln!(
+                f,
+                "variable `{}` is {}, not a whole number",
+                violation.name,
+                short(violation.value)
+            )?;
+        }
+        for name in &self.missing {
+            writeln!(f, "variable `{}` has no value, taken as zero", name)?;
+        }
+        for name in &self.unknown {
+            writeln!(f, "value for unknown variable `{}` ignored", name)?;
+        }
+        Ok(())
+    }
+}
+
+// a number keeps its literal so it can also be read exactly
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Number(String),
+    Name(String),
+    Operator(char),
+    Open,
+    Close,
+}
+
+pub(crate) fn lex(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut word = String::new();
+    let flush = |word: &mut String, tokens: &mut Vec<Token>| {
+        if !word.is_empty() {
+            // `inf` and `nan` parse as numbers but are names here
+            let numeric = word.starts_with(|c: char| c.is_ascii_digit() || c == '.');
+            tokens.push(if numeric && word.parse::<f64>().is_ok() {
+                Token::Number(word.clone())
+            } else {
+                Token::Name(word.clone())
+            });
+            word.clear();
+        }
+    };
+    for c in text.chars() {
+        // the sign of an exponent, as in `1e-6`
+        let exponent = word.len() > 1
+            && (word.ends_with('e') || word.ends_with('E'))
+            && word[..word.len() - 1].parse::<f64>().is_ok();
+        match c {
+            '+' | '-' if exponent => word.push(c),
+            '+' | '-' | '*' | '/' | '(' | ')' => {
+                flush(&mut word, &mut tokens);
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Operator(c),
+                });
+            }
+            c if c.is_whitespace() => flush(&mut word, &mut tokens),
+            c => word.push(c),
+        }
+    }
+    flush(&mut word, &mut tokens);
+    tokens
+}
+
+// evaluates + - * / and parentheses with the usual precedence, on its own
+// so it doesn't share mistakes with the parser that feeds the solver
+struct Evaluator<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    values: &'a HashMap<String, f64>,
+}
+
+impl Evaluator<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        self.position += 1;
+        self.tokens.get(self.position - 1).cloned()
+    }
+
+    fn sum(&mut self) -> Option<f64> {
+        let mut value = self.product()?;
+        while let Some(Token::Operator(operator @ ('+' | '-'))) = self.peek().cloned() {
+            self.position += 1;
+            let right = self.product()?;
+            value = if operator == '+' {
+                value + right
+            } else {
+                value - right
+            };
+        }
+        Some(value)
+    }
+
+    fn product(&mut self) -> Option<f64> {
+        let mut value = self.factor()?;
+        while let Some(Token::Operator(operator @ ('*' | '/'))) = self.peek().cloned() {
+            self.position += 1;
+            let right = self.factor()?;
+            value = if operator == '*' {
+                value * right
+            } else {
+                value / right
+            };
+        }
+        Some(value)
+    }
+
+    fn factor(&mut self) -> Option<f64> {
+        match self.next()? {
+            Token::Operator('-') => Some(-self.factor()?),
+            Token::Operator('+') => self.factor(),
+            Token::Number(number) => number.parse().ok(),
+            Token::Name(name) => Some(self.values.get(&name).copied().unwrap_or(0.0)),
+            Token::Open => {
+                let value = self.sum()?;
+                (self.next()? == Token::Close).then_some(value)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn evaluate(text: &str, values: &HashMap<String, f64>) -> Option<f64> {
+    let mut evaluator = Evaluator {
+        tokens: lex(text),
+        position: 0,
+        values,
+    };
+    let value = evaluator.sum()?;
+    (evaluator.position == evaluator.tokens.len()).then_some(value)
+}
+
+// splits a constraint at its comparison operator
+fn split(expression: &str) -> Option<(&str, Sense, &str)> {
+    ["<=", ">=", "=="].into_iter().find_map(|operator| {
+        let (lhs, rhs) = expression.split_once(operator)?;
+        Some((lhs, Sense::from_operator(operator)?, rhs))
+    })
+}
+
+/// Checks an assignment against a problem: every constraint, every bound
+/// and the whole values of `options.integers`. The expressions are
+/// evaluated by a parser of their own, not the one that builds the model
+/// for the solver, so it can catch mistakes of either.
+pub fn verify(
+    problem: &UnoptimizedProblem,
+    values: &HashMap<String, f64>,
+    options: &VerifyOptions,
+) -> Result<VerificationReport, AlpsError> {
+    let mut max_violation: f64 = 0.0;
+    let mut constraints = vec![];
+    for constraint in &problem.constraints {
+        let invalid = || {
+            AlpsError::Parse(format!(
+                "constraint `{}` is not a valid expression",
+                constraint.name
+            ))
+        };
+        let (lhs, sense, rhs) = split(&constraint.expression).ok_or_else(invalid)?;
+        let lhs = evaluate(lhs, values).ok_or_else(invalid)?;
+        let rhs = evaluate(rhs, values).ok_or_else(invalid)?;
+        let violation = match sense {
+            Sense::Le => (lhs - rhs).max(0.0),
+            Sense::Ge => (rhs - lhs).max(0.0),
+            Sense::Eq => (lhs - rhs).abs(),
+        };
+        max_violation = max_violation.max(violation);
+        constraints.push(ConstraintCheck {
+            name: constraint.name.clone(),
+            expression: constraint.expression.clone(),
+            lhs,
+            rhs,
+            violation,
+            satisfied: violation <= options.tolerance(lhs.abs().max(rhs.abs())),
+        });
+    }
+
+    let mut feasible = constraints.iter().all(|check| check.satisfied);
+    let mut bounds = vec![];
+    let mut integrality = vec![];
+    let mut missing = vec![];
+    for (name, variable) in &problem.variables {
+        let Some(value) = values.get(name).copied() else {
+            missing.push(name.clone());
+            continue;
+        };
+        let below = variable.min.map(|min| (min, min - value));
+        let above = variable.max.map(|max| (max, value - max));
+        for (limit, violation) in below.into_iter().chain(above) {
+            if violation > 0.0 {
+                max_violation = max_violation.max(violation);
+                if violation > options.tolerance(limit) {
+                    feasible = false;
+                    bounds.push(VariableViolation {
+                        name: name.clone(),
+                        value,
+                        limit,
+                        violation,
+                    });
+                }
+            }
+        }
+    }
+    for name in &options.integers {
+        let value = values.get(name).copied().unwrap_or(0.0);
+        let violation = (value - value.round()).abs();
+        max_violation = max_violation.max(violation);
+        if violation > options.tolerance(value) {
+            feasible = false;
+            integrality.push(VariableViolation {
+                name: name.clone(),
+                value,
+                limit: value.round(),
+                violation,
+            });
+        }
+    }
+    let mut unknown: Vec<String> = values
+        .keys()
+        .filter(|name| !problem.variables.contains_key(*name))
+        .cloned()
+        .collect();
+    unknown.sort();
+
+    let objective = evaluate(&problem.objective.expression, values)
+        .ok_or_else(|| AlpsError::Parse("the objective is not a valid expression".to_string()))?;
+    Ok(VerificationReport {
+        feasible,
+        max_violation,
+        objective,
+        constraints,
+        bounds,
+        integrality,
+        missing,
+        unknown,
+    })
+}
+
+/// Reads variable values from a solution as `alps solve` prints it, a JSON
+/// object of numbers, or `name,value` and `name = value` lines such as the
+/// variables section of `--format csv`.
+pub fn parse_assignment(text: &str) -> Result<HashMap<String, f64>, AlpsError> {
+    if text.trim_start().starts_with('{') {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| AlpsError::InvalidInput(e.to_string()))?;
+        let values = value.get("variable_solutions").unwrap_or(&value);
+        return serde_json::from_value(values.clone())
+            .map_err(|e| AlpsError::InvalidInput(e.to_string()));
+    }
+    let mut values = HashMap::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        // a blank line ends the variables section of the csv export
+        if line.is_empty() {
+            if values.is_empty() {
+                continue;
+            }
+            break;
+        }
+        let Some((name, rest)) = line.split_once(',').or_else(|| line.split_once('=')) else {
+            return Err(AlpsError::InvalidInput(format!(
+                "line {}: expected `name,value` or `name = value`",
+                number + 1
+            )));
+        };
+        let value = rest.split(',').next().unwrap_or_default().trim();
+        match value.parse() {
+            Ok(value) => {
+                values.insert(name.trim().to_string(), value);
+            }
+            // the header of the csv export
+            Err(_) if values.is_empty() => {}
+            Err(_) => {
+                return Err(AlpsError::InvalidInput(format!(
+                    "line {}: `{}` is not a number",
+                    number + 1,
+                    value
+                )))
+            }
+        }
+    }
+    Ok(values)
+}
@@ -0,0 +1,171 @@
+use crate::{parse_assignment, AlpsError};
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where a variable sits in a simplex basis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableStatus {
+    /// In the basis, usually strictly between its bounds
+    Basic,
+    /// Out of the basis at its lower bound, or at zero when it has none
+    Lower,
+    /// Out of the basis at its upper bound
+    Upper,
+}
+
+/// The basis a simplex solve ended in, saved with the solution so the next
+/// solve of a similar model can start from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Basis {
+    pub variables: IndexMap<String, VariableStatus>,
+    /// Constraints whose slack is basic, the ones that aren't binding
+    pub slack: Vec<String>,
+    /// Pivots the solve took, or for one warm started from a basis the
+    /// pivots its cold start took, so savings are against a cold start
+    pub iterations: usize,
+}
+
+/// A starting point for [`SolveOptions::warm_start`](crate::SolveOptions::warm_start).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmStart {
+    /// Variable values, such as the `variable_solutions` of an earlier
+    /// solution, turned into a basis by where they sit against the bounds
+    /// and constraints
+    Values(HashMap<String, f64>),
+    Basis(Basis),
+}
+
+impl WarmStart {
+    /// Reads a starting point from a solution as `alps solve` prints it,
+    /// using its basis when it has one, a basis on its own, or values in
+    /// any form [`parse_assignment`] takes.
+    pub fn parse(text: &str) -> Result<WarmStart, AlpsError> {
+        if text.trim_start().starts_with('{') {
+            let value: serde_json::Value =
+                serde_json::from_str(text).map_err(|e| AlpsError::InvalidInput(e.to_string()))?;
+            let basis = value.get("basis").unwrap_or(&value);
+            if let Ok(basis) = serde_json::from_value::<Basis>(basis.clone()) {
+                return Ok(WarmStart::Basis(basis));
+            }
+        }
+        parse_assignment(text).map(WarmStart::Values)
+    }
+}
+
+/// What became of a warm start.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WarmStartReport {
+    /// Whether the solve started from it, otherwise it started from scratch
+    pub accepted: bool,
+    /// Why it wasn't used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Pivots this solve took
+    pub iterations: usize,
+    /// Pivots saved against solving from scratch, known when starting from
+    /// a basis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved: Option<i64>,
+}
+
+impl WarmStartReport {
+    pub(crate) fn rejected(reason: &str) -> WarmStartReport {
+        WarmStartReport {
+            accepted: false,
+            reason: Some(reason.to_string()),
+            iterations: 0,
+            saved: None,
+        }
+    }
+}
+
+// a warm start laid out like the columns and rows of the problem
+pub(crate) enum Start<T> {
+    Values(Vec<Option<T>>),
+    Statuses {
+        variables: Vec<VariableStatus>,
+        slack: Vec<bool>,
+    },
+}
+
+impl WarmStart {
+    pub(crate) fn start<T>(
+        &self,
+        variables: &[String],
+        constraints: &[String],
+        value: impl Fn(f64) -> Option<T>,
+    ) -> Start<T> {
+        match self {
+            WarmStart::Values(values) => Start::Values(
+                variables
+                    .iter()
+                    .map(|name| values.get(name).copied().and_then(&value))
+                    .collect(),
+            ),
+            WarmStart::Basis(basis) => Start::Statuses {
+                variables: variables
+                    .iter()
+                    .map(|name| {
+                        let status = basis.variables.get(name).copied();
+                        status.unwrap_or(VariableStatus::Lower)
+                    })
+                    .collect(),
+                slack: constraints
+                    .iter()
+                    .map(|name| basis.slack.contains(name))
+                    .collect(),
+            },
+        }
+    }
+
+    // the report for a solve that started from this, `rejected` saying why
+    // it didn't
+    pub(crate) fn report(&self, iterations: usize, rejected: Option<String>) -> WarmStartReport {
+        let cold = match self {
+            WarmStart::Basis(basis) if rejected.is_none() => Some(basis.iterations),
+            _ => None,
+        };
+        WarmStartReport {
+            accepted: rejected.is_none(),
+            reason: rejected,
+            iterations,
+            saved: cold.map(|cold| cold as i64 - iterations as i64),
+        }
+    }
+
+    // the iterations a cold start would have taken, to carry over
+    pub(crate) fn baseline(&self, iterations: usize, accepted: bool) -> usize {
+        match self {
+            WarmStart::Basis(basis) if accepted => basis.iterations,
+            _ => iterations,
+        }
+    }
+}
+
+// the basis of a solve, `statuses` and `slack` as the simplex reports them
+pub(crate) fn basis(
+    variables: &[String],
+    constraints: &[String],
+    statuses: &[VariableStatus],
+    slack: &[bool],
+    iterations: usize,
+) -> Basis {
+    Basis {
+        variables: variables
+            .iter()
+            .cloned()
+            .zip(statuses.iter().copied())
+            .collect(),
+        slack: constraints
+            .iter()
+            .zip(slack)
+            .filter(|(_, basic)| **basic)
+            .map(|(name, _)| name.clone())
+            .collect(),
+        iterations,
+    }
+}